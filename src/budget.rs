@@ -0,0 +1,449 @@
+//! Contains `TimeBudget` type to limit time spent on work.
+
+use core::{num::NonZeroU32, ops::ControlFlow};
+
+use crate::{span::TimeSpan, stamp::TimeStamp};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use crate::freq::Frequency;
+
+#[cfg(feature = "std")]
+use crate::clock::Clock;
+
+/// Limits amount of time spent on work.
+///
+/// Time is provided explicitly as "now" time stamps,
+/// so it works with any time source.
+/// See [`ClockBudget`] for a variant that measures time itself.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeBudget {
+    start: TimeStamp,
+    limit: TimeSpan,
+    granularity: NonZeroU32,
+}
+
+impl TimeBudget {
+    /// Returns new budget of `limit` time starting at `now`.
+    ///
+    /// Time is checked on every iteration of [`TimeBudget::run_while`].
+    #[inline(always)]
+    pub fn new(limit: TimeSpan, now: TimeStamp) -> Self {
+        TimeBudget {
+            start: now,
+            limit,
+            granularity: NonZeroU32::MIN,
+        }
+    }
+
+    /// Sets how often [`TimeBudget::run_while`] checks time, in iterations.
+    ///
+    /// Larger values reduce overhead of querying time source
+    /// at the cost of overshooting the budget by up to `granularity` iterations.
+    #[inline(always)]
+    pub fn with_granularity(mut self, granularity: NonZeroU32) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Returns time budget limit.
+    #[inline(always)]
+    pub fn limit(&self) -> TimeSpan {
+        self.limit
+    }
+
+    /// Returns time stamp when budget started.
+    #[inline(always)]
+    pub fn start(&self) -> TimeStamp {
+        self.start
+    }
+
+    /// Returns time remaining in the budget at `now`.
+    #[inline(always)]
+    pub fn remaining(&self, now: TimeStamp) -> TimeSpan {
+        self.limit.saturating_sub(self.elapsed(now))
+    }
+
+    /// Returns `true` if budget is exhausted at `now`.
+    #[inline(always)]
+    pub fn expired(&self, now: TimeStamp) -> bool {
+        self.elapsed(now) >= self.limit
+    }
+
+    /// Repeatedly calls `f` until it breaks or budget expires.
+    ///
+    /// `now` is called every `granularity` iterations to check the budget,
+    /// starting before the first iteration.
+    ///
+    /// Returns `true` if `f` signaled that work is done
+    /// and `false` if budget expired first.
+    pub fn run_while(
+        &self,
+        mut now: impl FnMut() -> TimeStamp,
+        mut f: impl FnMut() -> ControlFlow<()>,
+    ) -> bool {
+        let mut countdown = 0;
+        loop {
+            if countdown == 0 {
+                if self.expired(now()) {
+                    return false;
+                }
+                countdown = self.granularity.get();
+            }
+            countdown -= 1;
+
+            if f().is_break() {
+                return true;
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn elapsed(&self, now: TimeStamp) -> TimeSpan {
+        now.checked_elapsed_since(self.start)
+            .unwrap_or(TimeSpan::ZERO)
+    }
+}
+
+/// Limits amount of time spent on work measuring time with own [`Clock`].
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct ClockBudget {
+    clock: Clock,
+    budget: TimeBudget,
+}
+
+#[cfg(feature = "std")]
+impl ClockBudget {
+    /// Returns new budget of `limit` time starting now.
+    pub fn new(limit: TimeSpan) -> Self {
+        let clock = Clock::new();
+        let budget = TimeBudget::new(limit, clock.now());
+        ClockBudget { clock, budget }
+    }
+
+    /// Sets how often [`ClockBudget::run_while`] checks time, in iterations.
+    pub fn with_granularity(mut self, granularity: NonZeroU32) -> Self {
+        self.budget = self.budget.with_granularity(granularity);
+        self
+    }
+
+    /// Returns time budget limit.
+    pub fn limit(&self) -> TimeSpan {
+        self.budget.limit()
+    }
+
+    /// Returns time remaining in the budget.
+    pub fn remaining(&self) -> TimeSpan {
+        self.budget.remaining(self.clock.current())
+    }
+
+    /// Returns `true` if budget is exhausted.
+    pub fn expired(&self) -> bool {
+        self.budget.expired(self.clock.current())
+    }
+
+    /// Repeatedly calls `f` until it breaks or budget expires.
+    ///
+    /// Returns `true` if `f` signaled that work is done
+    /// and `false` if budget expired first.
+    pub fn run_while(&self, f: impl FnMut() -> ControlFlow<()>) -> bool {
+        self.budget.run_while(|| self.clock.current(), f)
+    }
+}
+
+/// Named part of the [`FrameBudget`] with statistics of measured time.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubBudget {
+    name: &'static str,
+    budget: TimeSpan,
+    last: Option<TimeSpan>,
+    overruns: u64,
+    worst_overrun: TimeSpan,
+}
+
+#[cfg(feature = "alloc")]
+impl SubBudget {
+    /// Returns name of the sub-budget.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns time allocated to the sub-budget.
+    pub fn budget(&self) -> TimeSpan {
+        self.budget
+    }
+
+    /// Returns last measured time.
+    pub fn last(&self) -> Option<TimeSpan> {
+        self.last
+    }
+
+    /// Returns number of measurements that exceeded the budget.
+    pub fn overruns(&self) -> u64 {
+        self.overruns
+    }
+
+    /// Returns largest excess over the budget measured.
+    pub fn worst_overrun(&self) -> TimeSpan {
+        self.worst_overrun
+    }
+}
+
+/// Frame time split into named sub-budgets,
+/// e.g. 30% of a 60 Hz frame for physics.
+///
+/// Total of allocated sub-budgets never exceeds the frame time.
+/// Measured times are checked against sub-budgets
+/// and overruns are counted for summary reporting via `Display`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct FrameBudget {
+    frame: TimeSpan,
+    allocated: TimeSpan,
+    subs: Vec<SubBudget>,
+}
+
+#[cfg(feature = "alloc")]
+impl FrameBudget {
+    /// Returns new budget with given frame time and no sub-budgets.
+    pub fn new(frame: TimeSpan) -> Self {
+        FrameBudget {
+            frame,
+            allocated: TimeSpan::ZERO,
+            subs: Vec::new(),
+        }
+    }
+
+    /// Returns new budget with frame time of given frequency.
+    /// Returns `None` for zero frequency.
+    pub fn from_frequency(freq: Frequency) -> Option<Self> {
+        Some(FrameBudget::new(freq.frame_time()?))
+    }
+
+    /// Returns frame time.
+    pub fn frame_time(&self) -> TimeSpan {
+        self.frame
+    }
+
+    /// Returns part of the frame time not allocated to sub-budgets.
+    pub fn unallocated(&self) -> TimeSpan {
+        self.frame - self.allocated
+    }
+
+    /// Allocates `fraction` of the frame time to sub-budget with given name
+    /// and returns allocated time, rounded down to the nanosecond.
+    ///
+    /// Returns `None` if `fraction` is not in `[0, 1]`,
+    /// name is already allocated or there is not enough unallocated time.
+    pub fn allocate(&mut self, name: &'static str, fraction: f32) -> Option<TimeSpan> {
+        if !(0.0..=1.0).contains(&fraction) || self.get(name).is_some() {
+            return None;
+        }
+
+        let budget = TimeSpan::new((self.frame.as_nanos() as f64 * fraction as f64) as u64);
+        if budget > self.unallocated() {
+            return None;
+        }
+
+        self.allocated += budget;
+        self.subs.push(SubBudget {
+            name,
+            budget,
+            last: None,
+            overruns: 0,
+            worst_overrun: TimeSpan::ZERO,
+        });
+        Some(budget)
+    }
+
+    /// Returns sub-budget with given name.
+    pub fn get(&self, name: &str) -> Option<&SubBudget> {
+        self.subs.iter().find(|sub| sub.name == name)
+    }
+
+    /// Records time measured for sub-budget with given name
+    /// and returns how much it exceeded the budget.
+    /// Returns `None` if measured time is within the budget.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no sub-budget with given name.
+    pub fn check(&mut self, name: &str, measured: TimeSpan) -> Option<TimeSpan> {
+        let sub = self
+            .subs
+            .iter_mut()
+            .find(|sub| sub.name == name)
+            .expect("no sub-budget with given name");
+
+        sub.last = Some(measured);
+        let over = measured.over_budget(sub.budget);
+        if let Some(over) = over {
+            sub.overruns += 1;
+            sub.worst_overrun = sub.worst_overrun.max(over);
+        }
+        over
+    }
+
+    /// Returns iterator over sub-budgets in order of allocation.
+    pub fn sub_budgets(&self) -> impl ExactSizeIterator<Item = &SubBudget> + '_ {
+        self.subs.iter()
+    }
+}
+
+/// Displays one line per sub-budget with last measured time and overruns.
+#[cfg(feature = "alloc")]
+impl fmt::Display for FrameBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "frame {}, unallocated {}",
+            self.frame,
+            self.unallocated()
+        )?;
+
+        for sub in &self.subs {
+            write!(f, "{}: ", sub.name)?;
+            match sub.last {
+                None => write!(f, "- / {}", sub.budget)?,
+                Some(last) => write!(f, "{} / {}", last, sub.budget)?,
+            }
+            if sub.overruns > 0 {
+                write!(
+                    f,
+                    ", {} overruns, worst +{}",
+                    sub.overruns, sub.worst_overrun
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_budget_remaining() {
+    let start = TimeStamp::start() + TimeSpan::SECOND;
+    let budget = TimeBudget::new(TimeSpan::MILLISECOND * 10, start);
+
+    assert_eq!(budget.remaining(start), TimeSpan::MILLISECOND * 10);
+    assert_eq!(
+        budget.remaining(start + TimeSpan::MILLISECOND * 4),
+        TimeSpan::MILLISECOND * 6
+    );
+    assert!(!budget.expired(start + TimeSpan::MILLISECOND * 9));
+    assert!(budget.expired(start + TimeSpan::MILLISECOND * 10));
+    assert_eq!(budget.remaining(start + TimeSpan::SECOND), TimeSpan::ZERO);
+
+    // Time before start does not count.
+    assert_eq!(
+        budget.remaining(TimeStamp::start()),
+        TimeSpan::MILLISECOND * 10
+    );
+}
+
+#[test]
+fn test_budget_run_while() {
+    use core::cell::Cell;
+
+    let now = Cell::new(TimeStamp::start());
+    let budget = TimeBudget::new(TimeSpan::MILLISECOND * 10, now.get())
+        .with_granularity(NonZeroU32::new(3).unwrap());
+
+    let mut iterations = 0;
+    let mut queries = 0;
+    let done = budget.run_while(
+        || {
+            queries += 1;
+            now.get()
+        },
+        || {
+            iterations += 1;
+            now.set(now.get() + TimeSpan::MILLISECOND);
+            ControlFlow::Continue(())
+        },
+    );
+
+    assert!(!done);
+    assert!((10..13).contains(&iterations));
+    assert_eq!(queries, 5);
+
+    let mut iterations = 0;
+    let done =
+        TimeBudget::new(TimeSpan::SECOND, TimeStamp::start()).run_while(TimeStamp::start, || {
+            iterations += 1;
+            if iterations == 5 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+    assert!(done);
+    assert_eq!(iterations, 5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_clock_budget() {
+    let budget = ClockBudget::new(TimeSpan::MILLISECOND * 5);
+    assert!(budget.remaining() <= TimeSpan::MILLISECOND * 5);
+
+    let done = budget.run_while(|| {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        ControlFlow::Continue(())
+    });
+    assert!(!done);
+    assert!(budget.expired());
+    assert_eq!(budget.remaining(), TimeSpan::ZERO);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_frame_budget() {
+    let mut budget = FrameBudget::from_frequency(Frequency::from_hz(50)).unwrap();
+    assert_eq!(budget.frame_time(), TimeSpan::MILLISECOND * 20);
+
+    assert_eq!(
+        budget.allocate("physics", 0.3),
+        Some(TimeSpan::MILLISECOND * 6)
+    );
+    assert_eq!(
+        budget.allocate("render", 0.5),
+        Some(TimeSpan::MILLISECOND * 10)
+    );
+    assert_eq!(budget.allocate("physics", 0.1), None);
+    assert_eq!(budget.allocate("audio", 1.5), None);
+
+    // Allocations can't exceed 100%.
+    assert_eq!(budget.allocate("ai", 0.25), None);
+    assert_eq!(budget.allocate("ai", 0.2), Some(TimeSpan::MILLISECOND * 4));
+    assert_eq!(budget.unallocated(), TimeSpan::ZERO);
+    assert_eq!(budget.allocate("audio", 0.01), None);
+    assert_eq!(budget.allocate("audio", 0.0), Some(TimeSpan::ZERO));
+
+    assert_eq!(budget.check("physics", TimeSpan::MILLISECOND * 5), None);
+    assert_eq!(
+        budget.check("physics", TimeSpan::MILLISECOND * 8),
+        Some(TimeSpan::MILLISECOND * 2)
+    );
+    assert_eq!(
+        budget.check("physics", TimeSpan::MILLISECOND * 7),
+        Some(TimeSpan::MILLISECOND)
+    );
+
+    let physics = budget.get("physics").unwrap();
+    assert_eq!(physics.overruns(), 2);
+    assert_eq!(physics.worst_overrun(), TimeSpan::MILLISECOND * 2);
+    assert_eq!(physics.last(), Some(TimeSpan::MILLISECOND * 7));
+    assert_eq!(budget.sub_budgets().len(), 4);
+
+    let summary = budget.to_string();
+    assert_eq!(summary.lines().count(), 5);
+    assert!(summary.lines().nth(1).unwrap().contains("2 overruns"));
+}
@@ -2,27 +2,37 @@
 //! that handle time spans and time stamps
 //! where actual passing time spans are provided externally.
 
-use std::time::{Duration, Instant};
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
-use crate::{span::TimeSpan, stamp::TimeStamp, Frequency, FrequencyTicker};
-
-/// Result of `Clock` step.
-/// Contains time stamp corresponding to "now"
-/// and time span since previous step.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct ClockStep {
-    /// TimeStamp corresponding to "now".
-    pub now: TimeStamp,
-    pub step: TimeSpan,
-}
+use crate::{
+    span::TimeSpan,
+    stamp::{LongTimeStamp, TimeStamp, TimeStampOutOfRange},
+    step::ClockStep,
+    ClockRate, Frequency, FrequencyTicker,
+};
 
 /// Time measuring device.
 /// Uses system monotonic clock counter
 /// and yields `ClockStep`s for each step.
+///
+/// Time stamp of a step is real time elapsed since the `start` anchor.
+/// [`TimeStamp::start`] corresponds to the anchor instant,
+/// see [`Clock::stamp_instant`].
+/// [`Clock::reset`] and [`Clock::rewind_to`] move the anchor.
+///
+/// Cloned clock shares anomaly handler with the original.
 #[derive(Clone)] // Not Copy to avoid accidental copying.
 pub struct Clock {
+    /// Instant corresponding to `TimeStamp::start()`.
     start: Instant,
     now: TimeStamp,
+
+    /// Number of non-monotonic readings observed.
+    anomalies: u64,
+    anomaly_handler: Option<Arc<dyn Fn(TimeSpan) + Send + Sync>>,
 }
 
 impl Default for Clock {
@@ -39,6 +49,8 @@ impl Clock {
         Clock {
             start: Instant::now(),
             now: TimeStamp::start(),
+            anomalies: 0,
+            anomaly_handler: None,
         }
     }
 
@@ -50,17 +62,121 @@ impl Clock {
     /// Advances the clock and returns `ClockStep` result
     /// with new time stamp and time span since previous step.
     pub fn step(&mut self) -> ClockStep {
-        let from_start = self.start.elapsed();
+        self.step_at(Instant::now())
+    }
+
+    /// Advances the clock to given instant and returns `ClockStep` result
+    /// with new time stamp and time span since previous step.
+    ///
+    /// If `instant` is earlier than the last step, the reading is an anomaly.
+    /// It is counted, reported to the anomaly handler
+    /// and results in zero-length step, keeping the clock monotonic.
+    pub fn step_at(&mut self, instant: Instant) -> ClockStep {
+        let from_start = instant.saturating_duration_since(self.start);
         let now = TimeStamp::from_observed_duration(from_start);
-        let step = now - self.now;
-        self.now = now;
 
-        ClockStep {
-            now: self.now,
-            step,
+        match now.checked_elapsed_since(self.now) {
+            Some(step) => {
+                self.now = now;
+                ClockStep { now, step }
+            }
+            None => {
+                self.anomalies += 1;
+                if let Some(handler) = &self.anomaly_handler {
+                    handler(self.now - now);
+                }
+
+                ClockStep::zero_at(self.now)
+            }
         }
     }
 
+    /// Advances the clock and then `rate` by the real time step.
+    /// Returns scaled step of `rate`.
+    pub fn step_rate(&mut self, rate: &mut ClockRate) -> ClockStep {
+        self.step_rate_at(Instant::now(), rate)
+    }
+
+    /// Advances the clock to given instant and then `rate` by the real time step.
+    /// Returns scaled step of `rate`.
+    pub fn step_rate_at(&mut self, instant: Instant, rate: &mut ClockRate) -> ClockStep {
+        let step = self.step_at(instant);
+        rate.step(step.step)
+    }
+
+    /// Returns number of non-monotonic readings observed by this clock.
+    pub fn anomalies(&self) -> u64 {
+        self.anomalies
+    }
+
+    /// Sets handler that is called with the backward delta
+    /// whenever non-monotonic reading is observed.
+    pub fn set_anomaly_handler(&mut self, handler: impl Fn(TimeSpan) + Send + Sync + 'static) {
+        self.anomaly_handler = Some(Arc::new(handler));
+    }
+
+    /// Sets handler that is called with the backward delta
+    /// whenever non-monotonic reading is observed.
+    ///
+    /// Unlike [`Clock::set_anomaly_handler`] handler may mutate captured state,
+    /// e.g. to count or log anomalies.
+    /// Calls are serialized with a mutex, which clones of the clock share.
+    pub fn set_anomaly_handler_mut(&mut self, handler: impl FnMut(TimeSpan) + Send + 'static) {
+        let handler = Mutex::new(handler);
+        self.anomaly_handler = Some(Arc::new(move |delta| {
+            let mut handler = handler.lock().unwrap_or_else(PoisonError::into_inner);
+            handler(delta)
+        }));
+    }
+
+    /// Resets the clock so that current moment becomes [`TimeStamp::start`].
+    ///
+    /// Time stamps produced before reset are not comparable with new ones.
+    /// Tickers created from this clock should be moved to the new time line
+    /// with [`FrequencyTicker::rebase`]:
+    ///
+    /// ```
+    /// # use gametime::{Clock, Frequency};
+    /// let mut clock = Clock::new();
+    /// let mut ticker = clock.ticker(Frequency::from_hz(10));
+    ///
+    /// let old_now = clock.now();
+    /// clock.reset();
+    /// ticker.rebase(old_now, clock.now());
+    /// ```
+    pub fn reset(&mut self) {
+        self.start = Instant::now();
+        self.now = TimeStamp::start();
+    }
+
+    /// Sets time stamp of the last step to `now`, keeping real time of the last step.
+    ///
+    /// The anchor is moved so that real instant of the last step maps to `now`.
+    /// Following steps continue from `now` by real time elapsed since the last step.
+    /// `now` may be earlier or later than current time stamp,
+    /// e.g. to rewind or fast-forward replay.
+    ///
+    /// Time stamps produced before the call are not comparable with new ones,
+    /// see [`Clock::reset`].
+    ///
+    /// Returns error and leaves the clock unchanged if the anchor for `now`
+    /// is not representable as `Instant`.
+    /// This may happen on some platforms when `now` is far later
+    /// than real time elapsed since the system started.
+    pub fn rewind_to(&mut self, now: TimeStamp) -> Result<(), TimeStampOutOfRange> {
+        let last = self.stamp_instant(self.now);
+        self.start = last
+            .checked_sub(Duration::from_nanos(now.nanos_since_start()))
+            .ok_or(TimeStampOutOfRange)?;
+        self.now = now;
+        Ok(())
+    }
+
+    /// Returns time stamp corresponding to current moment without advancing the clock.
+    pub(crate) fn current(&self) -> TimeStamp {
+        TimeStamp::from_observed_duration(self.start.elapsed())
+    }
+
     /// Returns `Instant` corresponding to given `TimeStamp`.
     pub fn stamp_instant(&self, stamp: TimeStamp) -> Instant {
         self.start + Duration::from_nanos(stamp.nanos_since_start())
@@ -70,3 +186,422 @@ impl Clock {
         FrequencyTicker::new(freq, self.now)
     }
 }
+
+/// Time measuring device with 128-bit time stamps.
+///
+/// Works like [`Clock`], but never overflows
+/// no matter how long the process runs.
+#[derive(Clone)] // Not Copy to avoid accidental copying.
+pub struct LongClock {
+    start: Instant,
+    now: LongTimeStamp,
+}
+
+impl Default for LongClock {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LongClock {
+    /// Returns new `LongClock` instance.
+    #[inline(always)]
+    pub fn new() -> Self {
+        LongClock {
+            start: Instant::now(),
+            now: LongTimeStamp::start(),
+        }
+    }
+
+    /// Returns time stamp corresponding to "now" of the last step.
+    pub fn now(&self) -> LongTimeStamp {
+        self.now
+    }
+
+    /// Advances the clock and returns time span since previous step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than 500 years passed since previous step.
+    pub fn step(&mut self) -> TimeSpan {
+        self.advance_to(self.start.elapsed())
+    }
+
+    fn advance_to(&mut self, from_start: Duration) -> TimeSpan {
+        let now = LongTimeStamp::from_elapsed(from_start.as_nanos()).max(self.now);
+        let step = now
+            .checked_elapsed_since(self.now)
+            .expect("too much time passed since previous step");
+        self.now = now;
+        step
+    }
+}
+
+/// Time measuring device that uses global reference point.
+/// Yields `ClockStep`s for each step.
+///
+/// Time stamps of all `GlobalClock`s are relative to the same reference point
+/// as [`TimeStamp::now`] and thus can be compared with each other.
+///
+/// Constructing a `GlobalClock` initializes the global reference point
+/// if it is not initialized yet.
+#[cfg(feature = "global_reference")]
+#[derive(Clone)] // Not Copy to avoid accidental copying.
+pub struct GlobalClock {
+    now: TimeStamp,
+}
+
+#[cfg(feature = "global_reference")]
+impl Default for GlobalClock {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "global_reference")]
+impl GlobalClock {
+    /// Returns new `GlobalClock` instance with "now" set to [`TimeStamp::now`].
+    #[inline(always)]
+    pub fn new() -> Self {
+        GlobalClock {
+            now: TimeStamp::now(),
+        }
+    }
+
+    /// Returns new `GlobalClock` instance with "now" set to given time stamp.
+    ///
+    /// Steps report zero time span until global time catches up with `now`.
+    #[inline(always)]
+    pub fn with_now(now: TimeStamp) -> Self {
+        crate::global_reference::get();
+        GlobalClock { now }
+    }
+
+    /// Returns time stamp corresponding to "now" of the last step.
+    pub fn now(&self) -> TimeStamp {
+        self.now
+    }
+
+    /// Advances the clock and returns `ClockStep` result
+    /// with new time stamp and time span since previous step.
+    pub fn step(&mut self) -> ClockStep {
+        let now = TimeStamp::now();
+        let step = now
+            .checked_elapsed_since(self.now)
+            .unwrap_or(TimeSpan::ZERO);
+        self.now = self.now.max(now);
+
+        ClockStep {
+            now: self.now,
+            step,
+        }
+    }
+
+    pub fn ticker(&self, freq: Frequency) -> FrequencyTicker {
+        FrequencyTicker::new(freq, self.now)
+    }
+}
+
+/// Frequency ticker driven by global time.
+///
+/// Combines [`GlobalClock`] and [`FrequencyTicker`]
+/// for cases where ticks should simply follow real time.
+#[cfg(feature = "global_reference")]
+pub struct GlobalTicker {
+    clock: GlobalClock,
+    ticker: FrequencyTicker,
+}
+
+#[cfg(feature = "global_reference")]
+impl GlobalTicker {
+    /// Returns new `GlobalTicker` with given frequency starting from [`TimeStamp::now`].
+    pub fn new(freq: Frequency) -> Self {
+        let clock = GlobalClock::new();
+        let ticker = clock.ticker(freq);
+        GlobalTicker { clock, ticker }
+    }
+
+    /// Returns underlying frequency ticker.
+    pub fn ticker(&self) -> &FrequencyTicker {
+        &self.ticker
+    }
+
+    /// Advances ticker to current time and returns iterator over ticks
+    /// since last poll.
+    pub fn poll(&mut self) -> crate::FrequencyTickerIter {
+        let step = self.clock.step();
+        self.ticker.ticks(step.step)
+    }
+
+    /// Blocks current thread until next tick is due.
+    /// Returns immediately if it is already due.
+    pub fn sleep_until_next(&self) {
+        let Some(next) = self.ticker.next_tick() else {
+            return;
+        };
+
+        if let Some(span) = next.checked_elapsed_since(TimeStamp::now()) {
+            std::thread::sleep(span.into());
+        }
+    }
+}
+
+#[cfg(feature = "global_reference")]
+#[test]
+fn test_global_clocks_share_reference() {
+    let a = GlobalClock::new();
+    let reference = crate::global_reference::get();
+    let b = GlobalClock::default();
+
+    assert_eq!(reference, crate::global_reference::get());
+    assert!(a.now() <= b.now());
+}
+
+#[cfg(feature = "global_reference")]
+#[test]
+fn test_global_clock_with_now() {
+    let mut clock = GlobalClock::with_now(TimeStamp::never());
+    assert_eq!(clock.now(), TimeStamp::never());
+
+    let step = clock.step();
+    assert_eq!(step.now, TimeStamp::never());
+    assert_eq!(step.step, TimeSpan::ZERO);
+}
+
+#[cfg(feature = "global_reference")]
+#[test]
+#[ignore = "depends on real time and scheduler precision"]
+fn test_global_ticker() {
+    let mut ticker = GlobalTicker::new(Frequency::from_hz(100));
+    let start = TimeStamp::now();
+
+    let mut ticks = 0;
+    while TimeStamp::now() - start < TimeSpan::MILLISECOND * 100 {
+        ticker.sleep_until_next();
+        ticks += ticker.poll().count();
+    }
+
+    assert!(
+        (8..=12).contains(&ticks),
+        "{} ticks in 100ms at 100Hz",
+        ticks
+    );
+}
+
+#[test]
+fn test_clock_reset() {
+    let mut clock = Clock::new();
+    std::thread::sleep(Duration::from_millis(1));
+    clock.step();
+    assert!(clock.now() > TimeStamp::start());
+
+    clock.reset();
+    assert_eq!(clock.now(), TimeStamp::start());
+    assert!(clock.step().now >= TimeStamp::start());
+}
+
+#[test]
+fn test_clock_reset_ticker_phase() {
+    let mut clock = Clock::new();
+    let base = clock.stamp_instant(TimeStamp::start());
+    let mut ticker = clock.ticker(Frequency::from_hz(10));
+
+    let step = clock.step_at(base + Duration::from_millis(250));
+    assert_eq!(ticker.ticks(step.step).count(), 2);
+
+    // Ticker created before reset is moved to the new time line.
+    let old_now = clock.now();
+    clock.reset();
+    ticker.rebase(old_now, clock.now());
+    assert_eq!(ticker.now(), TimeStamp::start());
+
+    // Ticks continue every 100ms, 50ms after reset, without a burst or a stall.
+    let base = clock.stamp_instant(TimeStamp::start());
+    let mut ticks = Vec::new();
+    for ms in [30, 49, 50, 120, 149, 150, 360] {
+        let step = clock.step_at(base + Duration::from_millis(ms));
+        ticks.extend(ticker.ticks(step.step).map(|tick| tick.now));
+    }
+    assert_eq!(
+        ticks,
+        [50, 150, 250, 350].map(|ms| TimeStamp::start() + TimeSpan::MILLISECOND * ms)
+    );
+    assert_eq!(ticker.tick_index(), 6);
+}
+
+#[test]
+fn test_clock_rewind_to() {
+    let mut clock = Clock::new();
+    let base = clock.stamp_instant(TimeStamp::start());
+    let at = |ms: u64| base + Duration::from_millis(ms);
+
+    clock.step_at(at(1000));
+    clock
+        .rewind_to(TimeStamp::start() + TimeSpan::MILLISECOND * 200)
+        .unwrap();
+    assert_eq!(
+        clock.now(),
+        TimeStamp::start() + TimeSpan::MILLISECOND * 200
+    );
+
+    let step = clock.step_at(at(1016));
+    assert_eq!(step.step, TimeSpan::MILLISECOND * 16);
+    assert_eq!(step.now, TimeStamp::start() + TimeSpan::MILLISECOND * 216);
+
+    // Readings before rewound step are still anomalies.
+    let step = clock.step_at(at(1010));
+    assert_eq!(step.step, TimeSpan::ZERO);
+    assert_eq!(clock.anomalies(), 1);
+
+    // Fast-forward.
+    clock
+        .rewind_to(TimeStamp::start() + TimeSpan::MILLISECOND * 5000)
+        .unwrap();
+    let step = clock.step_at(at(1020));
+    assert_eq!(step.step, TimeSpan::MILLISECOND * 4);
+    assert_eq!(step.now, TimeStamp::start() + TimeSpan::MILLISECOND * 5004);
+
+    // Real time continues from the rewound point.
+    let rewound = TimeStamp::start() + TimeSpan::MILLISECOND;
+    clock.step();
+    clock.rewind_to(rewound).unwrap();
+    let step = clock.step();
+    assert!(step.now >= rewound);
+    assert!(step.step < TimeSpan::SECOND);
+    assert_eq!(step.now, rewound + step.step);
+
+    // Anchor centuries before system start may not be representable,
+    // then the clock is unchanged.
+    let before = clock.now();
+    match clock.rewind_to(TimeStamp::never()) {
+        Ok(()) => assert_eq!(clock.now(), TimeStamp::never()),
+        Err(TimeStampOutOfRange) => {
+            assert_eq!(clock.now(), before);
+            clock.rewind_to(rewound).unwrap();
+            assert_eq!(clock.now(), rewound);
+        }
+    }
+}
+
+#[test]
+fn test_long_clock_centuries() {
+    const YEAR: u64 = 365 * 24 * 60 * 60;
+
+    let mut clock = LongClock::new();
+    let step = clock.advance_to(Duration::from_secs(YEAR * 300));
+    assert_eq!(step, TimeSpan::DAY * 365 * 300);
+
+    // Total elapsed time no longer fits into `TimeStamp`.
+    let step = clock.advance_to(Duration::from_secs(YEAR * 700));
+    assert_eq!(step, TimeSpan::DAY * 365 * 400);
+    assert_eq!(
+        clock.now().nanos_since_start(),
+        YEAR as u128 * 700 * 1_000_000_000
+    );
+    assert_eq!(clock.now().to_stamp(), None);
+
+    // Real time is far behind simulated one.
+    assert_eq!(clock.step(), TimeSpan::ZERO);
+}
+
+#[test]
+fn test_clock_anomalies() {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    };
+
+    let mut clock = Clock::new();
+    let reported = Arc::new(AtomicU64::new(0));
+    let handler_reported = reported.clone();
+    clock.set_anomaly_handler(move |delta| {
+        handler_reported.fetch_add(delta.as_nanos(), Ordering::Relaxed);
+    });
+
+    let start = clock.stamp_instant(TimeStamp::start());
+    let step = clock.step_at(start + Duration::from_millis(10));
+    assert_eq!(step.step, TimeSpan::MILLISECOND * 10);
+
+    // Equal reading is not an anomaly.
+    let step = clock.step_at(start + Duration::from_millis(10));
+    assert_eq!(step.step, TimeSpan::ZERO);
+    assert_eq!(clock.anomalies(), 0);
+
+    // Backward reading.
+    let step = clock.step_at(start + Duration::from_millis(7));
+    assert_eq!(step.step, TimeSpan::ZERO);
+    assert_eq!(step.now, TimeStamp::start() + TimeSpan::MILLISECOND * 10);
+    assert_eq!(clock.anomalies(), 1);
+    assert_eq!(
+        reported.load(Ordering::Relaxed),
+        TimeSpan::MILLISECOND.as_nanos() * 3
+    );
+
+    // Clock continues from the latest monotonic reading.
+    let step = clock.step_at(start + Duration::from_millis(12));
+    assert_eq!(step.step, TimeSpan::MILLISECOND * 2);
+    assert_eq!(clock.anomalies(), 1);
+
+    // Cloned clock shares the handler.
+    let mut cloned = clock.clone();
+    cloned.step_at(start);
+    assert_eq!(cloned.anomalies(), 2);
+    assert_eq!(clock.anomalies(), 1);
+    assert_eq!(
+        reported.load(Ordering::Relaxed),
+        TimeSpan::MILLISECOND.as_nanos() * 15
+    );
+}
+
+#[test]
+fn test_clock_anomaly_handler_mut() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Clock>();
+
+    let mut clock = Clock::new();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    // Handler keeps its own count of anomalies.
+    let mut count = 0;
+    clock.set_anomaly_handler_mut(move |delta| {
+        count += 1;
+        sender.send((count, delta)).unwrap();
+    });
+
+    let start = clock.stamp_instant(TimeStamp::start());
+    clock.step_at(start + Duration::from_millis(10));
+    clock.step_at(start + Duration::from_millis(7));
+    clock.clone().step_at(start + Duration::from_millis(4));
+
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        [
+            (1, TimeSpan::MILLISECOND * 3),
+            (2, TimeSpan::MILLISECOND * 6),
+        ]
+    );
+}
+
+#[test]
+fn test_clock_step_rate() {
+    let mut clock = Clock::new();
+    let mut manual_clock = clock.clone();
+    let mut rate = ClockRate::new().with_rate(0.5);
+    let mut manual_rate = ClockRate::new().with_rate(0.5);
+
+    let start = clock.stamp_instant(TimeStamp::start());
+    for ms in [3, 10, 10, 17, 40] {
+        let instant = start + Duration::from_millis(ms);
+        let step = clock.step_rate_at(instant, &mut rate);
+
+        let real = manual_clock.step_at(instant);
+        assert_eq!(step, manual_rate.step(real.step));
+        assert_eq!(clock.now(), manual_clock.now());
+    }
+    assert_eq!(rate.now(), TimeStamp::start() + TimeSpan::MILLISECOND * 20);
+
+    let step = clock.step_rate(&mut rate);
+    assert_eq!(step.now, rate.now());
+}
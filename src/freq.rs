@@ -1,23 +1,56 @@
 //! Contains types and functions to work with frequencies.
 
-use core::{convert::TryInto, iter::FusedIterator, num::NonZeroU64, ops};
+use core::{
+    convert::TryInto,
+    fmt,
+    iter::FusedIterator,
+    num::NonZeroU64,
+    ops::{self, ControlFlow},
+};
 
 use crate::{
     gcd,
+    rate::Rate,
     span::{NonZeroTimeSpan, TimeSpan},
     stamp::TimeStamp,
     ClockStep,
 };
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use alloc::{format, string::String};
+
 #[cfg(feature = "serde")]
-use serde::ser::SerializeTupleStruct;
+use serde::ser::{SerializeStruct, SerializeTupleStruct};
+
+/// Spacing of frequencies produced by [`Frequency::sweep`].
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SweepScale {
+    /// Successive frequencies differ by the same amount.
+    Linear,
+
+    /// Successive frequencies differ by the same factor.
+    Log,
+}
 
 /// Represents frequency.
 /// Able to accurately represent any rational frequency.
-#[derive(Clone, Copy)]
+///
+/// Frequency is `count` ticks per `period` nanoseconds.
+/// Both values are always kept reduced by their greatest common divisor,
+/// so equal frequencies have equal representation.
+///
+/// Any combination of `count` and `period` is valid.
+/// [`FrequencyTicker`] measures time in units of `1 / count` nanoseconds
+/// using 128-bit arithmetic, where product of any `u64` span and `count`
+/// and any `u64` number of periods fits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Frequency {
-    pub count: u64,
-    pub period: NonZeroU64,
+    count: u64,
+    period: NonZeroU64,
 }
 
 impl Frequency {
@@ -29,86 +62,554 @@ impl Frequency {
             .map(|period| Frequency::new(count, period))
     }
 
-    pub fn new(count: u64, period: NonZeroTimeSpan) -> Self {
-        let gcd = gcd(count, period.as_nanos().get());
+    #[inline(always)]
+    pub const fn new(count: u64, period: NonZeroTimeSpan) -> Self {
+        Frequency::from_raw(count, period.as_nanos())
+    }
+
+    /// Creates frequency of `count` ticks per `period` nanoseconds.
+    ///
+    /// Values are reduced by their greatest common divisor.
+    pub const fn from_raw(count: u64, period: NonZeroU64) -> Self {
+        let gcd = gcd(count, period.get());
         let count = count / gcd;
-        let period_nanos = period.as_nanos().get() / gcd;
 
-        Frequency {
-            count,
-            period: NonZeroU64::new(period_nanos).unwrap(),
-        }
+        // Safety: gcd divides period, so quotient is not zero.
+        let period = unsafe { NonZeroU64::new_unchecked(period.get() / gcd) };
+        Frequency { count, period }
+    }
+
+    /// Returns number of ticks per period.
+    #[inline(always)]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns period in nanoseconds.
+    #[inline(always)]
+    pub const fn period_nanos(&self) -> NonZeroU64 {
+        self.period
+    }
+
+    /// Returns period as time span.
+    #[inline(always)]
+    pub const fn period_span(&self) -> NonZeroTimeSpan {
+        NonZeroTimeSpan::new(self.period)
     }
 
     #[inline(always)]
-    pub fn from_hz(value: u64) -> Self {
+    pub const fn from_hz(value: u64) -> Self {
         Frequency::new(value, NonZeroTimeSpan::SECOND)
     }
 
     #[inline(always)]
-    pub fn from_khz(value: u64) -> Self {
+    pub const fn from_khz(value: u64) -> Self {
         Frequency::new(value, NonZeroTimeSpan::MILLISECOND)
     }
 
     #[inline(always)]
-    pub fn from_mhz(value: u64) -> Self {
+    pub const fn from_mhz(value: u64) -> Self {
         Frequency::new(value, NonZeroTimeSpan::MICROSECOND)
     }
 
     #[inline(always)]
-    pub fn from_ghz(value: u64) -> Self {
+    pub const fn from_ghz(value: u64) -> Self {
         Frequency::new(value, NonZeroTimeSpan::NANOSECOND)
     }
 
+    /// Returns exact rational frequency closest to `hz` hertz
+    /// found with continued fraction expansion.
+    ///
+    /// Expansion stops when the fraction equals `hz` within `f64` precision
+    /// or period doesn't fit into `u64` nanoseconds any more,
+    /// so decimal values like `59.94` produce `2997/50` Hz
+    /// and `60000.0 / 1001.0` produces exactly `60000/1001` Hz.
+    ///
+    /// Returns `None` for non-positive values, NaN, infinity
+    /// and values too small or too large to represent.
+    pub fn from_hz_f64(hz: f64) -> Option<Frequency> {
+        // Number of seconds in the period must fit into `u64` nanoseconds.
+        const MAX_SECONDS: u128 = (u64::MAX / 1_000_000_000) as u128;
+
+        if !(hz > 0.0 && hz < u64::MAX as f64) {
+            return None;
+        }
+
+        // Convergents `count / seconds`.
+        let (mut count, mut prev_count) = (1u128, 0u128);
+        let (mut seconds, mut prev_seconds) = (0u128, 1u128);
+
+        let mut x = hz;
+        for _ in 0..64 {
+            // Truncation is floor for positive values.
+            let a = x as u128;
+            let next_count = a * count + prev_count;
+            let next_seconds = a * seconds + prev_seconds;
+            if next_count > u64::MAX as u128 || next_seconds > MAX_SECONDS {
+                break;
+            }
+
+            (count, prev_count) = (next_count, count);
+            (seconds, prev_seconds) = (next_seconds, seconds);
+
+            let fract = x - a as f64;
+            if fract <= 0.0 || (count as f64 / seconds as f64 - hz).abs() <= hz * f64::EPSILON {
+                break;
+            }
+            x = 1.0 / fract;
+        }
+
+        if count == 0 || seconds == 0 {
+            return None;
+        }
+
+        let period = NonZeroU64::new(seconds as u64 * 1_000_000_000)?;
+        Some(Frequency::from_raw(count as u64, period))
+    }
+
+    /// Returns exact ratio of this frequency to `other` as reduced fraction.
+    ///
+    /// Returns `None` if `other` is zero or either part of the fraction
+    /// doesn't fit into `u64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use gametime::Frequency;
+    /// # use core::num::NonZeroU64;
+    /// let ratio = Frequency::from_hz(48000).ratio_to(&Frequency::from_hz(44100));
+    /// assert_eq!(ratio, Some((160, NonZeroU64::new(147).unwrap())));
+    /// ```
+    pub fn ratio_to(&self, other: &Frequency) -> Option<(u64, NonZeroU64)> {
+        if other.count == 0 {
+            return None;
+        }
+
+        // Both frequencies are reduced, so cross-reduction yields reduced fraction.
+        let gcd_count = gcd(self.count, other.count);
+        let gcd_period = gcd(self.period.get(), other.period.get());
+
+        let nom = (self.count / gcd_count) as u128 * (other.period.get() / gcd_period) as u128;
+        let denom = (self.period.get() / gcd_period) as u128 * (other.count / gcd_count) as u128;
+
+        let nom = u64::try_from(nom).ok()?;
+        let denom = NonZeroU64::new(u64::try_from(denom).ok()?)?;
+        Some((nom, denom))
+    }
+
+    /// Returns `true` if this frequency is integer multiple of `other`.
+    ///
+    /// Zero frequency is multiple of any frequency,
+    /// and only zero frequency is multiple of zero frequency.
+    pub fn is_multiple_of(&self, other: &Frequency) -> bool {
+        if other.count == 0 {
+            return self.count == 0;
+        }
+
+        self.count.is_multiple_of(other.count)
+            && other.period.get().is_multiple_of(self.period.get())
+    }
+
+    /// Returns least whole number of nanoseconds that is multiple of periods
+    /// of both this and `other` frequencies,
+    /// i.e. span after which tickers with these frequencies started together
+    /// tick simultaneously again.
+    ///
+    /// Returns `None` if either frequency is zero or span doesn't fit into [`TimeSpan`].
+    pub fn common_tick_period(&self, other: &Frequency) -> Option<TimeSpan> {
+        if self.count == 0 || other.count == 0 {
+            return None;
+        }
+
+        // Ticks happen every `period / count` nanoseconds.
+        // Count and period are coprime, so whole number of nanoseconds
+        // is multiple of that exactly when it is multiple of `period`.
+        // Hence the answer is least common multiple of the periods.
+        let gcd_period = gcd(self.period.get(), other.period.get());
+        let nanos = (self.period.get() / gcd_period).checked_mul(other.period.get())?;
+        Some(TimeSpan::new(nanos))
+    }
+
     #[inline(always)]
     pub fn periods_in(&self, span: TimeSpan) -> u64 {
         self.periods_in_elements(self.elements(span))
     }
 
+    /// Returns number of samples at this frequency in `span`, rounded down.
+    ///
+    /// Use [`SampleClock`] to convert a stream of spans without accumulating error.
+    #[inline(always)]
+    pub fn samples_in_span(&self, span: TimeSpan) -> u64 {
+        let samples = span.as_nanos() as u128 * self.count as u128 / self.period.get() as u128;
+        samples.try_into().unwrap_or(u64::MAX)
+    }
+
+    /// Returns number of samples at this frequency between `a` and `b`,
+    /// rounded down. Returns `0` if `b` is before `a`.
+    #[inline(always)]
+    pub fn samples_between(&self, a: TimeStamp, b: TimeStamp) -> u64 {
+        let span = b.checked_elapsed_since(a).unwrap_or(TimeSpan::ZERO);
+        self.samples_in_span(span)
+    }
+
+    /// Returns span of `samples` at this frequency, rounded up to the nanosecond.
+    ///
+    /// For frequencies up to 1 GHz `samples_in_span(span_of_samples(n)) == n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if frequency is zero or span doesn't fit into `TimeSpan`.
+    #[inline(always)]
+    pub fn span_of_samples(&self, samples: u64) -> TimeSpan {
+        let (span, remainder) = self.span_of_samples_exact(samples);
+        if remainder > 0 {
+            span + TimeSpan::NANOSECOND
+        } else {
+            span
+        }
+    }
+
+    /// Returns span of `samples` at this frequency, rounded down to the nanosecond,
+    /// and the remainder in units of `1 / count` nanoseconds,
+    /// where `count` is [`Frequency::count`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if frequency is zero or span doesn't fit into `TimeSpan`.
+    #[inline(always)]
+    pub fn span_of_samples_exact(&self, samples: u64) -> (TimeSpan, u64) {
+        assert_ne!(self.count, 0, "span of samples at zero frequency");
+
+        let elements = samples as u128 * self.period.get() as u128;
+        let nanos = (elements / self.count as u128)
+            .try_into()
+            .expect("overflow when calculating span of samples");
+        let remainder = (elements % self.count as u128) as u64;
+        (TimeSpan::new(nanos), remainder)
+    }
+
+    /// Returns number of whole periods that fit into `span`
+    /// and the part of `span` not covered by them, rounded down to the nanosecond.
+    ///
+    /// For non-zero frequency `span_of_samples_exact(n).0 + remainder`
+    /// equals `span` or `span - 1ns`, where `n` is the number of periods.
+    /// Zero frequency has no periods and the whole span is the remainder.
+    ///
+    /// Use [`Frequency::periods_and_phase`] to carry exact remainder across calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if number of periods doesn't fit into `u64`.
+    #[inline(always)]
+    pub fn periods_and_remainder(&self, span: TimeSpan) -> (u64, TimeSpan) {
+        if self.count == 0 {
+            return (0, span);
+        }
+        let (periods, phase) = self.periods_and_phase(span, PhaseRemainder::ZERO);
+        (periods, self.phase_span(phase))
+    }
+
+    /// Returns number of whole periods that fit into `span` plus `carry`
+    /// and the exact part not covered by them.
+    ///
+    /// Passing returned remainder as `carry` to the next call
+    /// counts periods over a sequence of spans without accumulating error.
+    /// Remainder is only meaningful for this frequency.
+    /// Zero frequency has no periods and zero remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if number of periods doesn't fit into `u64`.
+    #[inline(always)]
+    pub fn periods_and_phase(
+        &self,
+        span: TimeSpan,
+        carry: PhaseRemainder,
+    ) -> (u64, PhaseRemainder) {
+        if self.count == 0 {
+            return (0, PhaseRemainder::ZERO);
+        }
+
+        let period = self.period.get() as u128;
+        let elements = span.as_nanos() as u128 * self.count as u128 + carry.elements as u128;
+        let periods = (elements / period)
+            .try_into()
+            .expect("overflow when counting periods");
+
+        // Remainder is less than period.
+        let elements = (elements % period) as u64;
+        (periods, PhaseRemainder { elements })
+    }
+
+    /// Returns span of the remainder returned by [`Frequency::periods_and_phase`],
+    /// rounded down to the nanosecond.
+    #[inline(always)]
+    pub fn phase_span(&self, phase: PhaseRemainder) -> TimeSpan {
+        match self.count {
+            0 => TimeSpan::ZERO,
+            count => TimeSpan::new(phase.elements / count),
+        }
+    }
+
+    /// Returns duration of one frame at this frequency, rounded down to the nanosecond.
+    /// Returns `None` for zero frequency.
+    #[inline(always)]
+    pub fn frame_time(&self) -> Option<TimeSpan> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.span_of_samples_exact(1).0)
+    }
+
+    /// Returns `fraction` of the frame time at this frequency, rounded down to the nanosecond.
+    /// Returns `None` for zero frequency or if `fraction` is not in `[0, 1]`.
+    #[inline(always)]
+    pub fn budget_fraction(&self, fraction: f32) -> Option<TimeSpan> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return None;
+        }
+        let frame = self.frame_time()?;
+        Some(TimeSpan::new(
+            (frame.as_nanos() as f64 * fraction as f64) as u64,
+        ))
+    }
+
+    /// Returns iterator over `n` successive halvings of this frequency,
+    /// starting with the frequency itself, e.g. 60, 30, 15 Hz.
+    ///
+    /// Halved frequencies are exact. Iteration stops early
+    /// if period of the next frequency doesn't fit into `u64` nanoseconds.
+    /// Zero frequency yields nothing.
+    pub fn harmonics(self, n: usize) -> impl Iterator<Item = Frequency> {
+        let first = if self.count == 0 { None } else { Some(self) };
+
+        core::iter::successors(first, |freq| freq.halved()).take(n)
+    }
+
+    /// Returns exactly half of this frequency.
+    ///
+    /// Returns `None` if its period doesn't fit into `u64` nanoseconds.
+    pub fn halved(self) -> Option<Frequency> {
+        // Frequency is reduced, so dividing even count or doubling period keeps it reduced.
+        if self.count.is_multiple_of(2) {
+            Some(Frequency {
+                count: self.count / 2,
+                period: self.period,
+            })
+        } else {
+            Some(Frequency {
+                count: self.count,
+                period: self.period.checked_mul(NonZeroU64::new(2).unwrap())?,
+            })
+        }
+    }
+
+    /// Returns exactly twice this frequency.
+    ///
+    /// Returns `None` if its count doesn't fit into `u64`.
+    pub fn doubled(self) -> Option<Frequency> {
+        // Frequency is reduced, so halving even period or doubling count keeps it reduced.
+        if self.period.get().is_multiple_of(2) {
+            Some(Frequency {
+                count: self.count,
+                period: NonZeroU64::new(self.period.get() / 2).unwrap(),
+            })
+        } else {
+            Some(Frequency {
+                count: self.count.checked_mul(2)?,
+                period: self.period,
+            })
+        }
+    }
+
+    /// Returns this frequency scaled by `rate`.
+    ///
+    /// Result is exact when it is representable.
+    /// Otherwise it is approximated by a ratio that fits into `u64` count and period,
+    /// and clamped between
+    /// one tick per `u64::MAX` nanoseconds and `u64::MAX` ticks per nanosecond.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use gametime::{Frequency, Rate};
+    /// # use core::num::NonZeroU64;
+    /// let freq = Frequency::from_khz(1).clamp_rate(Rate::new(1000, NonZeroU64::MIN));
+    /// assert_eq!(freq, Frequency::from_mhz(1));
+    ///
+    /// let fastest = Frequency::from_raw(u64::MAX, NonZeroU64::MIN);
+    /// assert_eq!(fastest.clamp_rate(Rate::new(2, NonZeroU64::MIN)), fastest);
+    /// ```
+    pub fn clamp_rate(self, rate: Rate) -> Frequency {
+        let count = self.count as u128 * rate.nom() as u128;
+        let period = self.period.get() as u128 * rate.denom().get() as u128;
+
+        let gcd = crate::gcd_u128(count, period);
+        let (count, period) = (count / gcd, period / gcd);
+
+        if let (Ok(count), Ok(period)) = (u64::try_from(count), u64::try_from(period)) {
+            return Frequency::from_raw(count, NonZeroU64::new(period).unwrap());
+        }
+
+        // Last continued fraction convergent that fits is a close approximation.
+        let (mut n, mut d) = (count, period);
+        let (mut h, mut k) = ((0u128, 1u128), (1u128, 0u128));
+        while d != 0 {
+            let a = n / d;
+            let next_h = a.checked_mul(h.1).and_then(|x| x.checked_add(h.0));
+            let next_k = a.checked_mul(k.1).and_then(|x| x.checked_add(k.0));
+            match (next_h, next_k) {
+                (Some(next_h), Some(next_k))
+                    if next_h <= u64::MAX as u128 && next_k <= u64::MAX as u128 =>
+                {
+                    h = (h.1, next_h);
+                    k = (k.1, next_k);
+                }
+                _ => break,
+            }
+            (n, d) = (d, n % d);
+        }
+
+        match (h.1 as u64, k.1 as u64) {
+            (_, 0) => Frequency::from_raw(u64::MAX, NonZeroU64::MIN),
+            (0, _) => Frequency::from_raw(1, NonZeroU64::MAX),
+            (count, period) => Frequency::from_raw(count, NonZeroU64::new(period).unwrap()),
+        }
+    }
+
+    /// Returns iterator over `steps` frequencies from `start` to `end` inclusive,
+    /// e.g. to run benchmarks at a range of tick rates.
+    ///
+    /// With [`SweepScale::Linear`] frequencies are evenly spaced and exact,
+    /// unless exact value is not representable and the closest one is used.
+    /// With [`SweepScale::Log`] ratio of successive frequencies is constant.
+    /// Intermediate values are computed with `f64`
+    /// and converted with [`Frequency::from_hz_f64`].
+    /// Logarithmic sweep with zero endpoint is linear.
+    ///
+    /// Both endpoints are exact.
+    /// Yields only `start` if `steps` is 1 and nothing if `steps` is 0.
+    /// If `start` is greater than `end` frequencies go downwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use gametime::{Frequency, SweepScale};
+    /// let rates: Vec<_> = Frequency::sweep(
+    ///     Frequency::from_hz(30),
+    ///     Frequency::from_hz(240),
+    ///     4,
+    ///     SweepScale::Log,
+    /// )
+    /// .collect();
+    ///
+    /// assert_eq!(rates, [30, 60, 120, 240].map(Frequency::from_hz));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn sweep(
+        start: Frequency,
+        end: Frequency,
+        steps: usize,
+        scale: SweepScale,
+    ) -> impl Iterator<Item = Frequency> {
+        let hz = |freq: Frequency| freq.count as f64 * 1e9 / freq.period.get() as f64;
+        let last = steps.saturating_sub(1);
+
+        (0..steps).map(move |i| {
+            if i == 0 {
+                return start;
+            }
+            if i == last {
+                return end;
+            }
+
+            let t = i as f64 / last as f64;
+            let freq = match scale {
+                SweepScale::Log if start.count != 0 && end.count != 0 => {
+                    Frequency::from_hz_f64(hz(start) * (hz(end) / hz(start)).powf(t))
+                }
+                _ => Frequency::lerp(start, end, i as u128, last as u128)
+                    .or_else(|| Frequency::from_hz_f64(hz(start) + (hz(end) - hz(start)) * t)),
+            };
+
+            // Only possible for frequencies close to representable limits.
+            freq.unwrap_or(if i * 2 < last { start } else { end })
+        })
+    }
+
+    /// Returns frequency `i / n` of the way from `start` to `end` exactly.
+    ///
+    /// Returns `None` if it is not representable.
+    #[cfg(feature = "std")]
+    fn lerp(start: Frequency, end: Frequency, i: u128, n: u128) -> Option<Frequency> {
+        let (p, q) = (start.period.get() as u128, end.period.get() as u128);
+        let period = p / crate::gcd_u128(p, q) * q;
+
+        // Counts over common period fit, as multiplier is the other period over gcd.
+        let count = (start.count as u128 * (period / p))
+            .checked_mul(n - i)?
+            .checked_add((end.count as u128 * (period / q)).checked_mul(i)?)?;
+        let period = period.checked_mul(n)?;
+
+        let gcd = crate::gcd_u128(count, period);
+        let count = u64::try_from(count / gcd).ok()?;
+        let period = NonZeroU64::new(u64::try_from(period / gcd).ok()?)?;
+        Some(Frequency { count, period })
+    }
+
+    /// Product of two `u64` always fits into `Elements`.
     #[inline(always)]
     fn elements(&self, span: TimeSpan) -> Elements {
-        Elements(span.as_nanos() * self.count)
+        Elements(span.as_nanos() as u128 * self.count as u128)
     }
 
+    /// Number of whole periods in elements, saturating at `u64::MAX`.
     #[inline(always)]
     fn periods_in_elements(&self, span: Elements) -> u64 {
-        span.0 / self.period
+        (span.0 / self.period.get() as u128)
+            .try_into()
+            .unwrap_or(u64::MAX)
     }
 
     #[inline(always)]
-    fn period(&self) -> Elements {
-        Elements(self.period.get())
+    fn period_elements(&self) -> Elements {
+        Elements(self.period.get() as u128)
     }
 
     #[inline(always)]
     fn periods(&self, count: u64) -> Elements {
-        Elements(self.period.get() * count)
+        Elements(self.period.get() as u128 * count as u128)
     }
 
     #[inline(always)]
     fn until_next(&self, span: Elements) -> Elements {
-        Elements(self.period.get() - span.0 % self.period)
+        let period = self.period.get() as u128;
+        Elements(period - span.0 % period)
     }
 
     /// Span of time in frequency elements rounded up.
     /// Avoid accumulating rounding errors.
+    ///
+    /// Returns `None` if span doesn't fit into `TimeSpan`.
     #[inline(always)]
     fn span(&self, span: Elements) -> Option<TimeSpan> {
         match (span.0, self.count) {
             (0, 0) => Some(TimeSpan::ZERO),
             (_, 0) => None,
-            (span, count) => Some(TimeSpan::new((span + (count - 1)) / count)),
+            (span, count) => Some(TimeSpan::new(span.div_ceil(count as u128).try_into().ok()?)),
         }
     }
 
     /// Span of time in frequency elements rounded down.
     /// Avoid accumulating rounding errors.
+    ///
+    /// Returns `None` if span doesn't fit into `TimeSpan`.
     #[inline(always)]
     fn span_back(&self, span: Elements) -> Option<TimeSpan> {
         match (span.0, self.count) {
             (0, 0) => Some(TimeSpan::ZERO),
             (_, 0) => None,
-            (span, count) => Some(TimeSpan::new(span / count)),
+            (span, count) => Some(TimeSpan::new((span / count as u128).try_into().ok()?)),
         }
     }
 
@@ -116,8 +617,295 @@ impl Frequency {
     pub fn ticker(&self, now: TimeStamp) -> FrequencyTicker {
         FrequencyTicker::new(*self, now)
     }
+
+    /// Returns index of the last tick at or before `stamp`
+    /// for ticks counted from `origin`.
+    ///
+    /// Tick `t` happens when exactly `t` periods elapsed since `origin`,
+    /// rounded up to the nanosecond. Tick `0` is at `origin` itself,
+    /// and ticker created at `origin` emits ticks `1`, `2` and so on.
+    ///
+    /// Returns `0` for stamps before `origin`, saturates at `u64::MAX`.
+    pub fn stamp_to_tick(&self, origin: TimeStamp, stamp: TimeStamp) -> u64 {
+        let Some(elapsed) = stamp.checked_elapsed_since(origin) else {
+            return 0;
+        };
+
+        let ticks = elapsed.as_nanos() as u128 * self.count as u128 / self.period.get() as u128;
+        ticks.try_into().unwrap_or(u64::MAX)
+    }
+
+    /// Returns time stamp of the tick `tick` counted from `origin`.
+    /// See [`Frequency::stamp_to_tick`].
+    ///
+    /// For frequencies up to 1 GHz this is exact inverse of [`Frequency::stamp_to_tick`].
+    /// With higher frequencies multiple ticks may share the same nanosecond.
+    ///
+    /// Returns `None` if time stamp overflows or tick never happens for zero frequency.
+    pub fn tick_to_stamp(&self, origin: TimeStamp, tick: u64) -> Option<TimeStamp> {
+        if tick == 0 {
+            return Some(origin);
+        }
+        if self.count == 0 {
+            return None;
+        }
+
+        let nanos = (tick as u128 * self.period.get() as u128).div_ceil(self.count as u128);
+        origin.add_span(TimeSpan::new(nanos.try_into().ok()?))
+    }
+
+    /// Returns index of the tick nearest to `stamp` counted from `origin`.
+    /// See [`Frequency::stamp_to_tick`].
+    ///
+    /// Stamps exactly between two ticks are rounded to the later one.
+    /// Returns `0` for stamps before `origin`, saturates at `u64::MAX`.
+    pub fn round_stamp_to_tick(&self, origin: TimeStamp, stamp: TimeStamp) -> u64 {
+        let Some(elapsed) = stamp.checked_elapsed_since(origin) else {
+            return 0;
+        };
+
+        let period = self.period.get() as u128;
+        let ticks = (2 * elapsed.as_nanos() as u128 * self.count as u128 + period) / (2 * period);
+        ticks.try_into().unwrap_or(u64::MAX)
+    }
+
+    /// Returns iterator over `count` tickers starting at `now`
+    /// with initial phases evenly distributed across one period.
+    ///
+    /// First ticker is the same as the one returned by [`Frequency::ticker`],
+    /// `i`-th ticker is delayed by `i / count` of the period.
+    /// Phases are computed exactly, so tickers never tick on the same nanosecond
+    /// as long as `count` does not exceed the period length in nanoseconds.
+    pub fn staggered_tickers(
+        &self,
+        now: TimeStamp,
+        count: usize,
+    ) -> impl Iterator<Item = FrequencyTicker> {
+        let freq = *self;
+        (0..count).map(move |i| {
+            let offset = i as u128 * freq.period.get() as u128 / count as u128;
+            // `i < count` hence offset is less than period.
+            let offset = Elements(offset);
+            FrequencyTicker::with_phase_elements(freq, freq.period_elements() + offset, now)
+        })
+    }
+}
+
+impl Frequency {
+    /// Returns value that displays frequency as number of frames per second.
+    ///
+    /// Formatter precision sets number of decimal places, up to 9.
+    /// Without precision up to 2 decimal places are printed and trailing zeros are omitted,
+    /// so 60 Hz is displayed as `60 FPS` and 60000/1001 Hz as `59.94 FPS`.
+    ///
+    /// Value is rounded half-up, i.e. `0.125` with precision 2 is displayed as `0.13`.
+    #[inline(always)]
+    pub fn display_fps(&self) -> impl fmt::Display {
+        FpsDisplay { freq: *self }
+    }
+
+    /// Parses frequency from number of frames per second.
+    ///
+    /// Accepts decimal number with up to 9 fractional digits
+    /// optionally followed by case-insensitive `FPS` suffix,
+    /// e.g. `60`, `60 fps` or `59.94FPS`.
+    /// Decimal values are converted to exact rational frequency.
+    pub fn parse_fps(s: &str) -> Result<Frequency, FrequencyParseErr> {
+        let s = s.trim();
+        let number = match s.len().checked_sub(3) {
+            Some(split) if s.is_char_boundary(split) && s[split..].eq_ignore_ascii_case("fps") => {
+                s[..split].trim_end()
+            }
+            _ => s,
+        };
+
+        let (value, decimals) = parse_decimal(number)?;
+        let period = NonZeroTimeSpan::SECOND * NonZeroU64::new(10u64.pow(decimals)).unwrap();
+        Ok(Frequency::new(value, period))
+    }
+}
+
+struct FpsDisplay {
+    freq: Frequency,
+}
+
+impl fmt::Display for FpsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = f.precision().unwrap_or(2).min(9) as u32;
+
+        // Frames per `10^decimals` seconds rounded half-up.
+        let value = self.freq.scaled_hz(1, decimals);
+        write_decimal(f, value, decimals, "FPS")
+    }
+}
+
+/// SI units of frequency with their size in Hz, from the smallest.
+const HZ_UNITS: [(u64, &str); 4] = [
+    (1, "Hz"),
+    (1_000, "kHz"),
+    (1_000_000, "MHz"),
+    (1_000_000_000, "GHz"),
+];
+
+/// Displays frequency in the largest SI unit that keeps integer part non-zero,
+/// e.g. `60 Hz`, `44.1 kHz` or `2.5 GHz`.
+///
+/// Formatter precision sets number of decimal places, up to 9.
+/// Without precision up to 2 decimal places are printed and trailing zeros are omitted.
+/// Value is rounded half-up.
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = f.precision().unwrap_or(2).min(9) as u32;
+        let scale = 10u128.pow(decimals);
+
+        // Pick unit by rounded value, so 999.999 kHz is displayed as 1 MHz.
+        let (value, unit) = HZ_UNITS
+            .iter()
+            .rev()
+            .map(|&(size, unit)| (self.scaled_hz(size, decimals), unit))
+            .find(|&(value, _)| value >= scale)
+            .unwrap_or((self.scaled_hz(1, decimals), "Hz"));
+
+        write_decimal(f, value, decimals, unit)
+    }
+}
+
+/// Parses frequency from decimal number followed by SI unit.
+///
+/// Accepts up to 9 fractional digits and `Hz`, `kHz`, `MHz` or `GHz` suffix,
+/// e.g. `60 Hz`, `44.1kHz` or `2.5 GHz`.
+/// Number without unit is in Hz.
+///
+/// `Hz` is case-insensitive, but the prefix is not, so millihertz `mHz`
+/// is rejected instead of being read as megahertz. `KHz` is accepted as kilohertz.
+/// Decimal values are converted to exact rational frequency,
+/// so output of `Display` without rounding is parsed back exactly.
+impl core::str::FromStr for Frequency {
+    type Err = FrequencyParseErr;
+
+    fn from_str(s: &str) -> Result<Frequency, FrequencyParseErr> {
+        let s = s.trim();
+        let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+        let (number, unit) = (s[..split].trim_end(), &s[split..]);
+
+        let prefix = match unit.len().checked_sub(2) {
+            _ if unit.is_empty() => "",
+            Some(split)
+                if unit.is_char_boundary(split) && unit[split..].eq_ignore_ascii_case("hz") =>
+            {
+                &unit[..split]
+            }
+            _ => return Err(FrequencyParseErr::InvalidNumber),
+        };
+
+        let size = match prefix {
+            "" => 1,
+            "k" | "K" => 1_000,
+            "M" => 1_000_000,
+            "G" => 1_000_000_000,
+            _ => return Err(FrequencyParseErr::InvalidNumber),
+        };
+
+        // `value / 10^decimals` units is `value` ticks
+        // per `10^(9 + decimals) / size` nanoseconds.
+        // Size is a power of ten not larger than 10^9, so period is exact and not zero.
+        let (value, decimals) = parse_decimal(number)?;
+        let period = 1_000_000_000 * 10u64.pow(decimals) / size;
+        Ok(Frequency::from_raw(value, NonZeroU64::new(period).unwrap()))
+    }
+}
+
+impl Frequency {
+    /// Returns number of `unit` Hz per `10^decimals` seconds rounded half-up.
+    fn scaled_hz(&self, unit: u64, decimals: u32) -> u128 {
+        let num = self.count as u128 * 1_000_000_000 * 10u128.pow(decimals);
+        let den = self.period.get() as u128 * unit as u128;
+        (2 * num + den) / (2 * den)
+    }
+}
+
+/// Writes `value / 10^decimals` followed by the suffix.
+/// Trailing zeros are omitted unless formatter has precision.
+fn write_decimal(
+    f: &mut fmt::Formatter<'_>,
+    value: u128,
+    mut decimals: u32,
+    suffix: &str,
+) -> fmt::Result {
+    let scale = 10u128.pow(decimals);
+    let int = value / scale;
+    let mut fract = value % scale;
+
+    if f.precision().is_none() {
+        while decimals > 0 && fract.is_multiple_of(10) {
+            fract /= 10;
+            decimals -= 1;
+        }
+    }
+
+    if decimals > 0 {
+        write!(
+            f,
+            "{int}.{fract:0width$} {suffix}",
+            width = decimals as usize
+        )
+    } else {
+        write!(f, "{int} {suffix}")
+    }
+}
+
+/// Parses decimal number into mantissa and number of fractional digits.
+fn parse_decimal(s: &str) -> Result<(u64, u32), FrequencyParseErr> {
+    let (int, fract) = match s.split_once('.') {
+        None => (s, ""),
+        Some((int, fract)) => (int, fract),
+    };
+
+    if int.is_empty() && fract.is_empty() {
+        return Err(FrequencyParseErr::InvalidNumber);
+    }
+    if !int.bytes().chain(fract.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(FrequencyParseErr::InvalidNumber);
+    }
+    if fract.len() > 9 {
+        return Err(FrequencyParseErr::TooPrecise);
+    }
+
+    let mut value = 0u64;
+    for b in int.bytes().chain(fract.bytes()) {
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(u64::from(b - b'0')))
+            .ok_or(FrequencyParseErr::Overflow)?;
+    }
+
+    Ok((value, fract.len() as u32))
+}
+
+/// Error returned when parsing frequency fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrequencyParseErr {
+    /// String is not a valid decimal number.
+    InvalidNumber,
+    /// Number has too many fractional digits.
+    TooPrecise,
+    /// Number is too large.
+    Overflow,
+}
+
+impl fmt::Display for FrequencyParseErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumber => f.write_str("Frequency must be a decimal number"),
+            Self::TooPrecise => f.write_str("Frequency may have at most 9 fractional digits"),
+            Self::Overflow => f.write_str("Frequency is too large"),
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for FrequencyParseErr {}
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for Frequency {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -142,16 +930,8 @@ impl<'de> serde::Deserialize<'de> for Frequency {
             let s = String::deserialize(deserializer)?;
 
             match s.split_once("/") {
-                None => {
-                    let count = s
-                        .strip_suffix("Hz")
-                        .ok_or_else(|| serde::de::Error::custom("Wrong frequency format"))?;
-                    let count = count.trim();
-                    let count = count.parse().map_err(serde::de::Error::custom)?;
-
-                    let period = NonZeroU64::new(1).unwrap();
-                    return Ok(Frequency { count, period });
-                }
+                // Decimal number with SI unit, e.g. `1 MHz`.
+                None => s.parse().map_err(serde::de::Error::custom),
 
                 Some((count, s)) => {
                     let count = count.trim();
@@ -162,7 +942,7 @@ impl<'de> serde::Deserialize<'de> for Frequency {
                     let period = period.trim();
                     let period = period.parse().map_err(serde::de::Error::custom)?;
 
-                    return Ok(Frequency { count, period });
+                    Ok(Frequency::from_raw(count, period))
                 }
             }
         } else {
@@ -185,7 +965,7 @@ impl<'de> serde::Deserialize<'de> for Frequency {
                     let period = seq
                         .next_element()?
                         .ok_or_else(|| serde::de::Error::custom("Frequency is empty"))?;
-                    Ok(Frequency { count, period })
+                    Ok(Frequency::from_raw(count, period))
                 }
             }
 
@@ -194,23 +974,49 @@ impl<'de> serde::Deserialize<'de> for Frequency {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Part of a frequency period not covered by whole periods,
+/// in exact units of `1 / count` nanoseconds.
+///
+/// Returned by [`Frequency::periods_and_phase`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PhaseRemainder {
+    elements: u64,
+}
+
+impl PhaseRemainder {
+    /// Zero remainder.
+    pub const ZERO: Self = PhaseRemainder { elements: 0 };
+
+    /// Returns `true` if remainder is zero.
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.elements == 0
+    }
+}
+
+/// Time measured in units of `1 / count` nanoseconds of a frequency,
+/// so that period of the frequency is whole number of elements.
+///
+/// Stored in `u128` so that any `TimeSpan` at any frequency is representable.
+/// Addition saturates, so a tick too far away to be represented
+/// stays at the farthest representable point instead of wrapping around.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
-struct Elements(u64);
+struct Elements(u128);
 
 impl ops::Add for Elements {
     type Output = Self;
 
     #[inline(always)]
     fn add(self, rhs: Self) -> Self::Output {
-        Elements(self.0 + rhs.0)
+        Elements(self.0.saturating_add(rhs.0))
     }
 }
 
 impl ops::AddAssign for Elements {
     #[inline(always)]
     fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
+        self.0 = self.0.saturating_add(rhs.0);
     }
 }
 
@@ -254,6 +1060,19 @@ pub struct FrequencyTicker {
 
     /// Last tick stamp.
     now: TimeStamp,
+
+    /// Number of ticks emitted since creation.
+    tick_index: u64,
+
+    /// Time stamp of the last emitted tick.
+    last_tick: Option<TimeStamp>,
+
+    /// Number of immediate ticks pending.
+    burst: u64,
+
+    /// Number of ticks emitted since creation or last reset of the counter.
+    /// Not part of the ticker state.
+    total: u64,
 }
 
 impl FrequencyTicker {
@@ -268,28 +1087,127 @@ impl FrequencyTicker {
     pub fn with_delay(freq: Frequency, periods: u64, now: TimeStamp) -> Self {
         FrequencyTicker {
             freq,
-            until_next: freq.periods(1 + periods),
+            until_next: freq.periods(periods) + freq.period_elements(),
+            now,
+            tick_index: 0,
+            last_tick: None,
+            burst: 0,
+            total: 0,
+        }
+    }
+
+    /// Creates new ticker with given frequency that starts `phase` into the first period,
+    /// e.g. with phase `0.3` the first tick happens after 70% of a period.
+    ///
+    /// `phase` is clamped to `[0, 1)`, NaN is treated as zero.
+    /// Phase of zero is equivalent to [`FrequencyTicker::new`].
+    /// Useful for staggering tickers with the same frequency.
+    #[inline(always)]
+    pub fn with_phase(freq: Frequency, phase: f32, now: TimeStamp) -> Self {
+        let period = freq.period_elements();
+        let phase = if phase > 0.0 { phase as f64 } else { 0.0 };
+
+        // At least one element must remain until the first tick.
+        let passed = ((period.0 as f64 * phase) as u128).min(period.0 - 1);
+        FrequencyTicker::with_phase_elements(freq, period - Elements(passed), now)
+    }
+
+    #[inline(always)]
+    fn with_phase_elements(freq: Frequency, until_next: Elements, now: TimeStamp) -> Self {
+        FrequencyTicker {
+            freq,
+            until_next,
             now,
+            tick_index: 0,
+            last_tick: None,
+            burst: 0,
+            total: 0,
+        }
+    }
+
+    /// Returns snapshot of the ticker state.
+    ///
+    /// Tickers with equal snapshots emit exactly the same ticks
+    /// when advanced identically.
+    #[inline(always)]
+    pub fn snapshot(&self) -> FrequencyTickerState {
+        FrequencyTickerState {
+            freq: self.freq,
+            until_next: self.until_next,
+            now: self.now,
+            tick_index: self.tick_index,
+            last_tick: self.last_tick,
+            burst: self.burst,
+        }
+    }
+
+    /// Restores ticker from the snapshot.
+    #[inline(always)]
+    pub fn restore(state: FrequencyTickerState) -> Self {
+        FrequencyTicker {
+            freq: state.freq,
+            until_next: state.until_next,
+            now: state.now,
+            tick_index: state.tick_index,
+            last_tick: state.last_tick,
+            burst: state.burst,
+            total: 0,
         }
     }
 
     /// Returns next timestamp when next tick will happen.
+    ///
+    /// Returns current time stamp while burst ticks are pending.
+    /// Returns `None` if next tick never happens or is not representable.
     #[inline(always)]
     pub fn next_tick(&self) -> Option<TimeStamp> {
-        Some(self.now + self.freq.span(self.until_next)?)
+        if self.burst > 0 {
+            return Some(self.now);
+        }
+        self.now.add_span(self.freq.span(self.until_next)?)
+    }
+
+    /// Schedules `count` immediate ticks.
+    ///
+    /// They are emitted by the next advancement at ticker's current time stamp
+    /// with zero step, before periodic ticks.
+    /// Periodic ticks are not shifted by the burst and keep reporting
+    /// steps relative to the previous periodic tick.
+    ///
+    /// Pending burst ticks are kept when frequency changes.
+    /// Burst ticks are not un-fired by [`FrequencyTicker::rewind`].
+    #[inline(always)]
+    pub fn burst(&mut self, count: u64) {
+        self.burst = self.burst.saturating_add(count);
+    }
+
+    /// Returns number of pending burst ticks.
+    #[inline(always)]
+    pub fn pending_burst(&self) -> u64 {
+        self.burst
     }
 
     /// Advances ticker forward for `span` and returns iterator over ticks
     /// since last advancement.
     #[inline(always)]
     pub fn ticks(&mut self, step: TimeSpan) -> FrequencyTickerIter {
+        let iter = self.advance(step);
+        self.total = self.total.saturating_add(iter.ticks());
+        iter
+    }
+
+    /// Advances ticker forward for `span` and returns iterator over ticks
+    /// without counting them in [`FrequencyTicker::total_ticks`].
+    #[inline(always)]
+    fn advance(&mut self, step: TimeSpan) -> FrequencyTickerIter {
         let span = self.freq.elements(step);
 
+        let burst = core::mem::take(&mut self.burst);
         let iter = FrequencyTickerIter {
             span,
             freq: self.freq,
             until_next: self.until_next,
-            accumulated: 0,
+            accumulated: burst,
             now: self.now,
         };
 
@@ -299,13 +1217,208 @@ impl FrequencyTicker {
             self.until_next -= span;
         }
 
+        let burst_now = self.now;
         self.now += step;
 
+        // Tick index saturates for adversarially high frequencies.
+        let ticks = iter.ticks();
+        self.tick_index = self.tick_index.saturating_add(ticks);
+
+        if ticks > burst {
+            let since_last = self
+                .freq
+                .span_back(self.freq.period_elements() - self.until_next)
+                .unwrap_or(TimeSpan::ZERO);
+            self.last_tick =
+                TimeStamp::from_elapsed(self.now.nanos_since_start() - since_last.as_nanos());
+        } else if burst > 0 {
+            self.last_tick = Some(burst_now);
+        }
+
         iter
     }
 
-    /// Advances ticker forward to `now` and returns number of ticks
-    /// since last advancement.
+    /// Advances ticker forward for `span` and returns iterator over ticks
+    /// since last advancement and exact time remaining until the next tick.
+    ///
+    /// Remaining time is the numerator of nanoseconds over frequency count.
+    /// It saturates at `u64::MAX` while long initial delay is not over.
+    #[inline(always)]
+    pub fn ticks_with_remainder(&mut self, step: TimeSpan) -> (FrequencyTickerIter, u64) {
+        let iter = self.ticks(step);
+        (iter, self.until_next.0.try_into().unwrap_or(u64::MAX))
+    }
+
+    /// Advances ticker forward for `span` and returns iterator over ticks
+    /// since last advancement and progress towards the next tick in `[0, 1)`.
+    ///
+    /// Progress is the interpolation factor between the last and the next tick
+    /// for fixed time step rendering.
+    /// It is 0 right after a tick and while delay of the ticker is not over.
+    #[inline(always)]
+    pub fn ticks_with_alpha(&mut self, step: TimeSpan) -> (FrequencyTickerIter, f32) {
+        let iter = self.ticks(step);
+        (iter, self.alpha())
+    }
+
+    /// Returns progress towards the next tick in `[0, 1)`.
+    fn alpha(&self) -> f32 {
+        let alpha = phase_fraction(self.freq, self.until_next);
+        (alpha as f32).min(1.0f32.next_down())
+    }
+
+    /// Advances ticker forward for `span` and returns iterator over ticks
+    /// since last advancement tagged with `source` id and their indices.
+    #[inline(always)]
+    pub fn ticks_tagged(
+        &mut self,
+        step: TimeSpan,
+        source: u32,
+    ) -> impl Iterator<Item = TaggedTick> {
+        self.ticks_indexed(step)
+            .map(move |tick| TaggedTick::new(source, tick.index, tick.step))
+    }
+
+    /// Advances ticker forward for `span` and returns iterator over ticks
+    /// since last advancement paired with their indices.
+    #[inline(always)]
+    pub fn ticks_indexed(&mut self, step: TimeSpan) -> FrequencyTickerIndexedIter {
+        let index = self.tick_index;
+        FrequencyTickerIndexedIter {
+            iter: self.ticks(step),
+            index,
+        }
+    }
+
+    /// Advances ticker forward to `target` time stamp and returns iterator over ticks
+    /// since last advancement.
+    ///
+    /// This is absolute-time counterpart of [`FrequencyTicker::ticks`].
+    /// If `target` is not after ticker's current time stamp,
+    /// ticker is not changed and returned iterator is empty.
+    #[inline(always)]
+    pub fn ticks_until(&mut self, target: TimeStamp) -> FrequencyTickerIter {
+        let step = target
+            .checked_elapsed_since(self.now)
+            .unwrap_or(TimeSpan::ZERO);
+        self.ticks(step)
+    }
+
+    /// Moves ticker from one time line to another preserving its phase.
+    ///
+    /// `old_now` and `new_now` are the same moment on old and new time lines.
+    /// Useful to keep ticker consistent after clock reset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if ticker's time stamp is not representable on the new time line.
+    pub fn rebase(&mut self, old_now: TimeStamp, new_now: TimeStamp) {
+        self.now = self
+            .now
+            .rebase(old_now, new_now)
+            .expect("ticker is not representable on the new time line");
+        self.last_tick = self
+            .last_tick
+            .and_then(|stamp| stamp.rebase(old_now, new_now));
+    }
+
+    /// Returns time stamp ticker is advanced to.
+    #[inline(always)]
+    pub fn now(&self) -> TimeStamp {
+        self.now
+    }
+
+    /// Moves ticker backward for `step`.
+    ///
+    /// Ticks emitted within rewound span are un-fired
+    /// and will be emitted again when ticker advances over them.
+    /// Returns number of un-fired ticks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is greater than time elapsed since start.
+    pub fn rewind(&mut self, step: TimeSpan) -> u64 {
+        let span = self.freq.elements(step);
+        let period = self.freq.period_elements();
+
+        let mut unfired = 0;
+        if self.tick_index > 0 && self.until_next <= period {
+            let since_last = period - self.until_next;
+            if span > since_last {
+                let ticks = ((span - since_last).0 - 1) / period.0 + 1;
+                unfired = ticks.min(self.tick_index as u128) as u64;
+            }
+        }
+
+        // Time to the next tick grows by rewound span, except unfired periods.
+        let unfired_elements = self.freq.periods(unfired);
+        self.until_next = if unfired_elements <= span {
+            self.until_next + (span - unfired_elements)
+        } else {
+            self.until_next - (unfired_elements - span)
+        };
+        self.now = self
+            .now
+            .nanos_since_start()
+            .checked_sub(step.as_nanos())
+            .and_then(TimeStamp::from_elapsed)
+            .expect("overflow when rewinding ticker past the start");
+
+        if unfired > 0 {
+            self.tick_index -= unfired;
+            self.last_tick = match self.tick_index {
+                0 => None,
+                _ => {
+                    let since_last = self
+                        .freq
+                        .span_back(period - self.until_next)
+                        .unwrap_or(TimeSpan::ZERO);
+                    TimeStamp::from_elapsed(self.now.nanos_since_start() - since_last.as_nanos())
+                }
+            };
+        }
+
+        unfired
+    }
+
+    /// Returns number of ticks emitted since creation.
+    /// This is also the index of the next tick.
+    #[inline(always)]
+    pub fn tick_index(&self) -> u64 {
+        self.tick_index
+    }
+
+    /// Returns number of ticks emitted since creation
+    /// or the last [`FrequencyTicker::reset_total`], including burst ticks.
+    ///
+    /// Ticks are counted when they are delivered to the caller.
+    /// Ticks dropped by [`FrequencyTicker::ticks_into`] and skipped after a break
+    /// in [`FrequencyTicker::with_ticks_until`] are not counted.
+    ///
+    /// Unlike [`FrequencyTicker::tick_index`], ticks un-fired by [`FrequencyTicker::rewind`]
+    /// are not subtracted and are counted again when emitted again.
+    /// The counter is not part of ticker state,
+    /// restored and deserialized tickers start from zero.
+    /// Saturates at `u64::MAX`.
+    #[inline(always)]
+    pub fn total_ticks(&self) -> u64 {
+        self.total
+    }
+
+    /// Resets counter of [`FrequencyTicker::total_ticks`] to zero.
+    #[inline(always)]
+    pub fn reset_total(&mut self) {
+        self.total = 0;
+    }
+
+    /// Returns time stamp of the last emitted tick.
+    #[inline(always)]
+    pub fn last_tick(&self) -> Option<TimeStamp> {
+        self.last_tick
+    }
+
+    /// Advances ticker forward to `now` and returns number of ticks
+    /// since last advancement.
     #[inline(always)]
     pub fn tick_count(&mut self, step: TimeSpan) -> u64 {
         self.ticks(step).ticks()
@@ -318,6 +1431,48 @@ impl FrequencyTicker {
         self.ticks(step).for_each(f)
     }
 
+    /// Advances ticker forward for `step` and calls provided closure with ticks
+    /// since last advancement until it breaks.
+    ///
+    /// Ticker is advanced for the whole `step` regardless,
+    /// ticks after the break are skipped.
+    #[inline(always)]
+    pub fn with_ticks_until(
+        &mut self,
+        step: TimeSpan,
+        f: impl FnMut(ClockStep) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        let mut f = f;
+        let mut delivered = 0;
+        let flow = self.advance(step).try_for_each(|tick| {
+            delivered += 1;
+            f(tick)
+        });
+        self.total = self.total.saturating_add(delivered);
+        flow
+    }
+
+    /// Advances ticker forward for `step` and writes ticks since last advancement
+    /// into `buf` without allocation.
+    ///
+    /// Returns number of ticks written and number of ticks that didn't fit.
+    /// Ticks that didn't fit are dropped: ticker is advanced for the whole `step`
+    /// and they are counted in [`FrequencyTicker::tick_index`],
+    /// so the next call continues in sync with time.
+    #[inline(always)]
+    pub fn ticks_into(&mut self, step: TimeSpan, buf: &mut [ClockStep]) -> (usize, u64) {
+        let mut iter = self.advance(step);
+        let total = iter.ticks();
+
+        let mut written = 0;
+        for (slot, tick) in buf.iter_mut().zip(&mut iter) {
+            *slot = tick;
+            written += 1;
+        }
+        self.total = self.total.saturating_add(written as u64);
+        (written, total - written as u64)
+    }
+
     /// Returns current frequency of the ticker.
     #[inline(always)]
     pub fn frequency(&self) -> Frequency {
@@ -328,13 +1483,344 @@ impl FrequencyTicker {
     #[inline(always)]
     pub fn set_frequency(&mut self, freq: Frequency) {
         self.freq = freq;
-        let period = freq.period();
+        let period = freq.period_elements();
         if self.until_next > period {
             self.until_next = period;
         }
     }
 }
 
+/// Serializes exact state of the ticker.
+///
+/// Human-readable form is frequency-independent:
+/// time stamps are nanoseconds since start, and time of the next tick
+/// is stored as whole nanoseconds plus fraction of a nanosecond, e.g.
+/// `{ "freq": "3/1000000000 Hz", "now": 1000, "next_tick": 333334333, "next_tick_frac": "1/3", ... }`.
+///
+/// Compact form stores internal representation as is.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FrequencyTicker {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let (next_tick, frac) = match self.freq.count {
+                0 => (None, None),
+                count => {
+                    let next_tick = u64::try_from(self.until_next.0 / count as u128)
+                        .ok()
+                        .and_then(|nanos| self.now.add_span(TimeSpan::new(nanos)))
+                        .ok_or_else(|| serde::ser::Error::custom("next tick is out of range"))?;
+                    let frac = format!("{}/{}", self.until_next.0 % count as u128, count);
+                    (Some(next_tick.nanos_since_start()), Some(frac))
+                }
+            };
+
+            let mut serializer = serializer.serialize_struct("FrequencyTicker", 7)?;
+            serializer.serialize_field("freq", &self.freq)?;
+            serializer.serialize_field("now", &self.now.nanos_since_start())?;
+            serializer.serialize_field("next_tick", &next_tick)?;
+            serializer.serialize_field("next_tick_frac", &frac)?;
+            serializer.serialize_field("tick_index", &self.tick_index)?;
+            serializer.serialize_field(
+                "last_tick",
+                &self.last_tick.map(TimeStamp::nanos_since_start),
+            )?;
+            serializer.serialize_field("burst", &self.burst)?;
+            serializer.end()
+        } else {
+            let mut serializer = serializer.serialize_tuple_struct("FrequencyTicker", 6)?;
+            serializer.serialize_field(&self.freq)?;
+            serializer.serialize_field(&self.until_next.0)?;
+            serializer.serialize_field(&self.now.as_raw())?;
+            serializer.serialize_field(&self.tick_index)?;
+            serializer.serialize_field(&self.last_tick.map(TimeStamp::as_raw))?;
+            serializer.serialize_field(&self.burst)?;
+            serializer.end()
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FrequencyTicker {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        fn stamp<E: Error>(nanos: u64) -> Result<TimeStamp, E> {
+            TimeStamp::from_elapsed(nanos).ok_or_else(|| E::custom("time stamp is out of range"))
+        }
+
+        /// Converts time of the next tick into elements until next tick.
+        fn until_next<E: Error>(
+            freq: Frequency,
+            now: TimeStamp,
+            next_tick: Option<TimeStamp>,
+            frac: Option<&str>,
+        ) -> Result<Elements, E> {
+            let count = freq.count as u128;
+            if count == 0 {
+                return Ok(freq.period_elements());
+            }
+
+            let next_tick = next_tick.ok_or_else(|| E::missing_field("next_tick"))?;
+            let nanos = next_tick
+                .checked_elapsed_since(now)
+                .ok_or_else(|| E::custom("next tick is before now"))?;
+
+            let (num, denom) = match frac {
+                None => (0, 1),
+                Some(frac) => {
+                    let (num, denom) = frac
+                        .split_once('/')
+                        .ok_or_else(|| E::custom("wrong fraction format"))?;
+                    let num: u128 = num.trim().parse().map_err(E::custom)?;
+                    let denom: NonZeroU64 = denom.trim().parse().map_err(E::custom)?;
+                    (num, denom.get() as u128)
+                }
+            };
+            if num >= denom || !(num * count).is_multiple_of(denom) {
+                return Err(E::custom(
+                    "fraction of nanosecond is not representable at this frequency",
+                ));
+            }
+
+            let elements = nanos.as_nanos() as u128 * count + num * count / denom;
+            match elements {
+                0 => Err(E::custom("next tick must be after now")),
+                elements => Ok(Elements(elements)),
+            }
+        }
+
+        struct HumanVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for HumanVisitor {
+            type Value = FrequencyTicker;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a frequency ticker")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut freq = None;
+                let mut now = None;
+                let mut next_tick = None;
+                let mut frac: Option<String> = None;
+                let mut tick_index = 0;
+                let mut last_tick = None;
+                let mut burst = 0;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match &*key {
+                        "freq" => freq = Some(map.next_value()?),
+                        "now" => now = Some(stamp(map.next_value()?)?),
+                        "next_tick" => {
+                            next_tick = map.next_value::<Option<u64>>()?.map(stamp).transpose()?
+                        }
+                        "next_tick_frac" => frac = map.next_value()?,
+                        "tick_index" => tick_index = map.next_value()?,
+                        "last_tick" => {
+                            last_tick = map.next_value::<Option<u64>>()?.map(stamp).transpose()?
+                        }
+                        "burst" => burst = map.next_value()?,
+                        _ => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                let freq: Frequency = freq.ok_or_else(|| A::Error::missing_field("freq"))?;
+                let now = now.ok_or_else(|| A::Error::missing_field("now"))?;
+
+                Ok(FrequencyTicker {
+                    freq,
+                    until_next: until_next(freq, now, next_tick, frac.as_deref())?,
+                    now,
+                    tick_index,
+                    last_tick,
+                    burst,
+                    total: 0,
+                })
+            }
+        }
+
+        struct CompactVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for CompactVisitor {
+            type Value = FrequencyTicker;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a tuple of 6 elements")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let freq: Frequency = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                let until_next: u128 = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+                let now: NonZeroU64 = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(2, &self))?;
+                let tick_index = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(3, &self))?;
+                let last_tick: Option<NonZeroU64> = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(4, &self))?;
+                let burst = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(5, &self))?;
+
+                if until_next == 0 {
+                    return Err(A::Error::custom("next tick must be after now"));
+                }
+
+                Ok(FrequencyTicker {
+                    freq,
+                    until_next: Elements(until_next),
+                    now: TimeStamp::from_raw(now),
+                    tick_index,
+                    last_tick: last_tick.map(TimeStamp::from_raw),
+                    burst,
+                    total: 0,
+                })
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_map(HumanVisitor)
+        } else {
+            deserializer.deserialize_tuple_struct("FrequencyTicker", 6, CompactVisitor)
+        }
+    }
+}
+
+/// Converts stream of time spans into sample counts at given frequency.
+///
+/// Fraction of a sample left over from each span is carried to the next one,
+/// so total number of samples is exact for the total time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SampleClock {
+    freq: Frequency,
+
+    /// Time accumulated towards the next sample.
+    phase: PhaseRemainder,
+
+    total: u64,
+}
+
+impl SampleClock {
+    /// Returns new sample clock for given frequency.
+    #[inline(always)]
+    pub fn new(freq: Frequency) -> Self {
+        SampleClock {
+            freq,
+            phase: PhaseRemainder::ZERO,
+            total: 0,
+        }
+    }
+
+    /// Returns frequency of the sample clock.
+    #[inline(always)]
+    pub fn frequency(&self) -> Frequency {
+        self.freq
+    }
+
+    /// Advances clock by `span` and returns number of samples in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if number of samples doesn't fit into `u64`,
+    /// see [`Frequency::periods_and_phase`].
+    pub fn advance(&mut self, span: TimeSpan) -> u64 {
+        let (samples, phase) = self.freq.periods_and_phase(span, self.phase);
+        self.phase = phase;
+        self.total += samples;
+        samples
+    }
+
+    /// Returns total number of samples since creation.
+    #[inline(always)]
+    pub fn total_samples(&self) -> u64 {
+        self.total
+    }
+}
+
+/// Snapshot of [`FrequencyTicker`] state.
+///
+/// Can be compared and hashed to check that tickers are in sync,
+/// and used to restore ticker with [`FrequencyTicker::restore`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FrequencyTickerState {
+    freq: Frequency,
+    until_next: Elements,
+    now: TimeStamp,
+    tick_index: u64,
+    last_tick: Option<TimeStamp>,
+    burst: u64,
+}
+
+impl FrequencyTickerState {
+    /// Returns frequency of the ticker.
+    #[inline(always)]
+    pub fn frequency(&self) -> Frequency {
+        self.freq
+    }
+
+    /// Returns current time stamp of the ticker.
+    #[inline(always)]
+    pub fn now(&self) -> TimeStamp {
+        self.now
+    }
+
+    /// Returns number of ticks emitted by the ticker.
+    #[inline(always)]
+    pub fn tick_index(&self) -> u64 {
+        self.tick_index
+    }
+}
+
+impl fmt::Debug for FrequencyTicker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrequencyTicker")
+            .field("freq", &HzDebug(self.freq))
+            .field("now", &self.now)
+            .field("phase", &phase_fraction(self.freq, self.until_next))
+            .field("tick_index", &self.tick_index)
+            .field("burst", &self.burst)
+            .finish()
+    }
+}
+
+/// Returns fraction of the current period passed with `until_next` elements left.
+/// Zero while delay is not over.
+fn phase_fraction(freq: Frequency, until_next: Elements) -> f64 {
+    let period = freq.period_elements();
+    if until_next >= period {
+        return 0.0;
+    }
+
+    (period - until_next).0 as f64 / period.0 as f64
+}
+
+/// Prints frequency in Hz.
+struct HzDebug(Frequency);
+
+impl fmt::Debug for HzDebug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hz = self.0.count as f64 * 1_000_000_000.0 / self.0.period.get() as f64;
+        write!(f, "{} Hz", hz)
+    }
+}
+
 /// Iterator over ticks from `FrequencyTicker`.
 pub struct FrequencyTickerIter {
     span: Elements,
@@ -345,15 +1831,40 @@ pub struct FrequencyTickerIter {
 }
 
 impl FrequencyTickerIter {
-    /// Returns number of ticks this iterator will produce.
+    /// Returns number of ticks this iterator will produce,
+    /// saturating at `u64::MAX`.
     #[inline]
     pub fn ticks(&self) -> u64 {
+        self.ticks_exact().try_into().unwrap_or(u64::MAX)
+    }
+
+    /// Returns number of ticks this iterator will produce without saturation.
+    #[inline]
+    fn ticks_exact(&self) -> u128 {
         if self.span < self.until_next {
-            return 0;
+            return self.accumulated as u128;
         }
 
+        // Number of periods is less than `2^128 / 1`, so only the sum may saturate.
         let span = self.span - self.until_next;
-        1 + self.freq.periods_in_elements(span)
+        (self.accumulated as u128 + 1).saturating_add(span.0 / self.freq.period.get() as u128)
+    }
+
+    /// Clears the buffer and fills it with ticks from this iterator.
+    /// Returns filled slice.
+    ///
+    /// Buffer is reserved for number of ticks from the size hint,
+    /// so reusing the same buffer avoids allocations once it is large enough.
+    #[cfg(feature = "alloc")]
+    pub fn collect_into(self, buf: &mut Vec<ClockStep>) -> &[ClockStep] {
+        buf.clear();
+
+        // Too many ticks to reserve memory for.
+        if let (lower, Some(_)) = self.size_hint() {
+            buf.reserve(lower);
+        }
+        buf.extend(self);
+        buf
     }
 }
 
@@ -388,18 +1899,19 @@ impl Iterator for FrequencyTickerIter {
             "Span cannot be less then span until next tick"
         );
 
-        let since_last = if self.until_next <= self.freq.period() {
+        let since_last = if self.until_next <= self.freq.period_elements() {
             self.freq
-                .span_back(self.freq.period() - self.until_next)
+                .span_back(self.freq.period_elements() - self.until_next)
                 .unwrap_or(TimeSpan::ZERO)
         } else {
             TimeSpan::ZERO
         };
 
-        self.until_next += self.freq.period();
+        self.until_next += self.freq.period_elements();
 
         if self.until_next < next_elements {
-            self.accumulated = self
+            // Ticks that happen within the same nanosecond.
+            self.accumulated = 1 + self
                 .freq
                 .periods_in_elements(next_elements - self.until_next);
 
@@ -418,10 +1930,220 @@ impl Iterator for FrequencyTickerIter {
             step,
         })
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.ticks_exact()) {
+            Ok(ticks) => (ticks, Some(ticks)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl fmt::Debug for FrequencyTickerIter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrequencyTickerIter")
+            .field("freq", &HzDebug(self.freq))
+            .field("now", &self.now)
+            .field("phase", &phase_fraction(self.freq, self.until_next))
+            .field("ticks", &self.ticks())
+            .finish()
+    }
 }
 
 impl FusedIterator for FrequencyTickerIter {}
 
+/// Tick emitted by `FrequencyTicker` paired with its index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tick {
+    /// Clock step of the tick.
+    pub step: ClockStep,
+
+    /// Index of the tick since ticker creation.
+    pub index: u64,
+}
+
+/// Tick emitted by `FrequencyTicker` tagged with id of the ticker
+/// to be sent across threads or queues.
+///
+/// With `bytemuck` feature it implements `bytemuck::NoUninit`
+/// and `bytemuck::CheckedBitPattern`, so it can be cast to bytes
+/// and decoded back on the receiving side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct TaggedTick {
+    /// Clock step of the tick.
+    pub step: ClockStep,
+
+    /// Index of the tick since ticker creation.
+    pub index: u64,
+
+    /// Id of the ticker that emitted the tick.
+    pub source: u32,
+
+    /// Explicit padding, so the struct has no uninitialized bytes.
+    _padding: u32,
+}
+
+impl TaggedTick {
+    /// Returns new tagged tick.
+    #[inline(always)]
+    pub fn new(source: u32, index: u64, step: ClockStep) -> Self {
+        TaggedTick {
+            step,
+            index,
+            source,
+            _padding: 0,
+        }
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::NoUninit for TaggedTick {}
+
+/// Bits of the [`TaggedTick`] with the same layout.
+#[cfg(feature = "bytemuck")]
+#[derive(Clone, Copy)]
+#[repr(C)]
+#[doc(hidden)]
+pub struct TaggedTickBits {
+    now: u64,
+    step: u64,
+    index: u64,
+    source: u32,
+    padding: u32,
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for TaggedTickBits {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for TaggedTickBits {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::CheckedBitPattern for TaggedTick {
+    type Bits = TaggedTickBits;
+
+    /// Time stamp must not be zero and padding must be zeroed,
+    /// so decoded ticks compare equal to the original ones.
+    #[inline(always)]
+    fn is_valid_bit_pattern(bits: &TaggedTickBits) -> bool {
+        bits.now != 0 && bits.padding == 0
+    }
+}
+
+/// Set of tickers identified by source ids.
+///
+/// Advances all tickers together and emits their ticks tagged with source ids.
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct TickerSet {
+    /// Tickers sorted by source id.
+    tickers: Vec<(u32, FrequencyTicker)>,
+}
+
+#[cfg(feature = "alloc")]
+impl TickerSet {
+    /// Returns new empty set.
+    #[inline(always)]
+    pub fn new() -> Self {
+        TickerSet {
+            tickers: Vec::new(),
+        }
+    }
+
+    /// Inserts ticker with given source id.
+    /// Returns previous ticker with the same id.
+    pub fn insert(&mut self, source: u32, ticker: FrequencyTicker) -> Option<FrequencyTicker> {
+        match self.tickers.binary_search_by_key(&source, |(id, _)| *id) {
+            Ok(index) => Some(core::mem::replace(&mut self.tickers[index].1, ticker)),
+            Err(index) => {
+                self.tickers.insert(index, (source, ticker));
+                None
+            }
+        }
+    }
+
+    /// Removes ticker with given source id.
+    pub fn remove(&mut self, source: u32) -> Option<FrequencyTicker> {
+        let index = self
+            .tickers
+            .binary_search_by_key(&source, |(id, _)| *id)
+            .ok()?;
+        Some(self.tickers.remove(index).1)
+    }
+
+    /// Returns ticker with given source id.
+    pub fn get(&self, source: u32) -> Option<&FrequencyTicker> {
+        let index = self
+            .tickers
+            .binary_search_by_key(&source, |(id, _)| *id)
+            .ok()?;
+        Some(&self.tickers[index].1)
+    }
+
+    /// Returns ticker with given source id.
+    pub fn get_mut(&mut self, source: u32) -> Option<&mut FrequencyTicker> {
+        let index = self
+            .tickers
+            .binary_search_by_key(&source, |(id, _)| *id)
+            .ok()?;
+        Some(&mut self.tickers[index].1)
+    }
+
+    /// Returns number of tickers in the set.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.tickers.len()
+    }
+
+    /// Returns `true` if set contains no tickers.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.tickers.is_empty()
+    }
+
+    /// Advances all tickers forward for `step` and returns iterator over their ticks
+    /// tagged with source ids in chronological order.
+    ///
+    /// Simultaneous ticks are ordered by source id.
+    pub fn advance_tagged(&mut self, step: TimeSpan) -> impl Iterator<Item = TaggedTick> {
+        let mut ticks = Vec::new();
+        for (source, ticker) in &mut self.tickers {
+            ticks.extend(ticker.ticks_tagged(step, *source));
+        }
+
+        // Stable sort keeps ticks of one source in order of their indices.
+        ticks.sort_by_key(|tick| (tick.step.now, tick.source));
+        ticks.into_iter()
+    }
+}
+
+/// Iterator over indexed ticks from `FrequencyTicker`.
+pub struct FrequencyTickerIndexedIter {
+    iter: FrequencyTickerIter,
+    index: u64,
+}
+
+impl Iterator for FrequencyTickerIndexedIter {
+    type Item = Tick;
+
+    #[inline]
+    fn next(&mut self) -> Option<Tick> {
+        let step = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some(Tick { step, index })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl FusedIterator for FrequencyTickerIndexedIter {}
+
 /// This trait adds methods to integers to convert values into `Frequency`s.
 pub trait FrequencyNumExt {
     /// Convert integer value into `Frequency` with that amount of Herz.
@@ -552,3 +2274,1511 @@ fn test_hz() {
         ]
     );
 }
+
+#[test]
+fn test_tick_index() {
+    let mut ticker = Frequency::from_hz(3).ticker(TimeStamp::start());
+    assert_eq!(ticker.tick_index(), 0);
+    assert_eq!(ticker.last_tick(), None);
+
+    let mut expected = 0;
+    for step in [400, 100, 1_500, 0, 333, 2_000] {
+        let ticks = ticker
+            .ticks_indexed(TimeSpan::MILLISECOND * step)
+            .collect::<Vec<_>>();
+
+        for tick in &ticks {
+            assert_eq!(tick.index, expected);
+            expected += 1;
+        }
+
+        assert_eq!(ticker.tick_index(), expected);
+        if let Some(tick) = ticks.last() {
+            assert_eq!(ticker.last_tick(), Some(tick.step.now));
+        }
+    }
+
+    ticker.set_frequency(Frequency::from_hz(7));
+    for tick in ticker.ticks_indexed(TimeSpan::SECOND) {
+        assert_eq!(tick.index, expected);
+        expected += 1;
+    }
+    assert_eq!(ticker.tick_index(), expected);
+}
+
+#[test]
+fn test_last_tick() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let mut ticker = FrequencyTicker::new(
+        Frequency::new(3, NonZeroU64::new(10).unwrap().nanoseconds()),
+        TimeStamp::start(),
+    );
+
+    for _ in 0..100 {
+        let last = ticker.ticks(TimeSpan::NANOSECOND * 7).last();
+        if let Some(last) = last {
+            assert_eq!(ticker.last_tick(), Some(last.now));
+        }
+    }
+}
+
+#[test]
+fn test_ticks_with_remainder() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let freq = Frequency::new(3, NonZeroU64::new(10).unwrap().nanoseconds());
+    let mut ticker = FrequencyTicker::new(freq, TimeStamp::start());
+
+    for step in [1, 4, 2, 10, 3, 0, 7] {
+        let (ticks, remainder) = ticker.ticks_with_remainder(TimeSpan::NANOSECOND * step);
+        assert_eq!(ticks.ticks(), ticks.count() as u64);
+        assert_eq!(remainder as u128, ticker.until_next.0);
+        assert!(remainder > 0 && remainder <= freq.period.get());
+        assert_eq!(
+            ticker.next_tick().unwrap(),
+            ticker.now + TimeSpan::new(remainder.div_ceil(freq.count)),
+        );
+    }
+}
+
+#[test]
+fn test_rewind() {
+    let mut ticker = Frequency::from_hz(3).ticker(TimeStamp::start());
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND), 3);
+
+    assert_eq!(ticker.rewind(TimeSpan::MILLISECOND * 500), 2);
+    assert_eq!(ticker.tick_index(), 1);
+    assert_eq!(
+        ticker.last_tick(),
+        Some(TimeStamp::start() + TimeSpan::new(333_333_334))
+    );
+
+    let ticks = ticker
+        .ticks(TimeSpan::MILLISECOND * 500)
+        .collect::<Vec<_>>();
+    assert_eq!(ticks.len(), 2);
+    assert_eq!(
+        ticks[0].now,
+        TimeStamp::start() + TimeSpan::new(666_666_667)
+    );
+    assert_eq!(ticks[1].now, TimeStamp::start() + TimeSpan::SECOND);
+
+    // Rewinding before the first tick un-fires all ticks.
+    assert_eq!(ticker.rewind(TimeSpan::SECOND), 3);
+    assert_eq!(ticker.tick_index(), 0);
+    assert_eq!(ticker.last_tick(), None);
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND), 3);
+}
+
+#[test]
+fn test_elements_saturate() {
+    let max = Elements(u128::MAX);
+    assert_eq!(max + Elements(1), max);
+
+    let mut elements = Elements(u128::MAX - 1);
+    elements += Elements(u64::MAX as u128);
+    assert_eq!(elements, max);
+
+    // Far-delayed ticker stays far away when rewound further.
+    let freq = Frequency::from_raw(u64::MAX, NonZeroU64::new(u64::MAX - 1).unwrap());
+    let mut ticker = FrequencyTicker::with_delay(freq, u64::MAX, TimeStamp::never());
+    assert_eq!(ticker.rewind(TimeSpan::MAX - TimeSpan::NANOSECOND), 0);
+    assert_eq!(ticker.until_next, max);
+    assert_eq!(ticker.tick_count(TimeSpan::MAX - TimeSpan::NANOSECOND), 0);
+}
+
+#[test]
+fn test_clamp_rate() {
+    let rate = |nom, denom| Rate::new(nom, NonZeroU64::new(denom).unwrap());
+
+    assert_eq!(
+        Frequency::from_khz(1).clamp_rate(rate(1000, 1)),
+        Frequency::from_mhz(1)
+    );
+    assert_eq!(
+        Frequency::from_hz(3).clamp_rate(rate(2, 6)),
+        Frequency::from_hz(1)
+    );
+    assert_eq!(Frequency::from_hz(60).clamp_rate(Rate::ZERO).count(), 0);
+
+    // Too fast and too slow are clamped.
+    let fastest = Frequency::from_raw(u64::MAX, NonZeroU64::MIN);
+    let slowest = Frequency::from_raw(1, NonZeroU64::MAX);
+    assert_eq!(fastest.clamp_rate(rate(3, 2)), fastest);
+    assert_eq!(slowest.clamp_rate(rate(2, 3)), slowest);
+    assert_eq!(
+        Frequency::from_ghz(1).clamp_rate(rate(u64::MAX, 1)),
+        fastest
+    );
+
+    // Not representable exactly, but close.
+    let freq = Frequency::from_raw(1, NonZeroU64::new(100_000_000_000_000).unwrap())
+        .clamp_rate(rate(1_000_003, 1_000_000));
+    let expected = 1_000_003.0 / 1e20;
+    let actual = freq.count() as f64 / freq.period_nanos().get() as f64;
+    assert!((actual / expected - 1.0).abs() < 1e-12, "{:?}", freq);
+}
+
+#[test]
+fn test_rewind_extreme_delay() {
+    let start = TimeStamp::start() + TimeSpan::SECOND;
+    let freq = Frequency::from_raw(u64::MAX - 1, NonZeroU64::MAX);
+    let mut ticker = FrequencyTicker::with_delay(freq, u64::MAX, start);
+
+    assert_eq!(ticker.rewind(TimeSpan::SECOND), 0);
+    assert_eq!(ticker.now(), TimeStamp::start());
+    assert_eq!(ticker.next_tick(), None);
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND), 0);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_rewind_prop_walk(
+        count in 1u64..20,
+        period in 1u64..50,
+        walk in proptest::collection::vec((proptest::bool::ANY, 0u64..100), 0..50),
+    ) {
+        let freq = Frequency::new(count, NonZeroTimeSpan::new(NonZeroU64::new(period).unwrap()));
+        let mut ticker = freq.ticker(TimeStamp::start());
+        let mut emitted = Vec::new();
+        let mut offset = 0;
+
+        for (forward, nanos) in walk {
+            if forward {
+                offset += nanos;
+                emitted.extend(ticker.ticks(TimeSpan::new(nanos)).map(|tick| tick.now));
+            } else {
+                let nanos = nanos.min(offset);
+                offset -= nanos;
+                let unfired = ticker.rewind(TimeSpan::new(nanos));
+                for _ in 0..unfired {
+                    let stamp = emitted.pop().unwrap();
+                    proptest::prop_assert!(stamp > ticker.now);
+                }
+                if let Some(&last) = emitted.last() {
+                    proptest::prop_assert!(last <= ticker.now);
+                }
+            }
+        }
+
+        let mut straight = freq.ticker(TimeStamp::start());
+        let straight_emitted = straight.ticks(TimeSpan::new(offset)).map(|tick| tick.now).collect::<Vec<_>>();
+
+        proptest::prop_assert_eq!(emitted, straight_emitted);
+        proptest::prop_assert_eq!(ticker.now, straight.now);
+        proptest::prop_assert_eq!(ticker.until_next.0, straight.until_next.0);
+        proptest::prop_assert_eq!(ticker.tick_index, straight.tick_index);
+        proptest::prop_assert_eq!(ticker.last_tick, straight.last_tick);
+    }
+}
+
+#[test]
+fn test_ticks_until() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let freq = Frequency::new(3, NonZeroU64::new(10).unwrap().nanoseconds());
+    let mut lagging = FrequencyTicker::new(freq, TimeStamp::start());
+    let mut reference = FrequencyTicker::new(freq, TimeStamp::start());
+
+    let target = TimeStamp::start() + TimeSpan::NANOSECOND * 47;
+
+    let expected: Vec<_> = reference.ticks(TimeSpan::NANOSECOND * 47).collect();
+    let actual: Vec<_> = lagging.ticks_until(target).collect();
+    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 14);
+    assert_eq!(lagging.now(), target);
+    assert_eq!(lagging.next_tick(), reference.next_tick());
+
+    // Target in the past or at current time stamp yields nothing.
+    assert_eq!(lagging.ticks_until(target).count(), 0);
+    assert_eq!(lagging.ticks_until(TimeStamp::start()).count(), 0);
+    assert_eq!(lagging.now(), target);
+}
+
+#[test]
+fn test_staggered_tickers() {
+    const FRAME: TimeSpan = TimeSpan::new(16_666_667);
+
+    let freq = Frequency::from_hz(10);
+    let mut tickers: Vec<_> = freq.staggered_tickers(TimeStamp::start(), 10).collect();
+    assert_eq!(tickers.len(), 10);
+
+    // All tickers tick on distinct nanoseconds.
+    let mut firsts: Vec<_> = tickers.iter().map(|t| t.next_tick().unwrap()).collect();
+    firsts.sort();
+    firsts.dedup();
+    assert_eq!(firsts.len(), 10);
+
+    // 10 ticks per second distributed over 60 frames.
+    let period_frames = 6;
+    let max_per_frame = 10u64.div_ceil(period_frames) + 1;
+
+    let mut total = 0;
+    for _ in 0..600 {
+        let frame_ticks: u64 = tickers.iter_mut().map(|t| t.tick_count(FRAME)).sum();
+        assert!(frame_ticks <= max_per_frame);
+        total += frame_ticks;
+    }
+    assert!((990..=1000).contains(&total));
+}
+
+#[test]
+fn test_frequency_ratio() {
+    let ratio = |a: Frequency, b: Frequency| a.ratio_to(&b).map(|(n, d)| (n, d.get()));
+
+    assert_eq!(
+        ratio(Frequency::from_hz(48000), Frequency::from_hz(44100)),
+        Some((160, 147))
+    );
+    assert_eq!(
+        ratio(Frequency::from_hz(44100), Frequency::from_hz(48000)),
+        Some((147, 160))
+    );
+    assert_eq!(
+        ratio(crate::freq!(30000 per 1001 s), Frequency::from_hz(30)),
+        Some((1000, 1001))
+    );
+    assert_eq!(
+        ratio(Frequency::from_hz(0), Frequency::from_hz(60)),
+        Some((0, 1))
+    );
+    assert_eq!(ratio(Frequency::from_hz(60), Frequency::from_hz(0)), None);
+
+    // Co-prime worst cases.
+    let p1 = 4294967291;
+    let p2 = 4294967279;
+    let a = Frequency::from_raw(p1, NonZeroU64::new(p2).unwrap());
+    let b = Frequency::from_raw(p2, NonZeroU64::new(p1).unwrap());
+    assert_eq!(ratio(a, b), Some((p1 * p1, p2 * p2)));
+    let a = Frequency::from_raw(u64::MAX, NonZeroU64::new(1).unwrap());
+    let b = Frequency::from_raw(1, NonZeroU64::new(u64::MAX).unwrap());
+    assert_eq!(ratio(a, b), None);
+    assert_eq!(ratio(b, a), None);
+
+    assert!(Frequency::from_hz(120).is_multiple_of(&Frequency::from_hz(60)));
+    assert!(Frequency::from_hz(60).is_multiple_of(&Frequency::from_hz(60)));
+    assert!(!Frequency::from_hz(60).is_multiple_of(&Frequency::from_hz(120)));
+    assert!(!Frequency::from_hz(48000).is_multiple_of(&Frequency::from_hz(44100)));
+    assert!(Frequency::from_hz(30).is_multiple_of(&crate::freq!(1 per 2 s)));
+    assert!(Frequency::from_hz(0).is_multiple_of(&Frequency::from_hz(7)));
+    assert!(Frequency::from_hz(0).is_multiple_of(&Frequency::from_hz(0)));
+    assert!(!Frequency::from_hz(1).is_multiple_of(&Frequency::from_hz(0)));
+}
+
+#[test]
+fn test_common_tick_period() {
+    let common = |a: Frequency, b: Frequency| a.common_tick_period(&b);
+
+    assert_eq!(
+        common(Frequency::from_hz(48000), Frequency::from_hz(44100)),
+        Some(TimeSpan::MILLISECOND * 10)
+    );
+    assert_eq!(
+        common(Frequency::from_hz(60), Frequency::from_hz(50)),
+        Some(TimeSpan::MILLISECOND * 100)
+    );
+    assert_eq!(
+        common(crate::freq!(30000 per 1001 s), Frequency::from_hz(30)),
+        Some(TimeSpan::MILLISECOND * 100_100)
+    );
+    // Three periods of 50/3 ms.
+    assert_eq!(
+        common(Frequency::from_hz(60), Frequency::from_hz(60)),
+        Some(TimeSpan::MILLISECOND * 50)
+    );
+    assert_eq!(
+        common(Frequency::from_hz(4), Frequency::from_hz(2)),
+        Some(TimeSpan::MILLISECOND * 500)
+    );
+    assert_eq!(common(Frequency::from_hz(0), Frequency::from_hz(60)), None);
+
+    // Co-prime periods.
+    let p1 = 4294967291;
+    let p2 = 4294967279;
+    let a = Frequency::from_raw(1, NonZeroU64::new(p1).unwrap());
+    let b = Frequency::from_raw(1, NonZeroU64::new(p2).unwrap());
+    assert_eq!(common(a, b), Some(TimeSpan::new(p1 * p2)));
+    let a = Frequency::from_raw(1, NonZeroU64::new(u64::MAX).unwrap());
+    let b = Frequency::from_raw(1, NonZeroU64::new(u64::MAX - 1).unwrap());
+    assert_eq!(common(a, b), None);
+
+    // Both tickers tick at the common period.
+    for (a, b) in [
+        (Frequency::from_hz(48000), Frequency::from_hz(44100)),
+        (crate::freq!(30000 per 1001 s), Frequency::from_hz(24)),
+    ] {
+        let period = common(a, b).unwrap();
+        let mut ta = FrequencyTicker::new(a, TimeStamp::start());
+        let mut tb = FrequencyTicker::new(b, TimeStamp::start());
+        let last_a = ta.ticks(period).last().unwrap();
+        let last_b = tb.ticks(period).last().unwrap();
+        assert_eq!(last_a.now, TimeStamp::start() + period);
+        assert_eq!(last_b.now, TimeStamp::start() + period);
+    }
+}
+
+#[test]
+fn test_ticker_extremes() {
+    // Highest frequency ticks `u64::MAX` times per nanosecond.
+    let freq = Frequency::new(u64::MAX, NonZeroTimeSpan::NANOSECOND);
+    let mut ticker = FrequencyTicker::new(freq, TimeStamp::start());
+    assert_eq!(
+        ticker.next_tick(),
+        Some(TimeStamp::start() + TimeSpan::NANOSECOND)
+    );
+    assert_eq!(ticker.tick_count(TimeSpan::NANOSECOND), u64::MAX);
+    assert_eq!(ticker.tick_count(TimeSpan::NANOSECOND * 2), u64::MAX);
+    assert_eq!(ticker.tick_index(), u64::MAX);
+
+    // Lowest frequency.
+    let freq = Frequency::from_raw(1, NonZeroU64::MAX);
+    let mut ticker = FrequencyTicker::new(freq, TimeStamp::start());
+    assert_eq!(ticker.next_tick(), None);
+    assert_eq!(ticker.tick_count(TimeSpan::MAX - TimeSpan::NANOSECOND), 0);
+    assert_eq!(ticker.now(), TimeStamp::never());
+
+    // Longest delays.
+    for freq in [
+        Frequency::from_ghz(1),
+        Frequency::from_hz(1),
+        Frequency::from_raw(1, NonZeroU64::MAX),
+    ] {
+        let mut ticker = FrequencyTicker::with_delay(freq, u64::MAX, TimeStamp::start());
+        if freq.count() == 1 {
+            assert_eq!(ticker.next_tick(), None);
+        }
+        let (iter, remainder) = ticker.ticks_with_remainder(TimeSpan::MAX - TimeSpan::NANOSECOND);
+        assert_eq!(iter.ticks(), 0);
+        if freq == Frequency::from_hz(1) {
+            assert_eq!(remainder, u64::MAX);
+        }
+    }
+
+    // Period of the highest frequency is `1 / u64::MAX` nanoseconds.
+    let mut ticker = FrequencyTicker::with_delay(
+        Frequency::new(u64::MAX, NonZeroTimeSpan::NANOSECOND),
+        u64::MAX - 1,
+        TimeStamp::start(),
+    );
+    assert_eq!(ticker.tick_count(TimeSpan::NANOSECOND), 1);
+}
+
+#[test]
+fn test_ticker_stress() {
+    let counts = [1, u32::MAX as u64, u64::MAX / 2];
+    let periods = [1, 1_000_000_000, u64::MAX / 2];
+    let steps = [TimeSpan::NANOSECOND, TimeSpan::SECOND, TimeSpan::HOUR];
+
+    for count in counts {
+        for period in periods {
+            let freq = Frequency::from_raw(count, NonZeroU64::new(period).unwrap());
+
+            // Ticks up to `k` steps, counted independently.
+            let reference = |step: TimeSpan, k: u128| {
+                k * step.as_nanos() as u128 * freq.count() as u128
+                    / freq.period_nanos().get() as u128
+            };
+
+            for step in steps {
+                let mut ticker = FrequencyTicker::new(freq, TimeStamp::start());
+                let mut total = 0u64;
+
+                for k in 1..=3 {
+                    let expected = reference(step, k) - reference(step, k - 1);
+                    let expected = u64::try_from(expected).unwrap_or(u64::MAX);
+
+                    let iter = ticker.ticks(step);
+                    assert_eq!(iter.ticks(), expected, "{:?} {:?} #{}", freq, step, k);
+                    total = total.saturating_add(expected);
+
+                    let mut last = TimeStamp::start() + step * (k as u64 - 1);
+                    for tick in iter.take(1000) {
+                        assert!(tick.now >= last);
+                        assert!(tick.now <= TimeStamp::start() + step * k as u64);
+                        last = tick.now;
+                    }
+                    let _ = ticker.next_tick();
+                }
+                assert_eq!(ticker.tick_index(), total);
+
+                if total < u64::MAX {
+                    let last = u64::try_from(reference(step, 3) - reference(step, 2)).unwrap();
+                    assert_eq!(ticker.rewind(step), last);
+                    assert_eq!(ticker.tick_count(step), last);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_ticker_total_ticks() {
+    let freq = Frequency::from_hz(60);
+    let mut ticker = FrequencyTicker::new(freq, TimeStamp::start());
+    assert_eq!(ticker.total_ticks(), 0);
+
+    let mut sum = 0;
+    for ms in [7, 16, 33, 0, 250, 1, 1000] {
+        sum += ticker.ticks(TimeSpan::MILLISECOND * ms).count() as u64;
+        sum += ticker.tick_count(TimeSpan::MILLISECOND * ms);
+        ticker.with_ticks(TimeSpan::MILLISECOND * ms, |_| sum += 1);
+    }
+    assert_eq!(ticker.total_ticks(), sum);
+    assert_eq!(ticker.tick_index(), sum);
+
+    // Burst ticks are counted.
+    ticker.burst(3);
+    sum += ticker.ticks(TimeSpan::ZERO).count() as u64;
+    assert_eq!(ticker.total_ticks(), sum);
+
+    // Rewound ticks are counted again when emitted again.
+    let unfired = ticker.rewind(TimeSpan::MILLISECOND * 100);
+    assert_eq!(unfired, 6);
+    assert_eq!(ticker.tick_count(TimeSpan::MILLISECOND * 100), 6);
+    assert_eq!(ticker.total_ticks(), sum + 6);
+    assert_eq!(ticker.tick_index(), sum);
+
+    ticker.reset_total();
+    assert_eq!(ticker.total_ticks(), 0);
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND), 60);
+    assert_eq!(ticker.total_ticks(), 60);
+    assert_eq!(FrequencyTicker::restore(ticker.snapshot()).total_ticks(), 0);
+}
+
+#[test]
+fn test_ticker_total_ticks_delivered() {
+    let freq = Frequency::from_hz(10);
+    let mut ticker = FrequencyTicker::new(freq, TimeStamp::start());
+
+    // Ticks that don't fit into the buffer are dropped and not counted.
+    let mut buf = [ClockStep::zero_at(TimeStamp::start()); 3];
+    assert_eq!(ticker.ticks_into(TimeSpan::SECOND, &mut buf), (3, 7));
+    assert_eq!(ticker.total_ticks(), 3);
+    assert_eq!(ticker.tick_index(), 10);
+
+    // Ticks after the break are skipped and not counted.
+    let mut seen = 0;
+    let flow = ticker.with_ticks_until(TimeSpan::SECOND, |_| {
+        seen += 1;
+        if seen == 4 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(flow, ControlFlow::Break(()));
+    assert_eq!(ticker.total_ticks(), 7);
+    assert_eq!(ticker.tick_index(), 20);
+
+    // Without the break all ticks are delivered.
+    let flow = ticker.with_ticks_until(TimeSpan::SECOND, |_| ControlFlow::Continue(()));
+    assert_eq!(flow, ControlFlow::Continue(()));
+    assert_eq!(ticker.total_ticks(), 17);
+}
+
+#[test]
+fn test_ticker_debug() {
+    let mut ticker = FrequencyTicker::new(Frequency::from_hz(4), TimeStamp::start());
+    ticker.ticks(TimeSpan::MILLISECOND * 625).for_each(drop);
+
+    let debug = format!("{:?}", ticker);
+    assert!(
+        debug.starts_with("FrequencyTicker { freq: 4 Hz,"),
+        "{}",
+        debug
+    );
+    assert!(debug.contains("phase: 0.5,"), "{}", debug);
+    assert!(debug.contains("tick_index: 2,"), "{}", debug);
+
+    let iter = ticker.ticks(TimeSpan::MILLISECOND * 500);
+    let debug = format!("{:?}", iter);
+    assert!(debug.contains("freq: 4 Hz,"), "{}", debug);
+    assert!(debug.contains("phase: 0.5,"), "{}", debug);
+    assert!(debug.contains("ticks: 2 }"), "{}", debug);
+
+    let ticker = FrequencyTicker::with_delay(Frequency::from_hz(1), 1, TimeStamp::start());
+    assert!(format!("{:?}", ticker).contains("phase: 0.0,"));
+}
+
+#[test]
+fn test_ticks_into() {
+    let start = TimeStamp::start();
+    let mut ticker = Frequency::from_hz(10).ticker(start);
+    let mut buf = [ClockStep::zero_at(start); 4];
+
+    let (written, dropped) = ticker.ticks_into(TimeSpan::MILLISECOND * 250, &mut buf);
+    assert_eq!((written, dropped), (2, 0));
+    assert_eq!(buf[1].now, start + TimeSpan::MILLISECOND * 200);
+
+    // Buffer smaller than number of ticks.
+    let (written, dropped) = ticker.ticks_into(TimeSpan::MILLISECOND * 700, &mut buf);
+    assert_eq!((written, dropped), (4, 3));
+    assert_eq!(
+        buf.map(|tick| tick.now.elapsed_since(start).as_millis()),
+        [300, 400, 500, 600]
+    );
+    assert_eq!(ticker.tick_index(), 9);
+
+    // Dropped ticks are not delivered later.
+    let (written, dropped) = ticker.ticks_into(TimeSpan::MILLISECOND * 100, &mut buf);
+    assert_eq!((written, dropped), (1, 0));
+    assert_eq!(buf[0].now, start + TimeSpan::MILLISECOND * 1000);
+
+    assert_eq!(ticker.ticks_into(TimeSpan::SECOND, &mut []), (0, 10));
+}
+
+#[test]
+fn test_with_ticks_until() {
+    let start = TimeStamp::start();
+    let mut ticker = Frequency::from_hz(10).ticker(start);
+
+    let mut seen = Vec::new();
+    let flow = ticker.with_ticks_until(TimeSpan::SECOND, |tick| {
+        seen.push(tick.now);
+        if seen.len() == 3 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(flow, ControlFlow::Break(()));
+    assert_eq!(seen.len(), 3);
+    assert_eq!(ticker.now(), start + TimeSpan::SECOND);
+
+    let mut count = 0;
+    let flow = ticker.with_ticks_until(TimeSpan::MILLISECOND * 500, |_| {
+        count += 1;
+        ControlFlow::Continue(())
+    });
+    assert_eq!(flow, ControlFlow::Continue(()));
+    assert_eq!(count, 5);
+}
+
+#[test]
+fn test_with_phase() {
+    let freq = Frequency::from_hz(10);
+    let start = TimeStamp::start();
+
+    let mut a = FrequencyTicker::with_phase(freq, 0.0, start);
+    let mut b = FrequencyTicker::with_phase(freq, 0.5, start);
+    assert_eq!(a.next_tick(), FrequencyTicker::new(freq, start).next_tick());
+    assert_eq!(b.next_tick(), Some(start + TimeSpan::MILLISECOND * 50));
+
+    // Ticks interleave.
+    let mut ticks = Vec::new();
+    for _ in 0..100 {
+        ticks.extend(a.ticks(TimeSpan::MILLISECOND * 10).map(|t| (t.now, 'a')));
+        ticks.extend(b.ticks(TimeSpan::MILLISECOND * 10).map(|t| (t.now, 'b')));
+    }
+    assert_eq!(ticks.len(), 20);
+    for (i, (now, name)) in ticks.iter().enumerate() {
+        assert_eq!(*name, if i % 2 == 0 { 'b' } else { 'a' });
+        assert_eq!(*now, start + TimeSpan::MILLISECOND * (50 * i as u64 + 50));
+    }
+
+    let c = FrequencyTicker::with_phase(freq, 1.0, start);
+    assert_eq!(c.next_tick(), Some(start + TimeSpan::NANOSECOND));
+    let d = FrequencyTicker::with_phase(freq, f32::NAN, start);
+    assert_eq!(d.next_tick(), Some(start + TimeSpan::MILLISECOND * 100));
+}
+
+#[test]
+fn test_rebase() {
+    let mut ticker = Frequency::from_hz(10).ticker(TimeStamp::start());
+
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND * 5), 50);
+    assert_eq!(ticker.tick_count(TimeSpan::MILLISECOND * 50), 0);
+    let old_now = ticker.now();
+
+    ticker.rebase(old_now, TimeStamp::start());
+    assert_eq!(ticker.now(), TimeStamp::start());
+    assert_eq!(
+        ticker.last_tick(),
+        None,
+        "last tick is before new time line start"
+    );
+    assert_eq!(
+        ticker.next_tick(),
+        Some(TimeStamp::start() + TimeSpan::MILLISECOND * 50)
+    );
+
+    // Cadence continues without burst or stall.
+    assert_eq!(ticker.tick_count(TimeSpan::MILLISECOND * 49), 0);
+    assert_eq!(ticker.tick_count(TimeSpan::MILLISECOND), 1);
+    for _ in 0..10 {
+        assert_eq!(ticker.tick_count(TimeSpan::MILLISECOND * 100), 1);
+    }
+    assert_eq!(ticker.tick_index(), 61);
+
+    // Rebasing forward.
+    let now = ticker.now();
+    ticker.rebase(now, now + TimeSpan::HOUR);
+    assert_eq!(ticker.now(), now + TimeSpan::HOUR);
+    assert_eq!(ticker.last_tick(), Some(now + TimeSpan::HOUR));
+}
+
+#[test]
+fn test_display_fps() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let ntsc = Frequency::new(60000, NonZeroU64::new(1001).unwrap().seconds());
+
+    assert_eq!(Frequency::from_hz(60).display_fps().to_string(), "60 FPS");
+    assert_eq!(ntsc.display_fps().to_string(), "59.94 FPS");
+    assert_eq!(format!("{:.3}", ntsc.display_fps()), "59.940 FPS");
+    assert_eq!(format!("{:.0}", ntsc.display_fps()), "60 FPS");
+    assert_eq!(
+        format!("{:.2}", Frequency::from_hz(60).display_fps()),
+        "60.00 FPS"
+    );
+
+    // Half-up rounding.
+    let eighth = Frequency::new(1, NonZeroU64::new(8).unwrap().seconds());
+    assert_eq!(format!("{:.2}", eighth.display_fps()), "0.13 FPS");
+    assert_eq!(eighth.display_fps().to_string(), "0.13 FPS");
+    assert_eq!(format!("{:.3}", eighth.display_fps()), "0.125 FPS");
+}
+
+#[test]
+fn test_parse_fps() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let parse = |s: &str| Frequency::parse_fps(s).map(|freq| (freq.count, freq.period.get()));
+    let exact = |count: u64, seconds: u64| {
+        let freq = Frequency::new(count, NonZeroU64::new(seconds).unwrap().seconds());
+        Ok((freq.count, freq.period.get()))
+    };
+
+    assert_eq!(parse("60"), exact(60, 1));
+    assert_eq!(parse("60 fps"), exact(60, 1));
+    assert_eq!(parse(" 144 FPS "), exact(144, 1));
+    assert_eq!(parse("59.94FPS"), exact(5994, 100));
+    assert_eq!(parse("29.97 Fps"), exact(2997, 100));
+    assert_eq!(parse(".5"), exact(1, 2));
+
+    assert_eq!(parse(""), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("fps"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("."), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("-5"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("1.2.3"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("60 Hz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("1.0000000001"), Err(FrequencyParseErr::TooPrecise));
+    assert_eq!(
+        parse("100000000000000000000"),
+        Err(FrequencyParseErr::Overflow)
+    );
+
+    let ntsc = Frequency::parse_fps("59.94").unwrap();
+    assert_eq!(ntsc.display_fps().to_string(), "59.94 FPS");
+}
+
+#[test]
+fn test_ticks_exact_size() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    // Multiple ticks per nanosecond.
+    let freq = Frequency::new(7, NonZeroU64::new(3).unwrap().nanoseconds());
+    let mut ticker = freq.ticker(TimeStamp::start());
+
+    for step in [1, 2, 3, 10, 0, 5] {
+        let mut iter = ticker.ticks(TimeSpan::new(step));
+        let mut left = iter.ticks() as usize;
+        assert_eq!(iter.size_hint(), (left, Some(left)));
+        while iter.next().is_some() {
+            left -= 1;
+            assert_eq!(iter.size_hint(), (left, Some(left)));
+        }
+        assert_eq!(left, 0);
+    }
+}
+
+#[test]
+fn test_ticks_saturated() {
+    let freq = Frequency::from_raw(u64::MAX / 2, NonZeroU64::MIN);
+    let mut ticker = freq.ticker(TimeStamp::start());
+
+    let mut iter = ticker.ticks(TimeSpan::HOUR);
+    assert_eq!(iter.ticks(), u64::MAX);
+    assert_eq!(iter.size_hint(), (usize::MAX, None));
+
+    // Iterator is still usable, `collect_into` doesn't reserve for it.
+    assert_eq!(iter.by_ref().take(3).count(), 3);
+    assert_eq!(iter.size_hint(), (usize::MAX, None));
+    assert_eq!(ticker.tick_index(), u64::MAX);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_ticks_collect_into() {
+    let mut ticker = Frequency::from_hz(60).ticker(TimeStamp::start());
+    let mut buf = Vec::new();
+
+    let ticks = ticker
+        .ticks(TimeSpan::MILLISECOND * 50)
+        .collect_into(&mut buf);
+    assert_eq!(ticks.len(), 3);
+    let capacity = buf.capacity();
+    let ptr = buf.as_ptr();
+
+    for frame in 0..100 {
+        let step = TimeSpan::MILLISECOND * (frame % 3 * 16 + 1);
+        let ticks = ticker.ticks(step).collect_into(&mut buf);
+        assert!(ticks.len() <= 3);
+        assert_eq!(buf.capacity(), capacity);
+        assert_eq!(buf.as_ptr(), ptr);
+    }
+}
+
+#[test]
+fn test_stamp_to_tick() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let origin = TimeStamp::start() + TimeSpan::SECOND;
+    let ntsc = Frequency::new(60000, NonZeroU64::new(1001).unwrap().seconds());
+
+    assert_eq!(ntsc.stamp_to_tick(origin, origin), 0);
+    assert_eq!(ntsc.stamp_to_tick(origin, TimeStamp::start()), 0);
+    assert_eq!(
+        ntsc.tick_to_stamp(origin, 1),
+        Some(origin + TimeSpan::new(16_683_334))
+    );
+    assert_eq!(
+        ntsc.stamp_to_tick(origin, origin + TimeSpan::new(16_683_333)),
+        0
+    );
+    assert_eq!(
+        ntsc.stamp_to_tick(origin, origin + TimeSpan::new(16_683_334)),
+        1
+    );
+    assert_eq!(
+        ntsc.round_stamp_to_tick(origin, origin + TimeSpan::new(8_341_666)),
+        0
+    );
+    assert_eq!(
+        ntsc.round_stamp_to_tick(origin, origin + TimeSpan::new(8_341_667)),
+        1
+    );
+    assert_eq!(
+        ntsc.stamp_to_tick(origin, origin + TimeSpan::SECOND * 1001),
+        60000
+    );
+
+    for tick in [0, 1, 2, 59_999, 60_000, 1 << 40] {
+        let stamp = ntsc.tick_to_stamp(origin, tick).unwrap();
+        assert_eq!(ntsc.stamp_to_tick(origin, stamp), tick);
+        assert_eq!(ntsc.round_stamp_to_tick(origin, stamp), tick);
+    }
+
+    assert_eq!(ntsc.tick_to_stamp(origin, u64::MAX), None);
+    let never = Frequency::new(0, NonZeroTimeSpan::SECOND);
+    assert_eq!(never.tick_to_stamp(origin, 0), Some(origin));
+    assert_eq!(never.tick_to_stamp(origin, 1), None);
+    assert_eq!(never.stamp_to_tick(origin, TimeStamp::never()), 0);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_stamp_to_tick_prop_ticker(
+        count in 1u64..1000,
+        period in 1u64..1_000_000,
+        steps in proptest::collection::vec(0u64..100_000, 1..50),
+    ) {
+        let freq = Frequency::new(count, NonZeroTimeSpan::new(NonZeroU64::new(period).unwrap()));
+        let origin = TimeStamp::start() + TimeSpan::new(12345);
+        let mut ticker = freq.ticker(origin);
+
+        for step in steps {
+            for tick in ticker.ticks_indexed(TimeSpan::new(step)) {
+                let t = tick.index + 1;
+                proptest::prop_assert_eq!(freq.tick_to_stamp(origin, t), Some(tick.step.now));
+                proptest::prop_assert!(freq.stamp_to_tick(origin, tick.step.now) >= t);
+                if count <= period {
+                    proptest::prop_assert_eq!(freq.stamp_to_tick(origin, tick.step.now), t);
+                }
+            }
+            proptest::prop_assert_eq!(freq.stamp_to_tick(origin, ticker.now()), ticker.tick_index());
+        }
+    }
+}
+
+#[test]
+fn test_ticker_snapshot() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let freq = Frequency::new(60000, NonZeroU64::new(1001).unwrap().seconds());
+    let mut a = freq.ticker(TimeStamp::start());
+    let mut b = freq.ticker(TimeStamp::start());
+    assert_eq!(a.snapshot(), b.snapshot());
+
+    for step in [3, 17_000_000, 5, 100_000_000, 1] {
+        a.tick_count(TimeSpan::new(step));
+        b.tick_count(TimeSpan::new(step));
+        assert_eq!(a.snapshot(), b.snapshot());
+    }
+
+    b.tick_count(TimeSpan::NANOSECOND);
+    assert_ne!(a.snapshot(), b.snapshot());
+
+    let state = a.snapshot();
+    let mut c = FrequencyTicker::restore(state);
+    assert_eq!(c.snapshot(), state);
+    assert_eq!(state.tick_index(), a.tick_index());
+    assert_eq!(state.now(), a.now());
+    assert_eq!(state.frequency(), freq);
+
+    let a_ticks: Vec<_> = a.ticks(TimeSpan::SECOND).collect();
+    let c_ticks: Vec<_> = c.ticks(TimeSpan::SECOND).collect();
+    assert_eq!(a_ticks, c_ticks);
+    assert_eq!(a.snapshot(), c.snapshot());
+}
+
+#[test]
+fn test_frequency_reduced() {
+    let four = NonZeroU64::new(4).unwrap();
+    assert_eq!(
+        Frequency::from_raw(2, four),
+        Frequency::from_raw(1, NonZeroU64::new(2).unwrap())
+    );
+    assert_eq!(
+        Frequency::from_raw(0, four),
+        Frequency::from_raw(0, NonZeroU64::MIN)
+    );
+    assert_eq!(
+        Frequency::from_raw(60, NonZeroU64::new(1_000_000_000).unwrap()),
+        Frequency::from_hz(60)
+    );
+
+    let freq = Frequency::from_raw(6, NonZeroU64::new(9).unwrap());
+    assert_eq!(freq.count(), 2);
+    assert_eq!(freq.period_nanos().get(), 3);
+    assert_eq!(freq.period_span().as_nanos().get(), 3);
+}
+
+#[test]
+fn test_frequency_display_si() {
+    assert_eq!(Frequency::from_mhz(1).to_string(), "1 MHz");
+    assert_eq!(Frequency::from_hz(60).to_string(), "60 Hz");
+    assert_eq!(Frequency::from_hz(44_100).to_string(), "44.1 kHz");
+    assert_eq!(Frequency::from_mhz(2_500).to_string(), "2.5 GHz");
+    assert_eq!(Frequency::from_ghz(3_000).to_string(), "3000 GHz");
+    assert_eq!(Frequency::from_hz(0).to_string(), "0 Hz");
+    assert_eq!(crate::freq!(1 per 3 s).to_string(), "0.33 Hz");
+    assert_eq!(Frequency::from_hz(999_999).to_string(), "1 MHz");
+    assert_eq!(Frequency::from_hz(994_000).to_string(), "994 kHz");
+
+    let ntsc = Frequency::new(
+        60_000,
+        NonZeroTimeSpan::SECOND * NonZeroU64::new(1001).unwrap(),
+    );
+    assert_eq!(ntsc.to_string(), "59.94 Hz");
+    assert_eq!(format!("{:.3}", ntsc), "59.940 Hz");
+    assert_eq!(format!("{:.0}", Frequency::from_khz(1)), "1 kHz");
+}
+
+#[test]
+fn test_frequency_parse_si() {
+    let parse = |s: &str| s.parse::<Frequency>();
+
+    assert_eq!(parse("1 MHz"), Ok(Frequency::from_mhz(1)));
+    assert_eq!(
+        parse(&Frequency::from_mhz(1).to_string()),
+        Ok(Frequency::from_mhz(1))
+    );
+    assert_eq!(parse("60"), Ok(Frequency::from_hz(60)));
+    assert_eq!(parse("60Hz"), Ok(Frequency::from_hz(60)));
+    assert_eq!(parse(" 44.1 kHz "), Ok(Frequency::from_hz(44_100)));
+    assert_eq!(parse("2.5 Ghz"), Ok(Frequency::from_mhz(2_500)));
+    assert_eq!(parse("60 HZ"), Ok(Frequency::from_hz(60)));
+    assert_eq!(parse("48KHz"), Ok(Frequency::from_khz(48)));
+    assert_eq!(parse("0.000000001 GHz"), Ok(Frequency::from_hz(1)));
+    assert_eq!(
+        parse("59.94 Hz"),
+        Ok(Frequency::from_raw(
+            2997,
+            NonZeroU64::new(50_000_000_000).unwrap()
+        ))
+    );
+
+    for freq in [
+        Frequency::from_hz(60),
+        Frequency::from_hz(44_100),
+        Frequency::from_khz(48),
+        Frequency::from_mhz(2_500),
+        Frequency::from_ghz(7),
+    ] {
+        assert_eq!(parse(&freq.to_string()), Ok(freq));
+    }
+
+    assert_eq!(parse(""), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("MHz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("60 THz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("1 mHz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("500 mhz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("2.5 ghz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("60 z"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("60 Hz Hz"), Err(FrequencyParseErr::InvalidNumber));
+    assert_eq!(parse("1.0000000001 Hz"), Err(FrequencyParseErr::TooPrecise));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_frequency_deserialize_si() {
+    let freq: Frequency = serde_json::from_str("\"1 MHz\"").unwrap();
+    assert_eq!(freq, Frequency::from_mhz(1));
+
+    let freq: Frequency = serde_json::from_str("\"2.5 GHz\"").unwrap();
+    assert_eq!(freq, Frequency::from_mhz(2_500));
+
+    assert!(serde_json::from_str::<Frequency>("\"1 THz\"").is_err());
+    assert!(serde_json::from_str::<Frequency>("\"500 mHz\"").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_frequency_deserialize_reduced() {
+    let freq: Frequency = serde_json::from_str("\"2/4 Hz\"").unwrap();
+    assert_eq!(freq, Frequency::from_raw(1, NonZeroU64::new(2).unwrap()));
+
+    let freq: Frequency = serde_json::from_str("\"0/7 Hz\"").unwrap();
+    assert_eq!(freq, Frequency::from_raw(0, NonZeroU64::MIN));
+}
+
+#[test]
+fn test_ticker_burst() {
+    let start = TimeStamp::start();
+    let mut ticker = Frequency::from_hz(2).ticker(start);
+    assert_eq!(
+        ticker.next_tick(),
+        Some(start + TimeSpan::MILLISECOND * 500)
+    );
+
+    ticker.tick_count(TimeSpan::MILLISECOND * 100);
+    let now = ticker.now();
+
+    ticker.burst(3);
+    ticker.burst(0);
+    assert_eq!(ticker.pending_burst(), 3);
+    assert_eq!(ticker.next_tick(), Some(now));
+
+    let ticks: Vec<_> = ticker.ticks(TimeSpan::SECOND).collect();
+    let expected = [
+        ClockStep {
+            now,
+            step: TimeSpan::ZERO,
+        },
+        ClockStep {
+            now,
+            step: TimeSpan::ZERO,
+        },
+        ClockStep {
+            now,
+            step: TimeSpan::ZERO,
+        },
+        ClockStep {
+            now: start + TimeSpan::MILLISECOND * 500,
+            step: TimeSpan::MILLISECOND * 500,
+        },
+        ClockStep {
+            now: start + TimeSpan::SECOND,
+            step: TimeSpan::MILLISECOND * 500,
+        },
+    ];
+    assert_eq!(ticks, expected);
+    assert_eq!(ticker.pending_burst(), 0);
+    assert_eq!(ticker.tick_index(), 5);
+    assert_eq!(ticker.last_tick(), Some(start + TimeSpan::SECOND));
+    assert_eq!(
+        ticker.next_tick(),
+        Some(start + TimeSpan::MILLISECOND * 1500)
+    );
+
+    // Burst is flushed even without time passing.
+    ticker.burst(2);
+    let mut iter = ticker.ticks(TimeSpan::ZERO);
+    assert_eq!(iter.ticks(), 2);
+    assert!(iter.all(|tick| tick.step == TimeSpan::ZERO));
+    assert_eq!(ticker.last_tick(), Some(ticker.now()));
+    assert_eq!(ticker.tick_index(), 7);
+
+    // Pending burst survives frequency change.
+    ticker.burst(1);
+    ticker.set_frequency(Frequency::from_hz(1));
+    assert_eq!(ticker.pending_burst(), 1);
+    assert_eq!(ticker.next_tick(), Some(ticker.now()));
+    assert_eq!(ticker.tick_count(TimeSpan::MILLISECOND * 100), 1);
+
+    let snapshot = ticker.snapshot();
+    ticker.burst(1);
+    assert_ne!(ticker.snapshot(), snapshot);
+}
+
+#[test]
+fn test_ticks_with_alpha() {
+    let mut ticker = Frequency::from_hz(4).ticker(TimeStamp::start());
+
+    let (iter, alpha) = ticker.ticks_with_alpha(TimeSpan::ZERO);
+    assert_eq!((iter.ticks(), alpha), (0, 0.0));
+
+    let (iter, alpha) = ticker.ticks_with_alpha(TimeSpan::MILLISECOND * 125);
+    assert_eq!((iter.ticks(), alpha), (0, 0.5));
+
+    let (iter, alpha) = ticker.ticks_with_alpha(TimeSpan::MILLISECOND * 125);
+    assert_eq!((iter.ticks(), alpha), (1, 0.0));
+
+    let (iter, alpha) = ticker.ticks_with_alpha(TimeSpan::MILLISECOND * 250 - TimeSpan::NANOSECOND);
+    assert_eq!(iter.ticks(), 0);
+    assert!(alpha < 1.0 && alpha > 0.999, "{}", alpha);
+
+    let (iter, alpha) = ticker.ticks_with_alpha(TimeSpan::NANOSECOND);
+    assert_eq!((iter.ticks(), alpha), (1, 0.0));
+
+    let mut delayed = FrequencyTicker::with_delay(Frequency::from_hz(4), 2, TimeStamp::start());
+    let (_, alpha) = delayed.ticks_with_alpha(TimeSpan::MILLISECOND * 125);
+    assert_eq!(alpha, 0.0);
+
+    let mut slow =
+        Frequency::new(1, NonZeroTimeSpan::new(NonZeroU64::MAX)).ticker(TimeStamp::start());
+    let (_, alpha) = slow.ticks_with_alpha(TimeSpan::MAX - TimeSpan::NANOSECOND);
+    assert!(alpha < 1.0);
+}
+
+#[test]
+fn test_samples() {
+    let freq = Frequency::from_hz(44_100);
+
+    assert_eq!(freq.samples_in_span(TimeSpan::SECOND), 44_100);
+    assert_eq!(freq.samples_in_span(TimeSpan::MILLISECOND), 44);
+    assert_eq!(freq.span_of_samples(44_100), TimeSpan::SECOND);
+    assert_eq!(freq.span_of_samples(1), TimeSpan::new(22_676));
+    assert_eq!(freq.span_of_samples_exact(1), (TimeSpan::new(22_675), 325));
+
+    for samples in [0, 1, 2, 441, 1_000_000, 44_100 * 3600] {
+        let span = freq.span_of_samples(samples);
+        assert_eq!(freq.samples_in_span(span), samples);
+    }
+
+    let a = TimeStamp::start() + TimeSpan::SECOND;
+    let b = a + TimeSpan::SECOND * 2;
+    assert_eq!(freq.samples_between(a, b), 88_200);
+    assert_eq!(freq.samples_between(b, a), 0);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #![proptest_config(proptest::prelude::ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn test_sample_clock_no_drift(
+        chunks in proptest::collection::vec(1u64..50_000_000, 1..64),
+    ) {
+        let freq = Frequency::from_hz(44_100);
+        let mut clock = SampleClock::new(freq);
+
+        let mut total = TimeSpan::ZERO;
+        let mut sum = 0;
+        for &chunk in chunks.iter().cycle() {
+            if total >= TimeSpan::HOUR {
+                break;
+            }
+            let chunk = TimeSpan::new(chunk);
+            total += chunk;
+            sum += clock.advance(chunk);
+        }
+
+        proptest::prop_assert_eq!(sum, freq.samples_in_span(total));
+        proptest::prop_assert_eq!(clock.total_samples(), sum);
+    }
+}
+
+#[test]
+fn test_periods_and_remainder() {
+    let freq = Frequency::from_hz(3);
+    assert_eq!(
+        freq.periods_and_remainder(TimeSpan::SECOND),
+        (3, TimeSpan::ZERO)
+    );
+    assert_eq!(
+        freq.periods_and_remainder(TimeSpan::MILLISECOND * 500),
+        (1, TimeSpan::new(166_666_666))
+    );
+    assert_eq!(
+        Frequency::from_hz(0).periods_and_remainder(TimeSpan::SECOND),
+        (0, TimeSpan::SECOND)
+    );
+
+    // Carried remainder doesn't lose fractions of nanoseconds.
+    let step = TimeSpan::new(333_333_333);
+    let mut phase = PhaseRemainder::ZERO;
+    let mut total = 0;
+    for _ in 0..3 {
+        let (periods, rest) = freq.periods_and_phase(step, phase);
+        total += periods;
+        phase = rest;
+    }
+    assert_eq!(total, 2);
+    assert_eq!(freq.phase_span(phase), TimeSpan::new(333_333_332));
+    assert_eq!(
+        freq.periods_and_phase(TimeSpan::NANOSECOND, phase),
+        (1, PhaseRemainder::ZERO)
+    );
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_periods_and_remainder_prop(
+        count in 1u64..1_000_000,
+        period in 1u64..1_000_000_000,
+        span in 0u64..1_000_000_000_000,
+    ) {
+        let freq = Frequency::new(count, NonZeroTimeSpan::new(NonZeroU64::new(period).unwrap()));
+        let span = TimeSpan::new(span);
+        let (periods, remainder) = freq.periods_and_remainder(span);
+
+        let covered = freq.span_of_samples_exact(periods).0 + remainder;
+        proptest::prop_assert!(covered == span || covered + TimeSpan::NANOSECOND == span);
+        proptest::prop_assert!(remainder < freq.span_of_samples(1));
+    }
+}
+
+#[test]
+fn test_from_hz_f64() {
+    let hz = |freq: Frequency| freq.count() as f64 * 1e9 / freq.period_nanos().get() as f64;
+
+    let ntsc = crate::freq!(60000 per 1001 s);
+    let approx = Frequency::from_hz_f64(59.94).unwrap();
+    assert_eq!(approx, crate::freq!(2997 per 50 s));
+    assert!((hz(approx) - hz(ntsc)).abs() < 1e-4);
+
+    assert_eq!(Frequency::from_hz_f64(60000.0 / 1001.0), Some(ntsc));
+    assert_eq!(
+        Frequency::from_hz_f64(30000.0 / 1001.0),
+        Some(crate::freq!(30000 per 1001 s))
+    );
+    assert_eq!(
+        Frequency::from_hz_f64(24000.0 / 1001.0),
+        Some(crate::freq!(24000 per 1001 s))
+    );
+    assert_eq!(Frequency::from_hz_f64(60.0), Some(Frequency::from_hz(60)));
+    assert_eq!(Frequency::from_hz_f64(0.5), Some(crate::freq!(1 per 2 s)));
+    assert_eq!(
+        Frequency::from_hz_f64(1.0 / 3.0),
+        Some(crate::freq!(1 per 3 s))
+    );
+    assert_eq!(
+        Frequency::from_hz_f64(44100.0),
+        Some(Frequency::from_hz(44100))
+    );
+
+    // Irrational value is approximated closely.
+    let pi = Frequency::from_hz_f64(core::f64::consts::PI).unwrap();
+    assert!((hz(pi) - core::f64::consts::PI).abs() < 1e-12);
+
+    assert_eq!(Frequency::from_hz_f64(0.0), None);
+    assert_eq!(Frequency::from_hz_f64(-60.0), None);
+    assert_eq!(Frequency::from_hz_f64(f64::NAN), None);
+    assert_eq!(Frequency::from_hz_f64(f64::INFINITY), None);
+    assert_eq!(Frequency::from_hz_f64(1e-12), None);
+    assert_eq!(Frequency::from_hz_f64(1e30), None);
+}
+
+#[test]
+fn test_frame_time() {
+    let freq = Frequency::from_hz(60);
+    assert_eq!(freq.frame_time(), Some(TimeSpan::new(16_666_666)));
+    assert_eq!(freq.budget_fraction(0.0), Some(TimeSpan::ZERO));
+    assert_eq!(freq.budget_fraction(1.0), freq.frame_time());
+    assert_eq!(freq.budget_fraction(0.25), Some(TimeSpan::new(4_166_666)));
+    assert_eq!(freq.budget_fraction(1.5), None);
+    assert_eq!(freq.budget_fraction(f32::NAN), None);
+
+    let zero = Frequency::from_hz(0);
+    assert_eq!(zero.frame_time(), None);
+    assert_eq!(zero.budget_fraction(0.5), None);
+}
+
+#[test]
+fn test_harmonics() {
+    let harmonics: Vec<_> = Frequency::from_hz(60).harmonics(3).collect();
+    assert_eq!(
+        harmonics,
+        [
+            Frequency::from_hz(60),
+            Frequency::from_hz(30),
+            Frequency::from_hz(15)
+        ]
+    );
+
+    // 15/4 Hz is 3 ticks per 800ms.
+    let harmonics: Vec<_> = Frequency::from_hz(15).harmonics(3).collect();
+    assert_eq!(
+        harmonics[2],
+        Frequency::from_raw(3, NonZeroU64::new(800_000_000).unwrap())
+    );
+
+    assert_eq!(Frequency::from_hz(0).harmonics(3).count(), 0);
+    assert_eq!(Frequency::from_hz(60).harmonics(0).count(), 0);
+
+    let slow = Frequency::new(1, NonZeroTimeSpan::new(NonZeroU64::new(1 << 62).unwrap()));
+    assert_eq!(slow.harmonics(5).count(), 2);
+}
+
+#[test]
+fn test_frequency_halved_doubled() {
+    let ntsc = Frequency::from_raw(60000, NonZeroU64::new(1_001_000_000_000).unwrap());
+    for freq in [Frequency::from_hz(60), Frequency::from_hz(15), ntsc] {
+        assert_eq!(freq.halved().unwrap().doubled(), Some(freq));
+        assert_eq!(freq.doubled().unwrap().halved(), Some(freq));
+        assert_eq!(
+            freq.doubled().unwrap().ratio_to(&freq),
+            Some((2, NonZeroU64::MIN))
+        );
+    }
+
+    assert_eq!(
+        Frequency::from_hz(15).halved(),
+        Some(Frequency::from_raw(
+            15,
+            NonZeroU64::new(2_000_000_000).unwrap()
+        ))
+    );
+    assert_eq!(
+        Frequency::from_hz(15).doubled(),
+        Some(Frequency::from_hz(30))
+    );
+    assert_eq!(Frequency::from_hz(0).halved(), Some(Frequency::from_hz(0)));
+    assert_eq!(Frequency::from_hz(0).doubled(), Some(Frequency::from_hz(0)));
+
+    let slow = Frequency::from_raw(1, NonZeroU64::MAX);
+    assert_eq!(slow.halved(), None);
+    let fast = Frequency::from_raw(u64::MAX, NonZeroU64::MIN);
+    assert_eq!(fast.doubled(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_frequency_sweep() {
+    let hz = |freq: Frequency| freq.count() as f64 * 1e9 / freq.period_nanos().get() as f64;
+    let sweep = |start: u64, end: u64, steps: usize, scale: SweepScale| -> Vec<Frequency> {
+        Frequency::sweep(
+            Frequency::from_hz(start),
+            Frequency::from_hz(end),
+            steps,
+            scale,
+        )
+        .collect()
+    };
+
+    assert_eq!(
+        sweep(30, 240, 8, SweepScale::Linear),
+        [30, 60, 90, 120, 150, 180, 210, 240].map(Frequency::from_hz)
+    );
+    assert_eq!(
+        sweep(30, 240, 4, SweepScale::Log),
+        [30, 60, 120, 240].map(Frequency::from_hz)
+    );
+    assert_eq!(
+        sweep(240, 30, 4, SweepScale::Log),
+        [240, 120, 60, 30].map(Frequency::from_hz)
+    );
+
+    // Exact rational intermediate values.
+    let linear = sweep(1, 2, 4, SweepScale::Linear);
+    assert_eq!(
+        linear[1],
+        Frequency::from_raw(4, NonZeroU64::new(3_000_000_000).unwrap())
+    );
+    assert_eq!(
+        linear[2],
+        Frequency::from_raw(5, NonZeroU64::new(3_000_000_000).unwrap())
+    );
+
+    for scale in [SweepScale::Linear, SweepScale::Log] {
+        for (start, end) in [(30, 240), (240, 30), (1, 1_000_000), (60, 60), (0, 120)] {
+            for steps in [2, 3, 7, 100] {
+                let freqs = sweep(start, end, steps, scale);
+                assert_eq!(freqs.len(), steps);
+                assert_eq!(freqs[0], Frequency::from_hz(start));
+                assert_eq!(freqs[steps - 1], Frequency::from_hz(end));
+
+                for pair in freqs.windows(2) {
+                    if start <= end {
+                        assert!(hz(pair[0]) <= hz(pair[1]), "{:?}", pair);
+                    } else {
+                        assert!(hz(pair[0]) >= hz(pair[1]), "{:?}", pair);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(sweep(30, 240, 1, scale), [Frequency::from_hz(30)]);
+        assert_eq!(sweep(30, 240, 0, scale), []);
+    }
+
+    // Log sweep has constant ratio within `f64` precision.
+    let log = sweep(1, 1_000_000, 7, SweepScale::Log);
+    for (i, freq) in log.into_iter().enumerate() {
+        let expected = 10f64.powi(i as i32);
+        assert!(
+            (hz(freq) - expected).abs() <= expected * 1e-12,
+            "{:?}",
+            freq
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_ticker_serde() {
+    use crate::span::NonZeroTimeSpanNumExt;
+
+    let freq = Frequency::new(30000, NonZeroU64::new(1001).unwrap().seconds());
+    let mut ticker = freq.ticker(TimeStamp::start());
+    let mut uninterrupted = freq.ticker(TimeStamp::start());
+
+    for step in [7, 1_000_003, 33_366_999, 5, 123_456_789] {
+        let step = TimeSpan::new(step);
+        ticker.tick_count(step);
+        uninterrupted.tick_count(step);
+
+        let json = serde_json::to_string(&ticker).unwrap();
+        ticker = serde_json::from_str(&json).unwrap();
+        assert_eq!(ticker.snapshot(), uninterrupted.snapshot(), "{}", json);
+
+        let yaml = serde_yaml::to_string(&ticker).unwrap();
+        ticker = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(ticker.snapshot(), uninterrupted.snapshot(), "{}", yaml);
+    }
+
+    let a: Vec<_> = ticker.ticks(TimeSpan::SECOND).collect();
+    let b: Vec<_> = uninterrupted.ticks(TimeSpan::SECOND).collect();
+    assert_eq!(a, b);
+
+    let ticker = Frequency::from_hz(3).ticker(TimeStamp::start());
+    let json = serde_json::to_value(&ticker).unwrap();
+    assert_eq!(json["now"], 0);
+    assert_eq!(json["next_tick"], 333_333_333);
+    assert_eq!(json["next_tick_frac"], "1/3");
+    assert_eq!(json["last_tick"], serde_json::Value::Null);
+
+    // Same moment with different denominator.
+    let json = r#"{"freq":"3/1000000000 Hz","now":0,"next_tick":333333333,"next_tick_frac":"2/6"}"#;
+    let restored: FrequencyTicker = serde_json::from_str(json).unwrap();
+    assert_eq!(restored.snapshot(), ticker.snapshot());
+
+    // Fraction not representable at this frequency.
+    let json = r#"{"freq":"3/1000000000 Hz","now":0,"next_tick":333333333,"next_tick_frac":"1/2"}"#;
+    assert!(serde_json::from_str::<FrequencyTicker>(json).is_err());
+
+    // Next tick at or before now.
+    let json = r#"{"freq":"3/1000000000 Hz","now":5,"next_tick":5}"#;
+    assert!(serde_json::from_str::<FrequencyTicker>(json).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_ticker_serde_compact() {
+    let start = TimeStamp::start();
+
+    // Next tick is more than `u64::MAX` elements away.
+    let freq = Frequency::from_hz(1_000_000_007);
+    let mut ticker = FrequencyTicker::with_delay(freq, 20_000_000_000, start);
+    let mut uninterrupted = FrequencyTicker::with_delay(freq, 20_000_000_000, start);
+
+    let bytes = bincode::serialize(&ticker).unwrap();
+    ticker = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(ticker.snapshot(), uninterrupted.snapshot());
+
+    let step = TimeSpan::new(21_000_000_000);
+    assert_eq!(ticker.tick_count(step), uninterrupted.tick_count(step));
+    assert_eq!(ticker.snapshot(), uninterrupted.snapshot());
+
+    let bytes = bincode::serialize(&ticker).unwrap();
+    ticker = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(ticker.snapshot(), uninterrupted.snapshot());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_ticker_set_tagged() {
+    let start = TimeStamp::start();
+    let mut set = TickerSet::new();
+    assert!(set.insert(7, Frequency::from_hz(2).ticker(start)).is_none());
+    assert!(set.insert(3, Frequency::from_hz(4).ticker(start)).is_none());
+    assert!(set.insert(5, Frequency::from_hz(3).ticker(start)).is_none());
+    assert_eq!(set.len(), 3);
+
+    let ticks: Vec<_> = set
+        .advance_tagged(TimeSpan::SECOND)
+        .map(|tick| {
+            (
+                tick.step.now.elapsed_since(start).as_millis(),
+                tick.source,
+                tick.index,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        ticks,
+        [
+            (250, 3, 0),
+            (333, 5, 0),
+            (500, 3, 1),
+            (500, 7, 0),
+            (666, 5, 1),
+            (750, 3, 2),
+            (1000, 3, 3),
+            (1000, 5, 2),
+            (1000, 7, 1),
+        ]
+    );
+
+    let ticks: Vec<_> = set
+        .get_mut(5)
+        .unwrap()
+        .ticks_tagged(TimeSpan::SECOND, 5)
+        .collect();
+    assert_eq!(ticks.len(), 3);
+    assert!(ticks.iter().all(|tick| tick.source == 5));
+    assert_eq!(ticks[0].index, 3);
+    assert_eq!(ticks[0], TaggedTick::new(5, 3, ticks[0].step));
+
+    assert!(set.remove(5).is_some());
+    assert!(set.get(5).is_none());
+    assert!(set.insert(3, Frequency::from_hz(1).ticker(start)).is_some());
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_tagged_tick_bytes() {
+    let tick = TaggedTick::new(
+        42,
+        7,
+        ClockStep {
+            now: TimeStamp::start() + TimeSpan::SECOND,
+            step: TimeSpan::MILLISECOND,
+        },
+    );
+
+    let bytes = bytemuck::bytes_of(&tick);
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(&bytes[16..24], &7u64.to_ne_bytes());
+    assert_eq!(&bytes[24..28], &42u32.to_ne_bytes());
+
+    // Round trip through a byte buffer as a channel would do.
+    let mut buf = [0u64; 4];
+    bytemuck::bytes_of_mut(&mut buf).copy_from_slice(bytes);
+    let decoded: &TaggedTick = bytemuck::checked::from_bytes(bytemuck::bytes_of(&buf));
+    assert_eq!(*decoded, tick);
+
+    // Zero time stamp and non-zero padding are rejected.
+    let zeroed = [0u64; 4];
+    assert!(bytemuck::checked::try_from_bytes::<TaggedTick>(bytemuck::bytes_of(&zeroed)).is_err());
+    let mut padded = buf;
+    bytemuck::bytes_of_mut(&mut padded)[28] = 1;
+    assert!(bytemuck::checked::try_from_bytes::<TaggedTick>(bytemuck::bytes_of(&padded)).is_err());
+}
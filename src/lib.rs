@@ -9,45 +9,123 @@
 //! relative to reference point.
 //!
 //! [`Clock`] is a type that represents a clock to measure time and steps.
+//! With `global_reference` feature [`GlobalClock`] does the same
+//! using global reference point shared by all its instances.
+//! [`LongClock`] uses 128-bit [`LongTimeStamp`]s for processes that run for centuries.
 //!
 //! [`Frequency`] and [`FrequencyTicker`] allow exact frequency ticker using
 //! rational values.
 //!
+//! [`SimClock`] combines real time clock, time scaling and fixed update ticker.
+//!
+//! [`RateLimiter`] allows events at limited frequency with bursts.
+//!
+//! [`Throttle`] allows action at most once per interval.
+//!
+//! [`TimeBudget`] limits time spent on work sliced into iterations.
+//! [`FrameBudget`] splits frame time into named sub-budgets.
+//!
+//! [`CallbackScheduler`] delivers payloads at given time stamps, once or repeatedly.
+//! [`WakeupPlanner`] finds real time to sleep until the earliest deadline.
+//! [`SnapshotBuffer`] interpolates between time stamped samples.
+//!
+//! [`Progress`] and [`ProgressEta`] report progress of work and estimate time remaining.
+//!
+//! [`TimeSpanHistogram`] collects time spans into exponential buckets.
+//! [`FrameTimer`] computes frame time statistics over a window of recent frames.
+//! [`TimeSpanReservoir`] keeps uniform random sample of time spans for percentile estimation.
+//! [`SlidingWindowExtrema`] tracks minimum and maximum time spans over a sliding time window.
+//!
 //! # Features
 //!
-//! - `std` - enables `std` support, including `Clock` and `ClockStep` types.
+//! - `alloc` - enables types that need allocation, like [`CallbackScheduler`],
+//!   [`TickerSet`], [`SnapshotBuffer`] and [`FrameBudget`].
+//! - `std` - enables `std` support, including `Clock` type. Implies `alloc`.
+//!
+//!   Without `std` and `alloc` core types are still available:
+//!   [`TimeSpan`], [`TimeStamp`], [`Frequency`], [`FrequencyTicker`],
+//!   [`ClockStep`], [`ClockRate`] and statistics types.
 //! - `global_reference` - enables [`TimeStamp::now`] function to get time stamp
-//! relative to global reference point that is initialized by first call to
-//! [`TimeStamp::now`].
-//! - `serde` - enables `serde` support for [`TimeSpan`] and [`Frequency`].
+//!   relative to global reference point that is initialized by first call to
+//!   [`TimeStamp::now`]. Wall-clock time of the reference point is captured too,
+//!   so time stamps can be displayed as time of day with [`TimeStamp::display_wall`].
+//! - `serde` - enables `serde` support for [`TimeSpan`] and [`Frequency`]. Implies `alloc`.
+//! - `bytemuck` - implements `bytemuck` traits for [`TimeSpan`], [`TimeStamp`],
+//!   [`ClockStep`] and [`TaggedTick`], so they can be cast to bytes.
+//! - `num-traits` - implements `num-traits` traits like `Zero` and `Bounded` for [`TimeSpan`].
 //!
 
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 #[cfg(feature = "std")]
 mod clock;
 
+#[cfg(feature = "alloc")]
+mod scheduler;
+
 #[cfg(feature = "std")]
-mod rate;
+mod sim;
+
+#[cfg(feature = "alloc")]
+mod snapshot;
 
+mod budget;
 mod freq;
+mod limiter;
+mod progress;
+mod rate;
 mod span;
 mod stamp;
+mod stats;
+mod step;
+mod throttle;
+mod wakeup;
 
 pub use crate::{
-    freq::{Frequency, FrequencyNumExt, FrequencyTicker, FrequencyTickerIter},
-    span::{TimeSpan, TimeSpanNumExt},
-    stamp::TimeStamp,
+    budget::TimeBudget,
+    freq::{
+        Frequency, FrequencyNumExt, FrequencyParseErr, FrequencyTicker, FrequencyTickerIndexedIter,
+        FrequencyTickerIter, FrequencyTickerState, PhaseRemainder, SampleClock, TaggedTick, Tick,
+    },
+    limiter::RateLimiter,
+    progress::{Progress, ProgressEta},
+    rate::{ClockRate, FrequencyOutOfRange, RampingRate, Rate, RateInterp, RateKeyframe},
+    span::{
+        NonZeroTimeSpan, NonZeroTimeSpanNumExt, RelativeFormat, TimeSpan, TimeSpanNumExt,
+        TimeSpanParseErr, TimeSpanUnit,
+    },
+    stamp::{LongTimeStamp, TimeStamp, TimeStampOutOfRange},
+    stats::{FrameTimer, SlidingWindowExtrema, TimeSpanHistogram, TimeSpanReservoir},
+    step::{ClockStep, StepCarry},
+    throttle::Throttle,
+    wakeup::{NextDeadline, WakeupPlanner},
+};
+
+#[cfg(feature = "alloc")]
+pub use crate::{
+    budget::{FrameBudget, SubBudget},
+    freq::TickerSet,
+    rate::RateEnvelope,
+    scheduler::{CallbackScheduler, RepeatHandle},
+    snapshot::{Sample, SnapshotBuffer},
 };
 
 #[cfg(feature = "std")]
 pub use crate::{
-    clock::{Clock, ClockStep},
-    rate::ClockRate,
+    budget::ClockBudget,
+    clock::{Clock, LongClock},
+    freq::SweepScale,
+    sim::{SimClock, SimStep},
 };
 
 #[cfg(feature = "global_reference")]
-pub use crate::stamp::global_reference;
+pub use crate::{
+    clock::{GlobalClock, GlobalTicker},
+    stamp::global_reference,
+};
 
 #[doc(hidden)]
 pub trait U64ORF64 {}
@@ -66,62 +144,88 @@ where
 }
 
 /// Converts human-readable expression into `TimeSpan`.
+///
+/// Accepts integer and floating point literals.
+/// Value is computed at compile time, so out of range values
+/// fail to compile instead of silently overflowing.
+/// Fractional nanoseconds of floating point values are truncated.
+///
+/// ```
+/// # use gametime::{timespan, TimeSpan};
+/// assert_eq!(timespan!(2 years), TimeSpan::YEAR * 2);
+/// assert_eq!(timespan!(1.5 days), TimeSpan::HOUR * 36);
+/// assert_eq!(timespan!(1:30:0.5), TimeSpan::MINUTE * 90 + TimeSpan::MILLISECOND * 500);
+/// ```
+///
+/// ```compile_fail
+/// # use gametime::timespan;
+/// let _ = timespan!(1000 years);
+/// ```
+///
+/// ```compile_fail
+/// # use gametime::timespan;
+/// let _ = timespan!(1e12 seconds);
+/// ```
+///
+/// ```compile_fail
+/// # use gametime::timespan;
+/// let _ = timespan!(-1.5 seconds);
+/// ```
 #[macro_export]
 macro_rules! timespan {
+    (@units $v:literal $unit:ident) => {
+        const {
+            let nanos = $v * $crate::__as($crate::TimeSpan::$unit.as_nanos() as _, &$v);
+            assert!(
+                nanos as f64 >= 0.0 && nanos as f64 <= u64::MAX as f64,
+                "time span is out of range"
+            );
+            $crate::TimeSpan::new(nanos as u64)
+        }
+    };
+
     ($y:literal y) => { $crate::timespan!($y years) };
 
-    ($y:literal years) => {{
-        let years = $y * $crate::__as($crate::TimeSpan::YEAR.as_nanos() as _, &$d);
-        $crate::TimeSpan::new(years as u64)
-    }};
+    ($y:literal years) => { $crate::timespan!(@units $y YEAR) };
 
     ($d:literal d) => { $crate::timespan!($d days) };
 
-    ($d:literal days) => {{
-        let days = $d * $crate::__as($crate::TimeSpan::DAY.as_nanos() as _, &$d);
-        $crate::TimeSpan::new(days as u64)
-    }};
+    ($d:literal days) => { $crate::timespan!(@units $d DAY) };
 
-    ($h:literal : $m:literal : $s:literal) => {{
-        let hours = $h * $crate::TimeSpan::HOUR.as_nanos();
-        let minutes = $m * $crate::TimeSpan::MINUTE.as_nanos();
-        let seconds = $s * $crate::__as($crate::TimeSpan::SECOND.as_nanos() as _, &$s);
-
-        $crate::TimeSpan::new(hours + minutes + seconds as u64)
-    }};
+    ($h:literal : $m:literal : $s:literal) => {
+        const {
+            let hours = $h * $crate::TimeSpan::HOUR.as_nanos();
+            let minutes = $m * $crate::TimeSpan::MINUTE.as_nanos();
+            let seconds = $crate::timespan!(@units $s SECOND).as_nanos();
+            $crate::TimeSpan::new(hours + minutes + seconds)
+        }
+    };
 
     ($h:literal h) => { $crate::timespan!($h hours) };
 
     ($h:literal hrs) => { $crate::timespan!($h hours) };
 
-    ($h:literal hours) => {{
-        let hours = $h * $crate::__as($crate::TimeSpan::HOUR.as_nanos() as _, &$h);
-        $crate::TimeSpan::new(hours as u64)
-    }};
+    ($h:literal hours) => { $crate::timespan!(@units $h HOUR) };
 
-    ($m:literal : $s:literal) => {{
-        let minutes = $m * $crate::TimeSpan::MINUTE.as_nanos();
-        let seconds = $s * $crate::__as($crate::TimeSpan::SECOND.as_nanos() as _, &$s);
-        $crate::TimeSpan::new(minutes + seconds as u64)
-    }};
+    ($m:literal : $s:literal) => {
+        const {
+            let minutes = $m * $crate::TimeSpan::MINUTE.as_nanos();
+            let seconds = $crate::timespan!(@units $s SECOND).as_nanos();
+            $crate::TimeSpan::new(minutes + seconds)
+        }
+    };
 
     ($m:literal m) => { $crate::timespan!($m minutes) };
 
     ($m:literal mins) => { $crate::timespan!($m minutes) };
 
-    ($m:literal minutes) => {{
-        let minutes = $m * $crate::__as($crate::TimeSpan::MINUTE.as_nanos() as _, &$m);
-        $crate::TimeSpan::new(minutes as u64)
-    }};
+    ($m:literal minutes) => { $crate::timespan!(@units $m MINUTE) };
 
     ($s:literal s) => { $crate::timespan!($s seconds) };
 
     ($s:literal secs) => { $crate::timespan!($s seconds) };
 
-    ($s:literal seconds) => {{
-        let seconds = $s * $crate::__as($crate::TimeSpan::SECOND.as_nanos() as _, &$s);
-        $crate::TimeSpan::new(seconds as u64)
-    }};
+    ($s:literal seconds) => { $crate::timespan!(@units $s SECOND) };
 
     ($(1)?year) => { $crate::TimeSpan::YEAR };
     ($(1)?weak) => { $crate::TimeSpan::WEEK };
@@ -141,6 +245,107 @@ macro_rules! ts {
     ($($tt:tt)*) => { $crate::timespan!($($tt)*) };
 }
 
+/// Converts human-readable expression into `Frequency`.
+///
+/// Supports units `hz`, `Hz`, `HZ`, `khz`, `kHz`, `KHz`, `KHZ`, `MHz`, `MHZ`, `GHz`, `GHZ`,
+/// `bpm`, `BPM` and count per span, where span is any expression accepted by [`timespan!`].
+/// Units are read the same way as by `str::parse`,
+/// so lowercase `mhz` and `ghz` are not accepted.
+/// Usable in `const` context.
+///
+/// ```
+/// # use gametime::{freq, Frequency, TimeSpan};
+/// const FIXED: Frequency = freq!(60 Hz);
+/// assert_eq!(FIXED, Frequency::from_hz(60));
+/// assert_eq!(freq!(3 per 10 s), Frequency::from_raw(3, (TimeSpan::SECOND * 10).as_nanos().try_into().unwrap()));
+/// ```
+///
+/// ```compile_fail
+/// # use gametime::freq;
+/// let _ = freq!(500 mhz);
+/// ```
+#[macro_export]
+macro_rules! freq {
+    ($v:literal hz) => { $crate::Frequency::from_hz($v) };
+    ($v:literal Hz) => { $crate::Frequency::from_hz($v) };
+    ($v:literal HZ) => { $crate::Frequency::from_hz($v) };
+
+    ($v:literal khz) => { $crate::Frequency::from_khz($v) };
+    ($v:literal kHz) => { $crate::Frequency::from_khz($v) };
+    ($v:literal KHz) => { $crate::Frequency::from_khz($v) };
+    ($v:literal KHZ) => { $crate::Frequency::from_khz($v) };
+
+    ($v:literal MHz) => { $crate::Frequency::from_mhz($v) };
+    ($v:literal MHZ) => { $crate::Frequency::from_mhz($v) };
+
+    ($v:literal GHz) => { $crate::Frequency::from_ghz($v) };
+    ($v:literal GHZ) => { $crate::Frequency::from_ghz($v) };
+
+    ($v:literal bpm) => { $crate::freq!($v per 1 minute) };
+    ($v:literal BPM) => { $crate::freq!($v per 1 minute) };
+
+    ($c:literal per $($span:tt)+) => {
+        $crate::Frequency::from_raw(
+            $c,
+            match ::core::num::NonZeroU64::new($crate::timespan!($($span)+).as_nanos()) {
+                Some(period) => period,
+                None => panic!("frequency period must not be zero"),
+            },
+        )
+    };
+}
+
+#[cfg(test)]
+const TEST_FREQS: [Frequency; 6] = [
+    freq!(60 Hz),
+    freq!(1 khz),
+    freq!(2 MHz),
+    freq!(1 GHz),
+    freq!(120 bpm),
+    freq!(3 per 10 s),
+];
+
+#[test]
+fn test_freq_macro() {
+    assert_eq!(TEST_FREQS[0], Frequency::from_hz(60));
+    assert_eq!(TEST_FREQS[1], Frequency::from_hz(1000));
+    assert_eq!(TEST_FREQS[2], Frequency::from_khz(2000));
+    assert_eq!(TEST_FREQS[3], Frequency::from_mhz(1000));
+    assert_eq!(TEST_FREQS[4], Frequency::from_hz(2));
+    assert_eq!(
+        (TEST_FREQS[5].count(), TEST_FREQS[5].period_nanos().get()),
+        (3, 10_000_000_000)
+    );
+
+    // Macro units match parsed ones.
+    for (freq, unit) in [
+        (freq!(5 hz), "hz"),
+        (freq!(5 Hz), "Hz"),
+        (freq!(5 HZ), "HZ"),
+        (freq!(5 khz), "khz"),
+        (freq!(5 kHz), "kHz"),
+        (freq!(5 KHz), "KHz"),
+        (freq!(5 KHZ), "KHZ"),
+        (freq!(5 MHz), "MHz"),
+        (freq!(5 MHZ), "MHZ"),
+        (freq!(5 GHz), "GHz"),
+        (freq!(5 GHZ), "GHZ"),
+    ] {
+        assert_eq!(format!("5 {}", unit).parse::<Frequency>(), Ok(freq));
+    }
+    assert!("5 mhz".parse::<Frequency>().is_err());
+    assert!("5 ghz".parse::<Frequency>().is_err());
+
+    assert_eq!(freq!(30 per 1:00), freq!(1 per 2 s));
+    assert_eq!(
+        freq!(60000 per 1001 s),
+        Frequency::from_raw(
+            60000,
+            core::num::NonZeroU64::new(1_001_000_000_000).unwrap()
+        )
+    );
+}
+
 #[cfg(test)]
 const TEST_SPANS: [TimeSpan; 6] = [
     timespan!(1 day),   // 1 day
@@ -162,9 +367,65 @@ fn test_timespan_macro() {
     assert_eq!(TEST_SPANS[3], TimeSpan::MINUTE * 2 + TimeSpan::SECOND * 3);
     assert_eq!(TEST_SPANS[4], TimeSpan::MINUTE * 3);
     assert_eq!(TEST_SPANS[5], TimeSpan::SECOND * 42);
+
+    assert_eq!(timespan!(2 y), TimeSpan::YEAR * 2);
+    assert_eq!(timespan!(0.5 years), TimeSpan::YEAR / 2);
+    assert_eq!(timespan!(3 d), TimeSpan::DAY * 3);
+    assert_eq!(timespan!(0.25 days), TimeSpan::HOUR * 6);
+    assert_eq!(timespan!(1.5 h), TimeSpan::MINUTE * 90);
+    assert_eq!(timespan!(0.001 s), TimeSpan::MILLISECOND);
+    assert_eq!(timespan!(1:2.5), TimeSpan::MILLISECOND * 62_500);
+
+    // Largest representable values.
+    assert_eq!(timespan!(213503 days), TimeSpan::DAY * 213503);
+    assert_eq!(timespan!(18446744073 secs), TimeSpan::SECOND * 18446744073);
+    assert_eq!(timespan!(584 years), TimeSpan::YEAR * 584);
+}
+
+/// Appends LEB128 encoding of the value to the buffer.
+#[cfg(feature = "alloc")]
+fn encode_varint(mut value: u64, buf: &mut alloc::vec::Vec<u8>) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Decodes LEB128 encoded value from the start of the buffer.
+/// Returns value and number of bytes read.
+///
+/// Only the shortest encoding, as produced by `encode_varint`, is accepted,
+/// so each value has exactly one valid encoding.
+fn decode_varint(buf: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in buf.iter().enumerate().take(10) {
+        let bits = (byte & 0x7F) as u64;
+        if i == 9 && bits > 1 {
+            return None;
+        }
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            // Zero last byte means value could be encoded with fewer bytes.
+            if byte == 0 && i > 0 {
+                return None;
+            }
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let temp = b;
+        b = a % b;
+        a = temp;
+    }
+    a
 }
 
-fn gcd(mut a: u64, mut b: u64) -> u64 {
+const fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
     while b != 0 {
         let temp = b;
         b = a % b;
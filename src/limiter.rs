@@ -0,0 +1,101 @@
+//! Contains `RateLimiter` type that allows events at limited frequency.
+
+use crate::{freq::Frequency, span::TimeSpan, stamp::TimeStamp};
+
+/// Token bucket rate limiter.
+///
+/// Tokens are refilled at given frequency and accumulate up to burst size.
+/// Each allowed event consumes one token.
+#[derive(Clone)]
+pub struct RateLimiter {
+    freq: Frequency,
+    burst: u64,
+
+    /// Accumulated tokens in frequency elements.
+    /// One token is one frequency period.
+    elements: u128,
+
+    /// Time stamp of the last refill.
+    now: TimeStamp,
+}
+
+impl RateLimiter {
+    /// Creates new rate limiter with given frequency and burst size.
+    /// Limiter starts with full bucket.
+    pub fn new(freq: Frequency, burst: u64, now: TimeStamp) -> Self {
+        RateLimiter {
+            freq,
+            burst,
+            elements: burst as u128 * freq.period_nanos().get() as u128,
+            now,
+        }
+    }
+
+    /// Returns frequency at which tokens are refilled.
+    pub fn frequency(&self) -> Frequency {
+        self.freq
+    }
+
+    /// Returns maximum number of accumulated tokens.
+    pub fn burst(&self) -> u64 {
+        self.burst
+    }
+
+    /// Returns number of tokens available at `now`.
+    pub fn available(&mut self, now: TimeStamp) -> u64 {
+        self.refill(now);
+        (self.elements / self.freq.period_nanos().get() as u128) as u64
+    }
+
+    /// Tries to consume one token at `now`.
+    /// Returns `true` if event is allowed.
+    pub fn try_acquire(&mut self, now: TimeStamp) -> bool {
+        self.refill(now);
+
+        let period = self.freq.period_nanos().get() as u128;
+        if self.elements >= period {
+            self.elements -= period;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn refill(&mut self, now: TimeStamp) {
+        let span = now
+            .checked_elapsed_since(self.now)
+            .unwrap_or(TimeSpan::ZERO);
+        self.now = self.now.max(now);
+
+        let max = self.burst as u128 * self.freq.period_nanos().get() as u128;
+        let elements = span.as_nanos() as u128 * self.freq.count() as u128;
+        self.elements = self.elements.saturating_add(elements).min(max);
+    }
+}
+
+#[test]
+fn test_limiter_rate() {
+    let start = TimeStamp::start();
+    let mut limiter = RateLimiter::new(Frequency::from_hz(10), 1, start);
+
+    let allowed = (0..1000)
+        .filter(|&ms| limiter.try_acquire(start + TimeSpan::MILLISECOND * ms))
+        .count();
+
+    assert_eq!(allowed, 10);
+}
+
+#[test]
+fn test_limiter_burst() {
+    let start = TimeStamp::start();
+    let mut limiter = RateLimiter::new(Frequency::from_hz(10), 5, start);
+
+    let now = start + TimeSpan::SECOND * 10;
+    assert_eq!(limiter.available(now), 5);
+
+    let allowed = (0..100).filter(|_| limiter.try_acquire(now)).count();
+    assert_eq!(allowed, 5);
+
+    assert!(!limiter.try_acquire(now + TimeSpan::MILLISECOND * 99));
+    assert!(limiter.try_acquire(now + TimeSpan::MILLISECOND * 100));
+}
@@ -0,0 +1,261 @@
+//! Contains `Progress` type for progress reporting based on time spans.
+
+use crate::span::TimeSpan;
+
+/// Progress of the work in range `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Progress {
+    fraction: f64,
+}
+
+impl Progress {
+    /// Progress that is not started.
+    pub const ZERO: Self = Progress { fraction: 0.0 };
+
+    /// Progress that is complete.
+    pub const COMPLETE: Self = Progress { fraction: 1.0 };
+
+    /// Progress below this value is considered not started for ETA estimation.
+    const MIN_FOR_ETA: f64 = 1e-6;
+
+    /// Returns progress of `elapsed` out of `total`, clamped to `[0, 1]`.
+    ///
+    /// Zero `total` is complete.
+    pub fn of(elapsed: TimeSpan, total: TimeSpan) -> Self {
+        if elapsed >= total {
+            return Progress::COMPLETE;
+        }
+
+        Progress {
+            fraction: elapsed.as_nanos() as f64 / total.as_nanos() as f64,
+        }
+    }
+
+    /// Returns progress from fraction, clamped to `[0, 1]`.
+    /// NaN is treated as zero.
+    pub fn from_f64(fraction: f64) -> Self {
+        if fraction >= 1.0 {
+            Progress::COMPLETE
+        } else if fraction > 0.0 {
+            Progress { fraction }
+        } else {
+            Progress::ZERO
+        }
+    }
+
+    /// Returns progress as fraction in `[0, 1]`.
+    pub fn as_f32(&self) -> f32 {
+        self.fraction as f32
+    }
+
+    /// Returns progress as fraction in `[0, 1]`.
+    pub fn as_f64(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Returns progress in thousandths, rounded down, in `[0, 1000]`.
+    pub fn as_permille(&self) -> u16 {
+        (self.fraction * 1000.0) as u16
+    }
+
+    /// Returns `true` if progress is complete.
+    pub fn is_complete(&self) -> bool {
+        self.fraction >= 1.0
+    }
+
+    /// Returns part of `total` that remains.
+    pub fn remaining(&self, total: TimeSpan) -> TimeSpan {
+        TimeSpan::new((total.as_nanos() as f64 * (1.0 - self.fraction)) as u64)
+    }
+
+    /// Returns estimated real time remaining until completion,
+    /// assuming linear progress since start over `elapsed_real`.
+    ///
+    /// Returns `None` when progress is too close to zero to estimate.
+    /// See [`ProgressEta`] for smoothed estimation.
+    pub fn eta(&self, elapsed_real: TimeSpan) -> Option<TimeSpan> {
+        if self.fraction < Self::MIN_FOR_ETA {
+            return None;
+        }
+
+        let remaining = elapsed_real.as_nanos() as f64 * (1.0 - self.fraction) / self.fraction;
+        Some(TimeSpan::new(remaining as u64))
+    }
+}
+
+/// Float to int casts saturate, so huge estimates are clamped to `TimeSpan::MAX`.
+fn remaining_at_rate(progress: Progress, rate: f64) -> TimeSpan {
+    TimeSpan::new(((1.0 - progress.fraction) / rate) as u64)
+}
+
+/// Estimates real time remaining until completion
+/// from a series of progress updates.
+///
+/// Rate of progress is smoothed with exponential moving average,
+/// so estimation doesn't jump wildly when progress is uneven.
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressEta {
+    smoothing: f64,
+    last: Option<(Progress, TimeSpan)>,
+
+    /// Smoothed progress per nanosecond.
+    rate: Option<f64>,
+}
+
+impl ProgressEta {
+    /// Default smoothing factor.
+    pub const DEFAULT_SMOOTHING: f64 = 0.1;
+
+    /// Returns new estimator with given smoothing factor in `(0, 1]`.
+    /// Smaller values produce smoother estimation that reacts slower to changes of rate.
+    /// Factor of 1 disables smoothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `smoothing` is not in `(0, 1]`.
+    pub fn new(smoothing: f64) -> Self {
+        assert!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "smoothing factor must be in (0, 1]"
+        );
+
+        ProgressEta {
+            smoothing,
+            last: None,
+            rate: None,
+        }
+    }
+
+    /// Records `progress` reached after `elapsed_real` since start
+    /// and returns estimated real time remaining until completion.
+    ///
+    /// Returns `None` until rate of progress can be estimated.
+    /// Updates with `elapsed_real` not greater than the previous one are ignored.
+    pub fn update(&mut self, progress: Progress, elapsed_real: TimeSpan) -> Option<TimeSpan> {
+        let (last_progress, last_real) = self.last.unwrap_or((Progress::ZERO, TimeSpan::ZERO));
+
+        if elapsed_real > last_real {
+            let dt = (elapsed_real - last_real).as_nanos() as f64;
+            let rate = (progress.fraction - last_progress.fraction).max(0.0) / dt;
+
+            self.rate = Some(match self.rate {
+                None => rate,
+                Some(smoothed) => smoothed + (rate - smoothed) * self.smoothing,
+            });
+            self.last = Some((progress, elapsed_real));
+        }
+
+        self.eta()
+    }
+
+    /// Returns estimated real time remaining until completion
+    /// as of the last update.
+    pub fn eta(&self) -> Option<TimeSpan> {
+        let (progress, _) = self.last?;
+        if progress.is_complete() {
+            return Some(TimeSpan::ZERO);
+        }
+
+        match self.rate {
+            Some(rate) if rate > 0.0 => Some(remaining_at_rate(progress, rate)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for ProgressEta {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SMOOTHING)
+    }
+}
+
+#[test]
+fn test_progress() {
+    let total = TimeSpan::SECOND * 10;
+
+    let progress = Progress::of(TimeSpan::SECOND * 4, total);
+    assert_eq!(progress.as_f32(), 0.4);
+    assert_eq!(progress.as_permille(), 400);
+    assert_eq!(progress.remaining(total), TimeSpan::SECOND * 6);
+    assert!(!progress.is_complete());
+
+    assert_eq!(
+        Progress::of(TimeSpan::SECOND * 11, total),
+        Progress::COMPLETE
+    );
+    assert_eq!(
+        Progress::of(TimeSpan::ZERO, TimeSpan::ZERO),
+        Progress::COMPLETE
+    );
+    assert_eq!(Progress::of(TimeSpan::ZERO, total), Progress::ZERO);
+    assert_eq!(Progress::COMPLETE.as_permille(), 1000);
+    assert_eq!(Progress::COMPLETE.remaining(total), TimeSpan::ZERO);
+    assert_eq!(Progress::ZERO.remaining(total), total);
+
+    assert_eq!(Progress::from_f64(-0.5), Progress::ZERO);
+    assert_eq!(Progress::from_f64(f64::NAN), Progress::ZERO);
+    assert_eq!(Progress::from_f64(2.0), Progress::COMPLETE);
+}
+
+#[test]
+fn test_progress_eta() {
+    assert_eq!(Progress::ZERO.eta(TimeSpan::SECOND), None);
+    assert_eq!(
+        Progress::from_f64(0.25).eta(TimeSpan::SECOND),
+        Some(TimeSpan::SECOND * 3)
+    );
+    assert_eq!(
+        Progress::COMPLETE.eta(TimeSpan::SECOND),
+        Some(TimeSpan::ZERO)
+    );
+    assert_eq!(Progress::from_f64(1e-9).eta(TimeSpan::SECOND), None);
+}
+
+#[test]
+fn test_progress_eta_smoothing() {
+    // Linear progress over 100 seconds.
+    let mut eta = ProgressEta::default();
+    assert_eq!(eta.eta(), None);
+    for i in 1..100u64 {
+        let real = TimeSpan::SECOND * i;
+        let remaining = eta
+            .update(Progress::from_f64(i as f64 / 100.0), real)
+            .unwrap();
+        let error = remaining.as_secs_f64() - (100 - i) as f64;
+        assert!(error.abs() < 1e-3, "{} at {}", remaining, i);
+    }
+
+    // Uneven progress, alternating fast and stalled steps.
+    let mut smoothed = ProgressEta::default();
+    let mut raw = ProgressEta::new(1.0);
+    let mut progress = 0.0;
+    let mut smoothed_range = (f64::MAX, 0.0f64);
+    let mut raw_range = (f64::MAX, 0.0f64);
+    for i in 1..50u64 {
+        progress += if i % 2 == 0 { 0.018 } else { 0.002 };
+        let real = TimeSpan::SECOND * i;
+        let p = Progress::from_f64(progress);
+
+        let s = smoothed
+            .update(p, real)
+            .map_or(f64::MAX, |eta| eta.as_secs_f64());
+        let r = raw
+            .update(p, real)
+            .map_or(f64::MAX, |eta| eta.as_secs_f64());
+        if i > 10 {
+            smoothed_range = (smoothed_range.0.min(s), smoothed_range.1.max(s));
+            raw_range = (raw_range.0.min(r), raw_range.1.max(r));
+        }
+    }
+    assert!(smoothed_range.1 - smoothed_range.0 < (raw_range.1 - raw_range.0) / 4.0);
+
+    // Stalled progress has no estimation, stale updates are ignored.
+    let mut stalled = ProgressEta::new(1.0);
+    assert_eq!(stalled.update(Progress::ZERO, TimeSpan::SECOND), None);
+    assert_eq!(stalled.update(Progress::ZERO, TimeSpan::ZERO), None);
+    assert_eq!(
+        stalled.update(Progress::COMPLETE, TimeSpan::SECOND * 2),
+        Some(TimeSpan::ZERO)
+    );
+}
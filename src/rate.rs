@@ -2,10 +2,296 @@
 //! that handle time spans and time stamps
 //! where actual passing time spans are provided externally.
 
-use core::num::NonZeroU64;
+use core::{cmp::Ordering, fmt, num::NonZeroU64, ops::Mul};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 use crate::{gcd, span::TimeSpan, stamp::TimeStamp, ClockStep, Frequency, FrequencyTicker};
 
+/// Exact rational rate.
+///
+/// Rates are compared by value, so `1/2` is equal to `2/4`.
+#[derive(Clone, Copy, Debug)]
+pub struct Rate {
+    nom: u64,
+    denom: NonZeroU64,
+}
+
+impl Rate {
+    /// Zero rate.
+    pub const ZERO: Self = Rate {
+        nom: 0,
+        denom: NonZeroU64::MIN,
+    };
+
+    /// Rate of one.
+    pub const ONE: Self = Rate {
+        nom: 1,
+        denom: NonZeroU64::MIN,
+    };
+
+    /// Returns rate `nom / denom`.
+    #[inline(always)]
+    pub const fn new(nom: u64, denom: NonZeroU64) -> Self {
+        Rate { nom, denom }
+    }
+
+    /// Returns numerator of the rate.
+    #[inline(always)]
+    pub const fn nom(&self) -> u64 {
+        self.nom
+    }
+
+    /// Returns denominator of the rate.
+    #[inline(always)]
+    pub const fn denom(&self) -> NonZeroU64 {
+        self.denom
+    }
+
+    /// Returns the same rate with numerator and denominator reduced by their gcd.
+    #[inline(always)]
+    pub fn reduced(&self) -> Self {
+        let gcd = gcd(self.nom, self.denom.get());
+        Rate {
+            nom: self.nom / gcd,
+            denom: NonZeroU64::new(self.denom.get() / gcd).unwrap(),
+        }
+    }
+
+    /// Returns rational approximation of the float value.
+    ///
+    /// Approximation is computed in `f32`, so it may differ from
+    /// [`Rate::from_f64`] of the same value.
+    ///
+    /// Negative values and NaN are converted to zero,
+    /// infinity and values too large are saturated to `u64::MAX`.
+    pub fn from_f32(value: f32) -> Self {
+        const EPSILON: f32 = 1e-6;
+        const MAX_ITER: usize = 50;
+
+        let v = value.max(0.0);
+        if v >= u64::MAX as f32 {
+            return Rate::new(u64::MAX, NonZeroU64::MIN);
+        }
+
+        let mut d = 1;
+        let mut n = v;
+
+        for _ in 0..MAX_ITER {
+            let f = fract_f32(n);
+            if f < EPSILON {
+                break;
+            }
+
+            if d > u32::MAX as u64 {
+                break;
+            }
+
+            d = ceil_f32(d as f32 / f);
+            n = v * d as f32;
+        }
+
+        // Truncation for non-negative values.
+        let z = n as u64;
+        Rate::new(z, NonZeroU64::new(d).unwrap()).reduced()
+    }
+
+    /// Returns rational approximation of the float value.
+    ///
+    /// Negative values and NaN are converted to zero,
+    /// infinity and values too large are saturated to `u64::MAX`.
+    pub fn from_f64(value: f64) -> Self {
+        const EPSILON: f64 = 1e-6;
+        const MAX_ITER: usize = 50;
+
+        let v = value.max(0.0);
+        if v >= u64::MAX as f64 {
+            return Rate::new(u64::MAX, NonZeroU64::MIN);
+        }
+
+        let mut d = 1;
+        let mut n = v;
+
+        for _ in 0..MAX_ITER {
+            let f = fract(n);
+            if f < EPSILON {
+                break;
+            }
+
+            if d > u32::MAX as u64 {
+                break;
+            }
+
+            d = ceil(d as f64 / f);
+            n = v * d as f64;
+        }
+
+        // Truncation for non-negative values.
+        let z = n as u64;
+        Rate::new(z, NonZeroU64::new(d).unwrap()).reduced()
+    }
+
+    /// Returns rate as float value.
+    #[inline(always)]
+    pub fn as_f64(&self) -> f64 {
+        self.nom as f64 / self.denom.get() as f64
+    }
+
+    /// Returns product of two rates.
+    /// Returns `None` if result can't be represented.
+    pub fn checked_mul(self, rhs: Rate) -> Option<Rate> {
+        let lhs = self.reduced();
+        let rhs = rhs.reduced();
+
+        // Cross-reduce so that result is irreducible.
+        let gcd1 = gcd(lhs.nom, rhs.denom.get());
+        let gcd2 = gcd(rhs.nom, lhs.denom.get());
+
+        let nom = (lhs.nom / gcd1) as u128 * (rhs.nom / gcd2) as u128;
+        let denom = (lhs.denom.get() / gcd2) as u128 * (rhs.denom.get() / gcd1) as u128;
+
+        Some(Rate {
+            nom: nom.try_into().ok()?,
+            denom: NonZeroU64::new(denom.try_into().ok()?)?,
+        })
+    }
+
+    /// Returns inverse rate.
+    /// Returns `None` for zero rate.
+    #[inline(always)]
+    pub fn inverse(self) -> Option<Rate> {
+        Some(Rate {
+            nom: self.denom.get(),
+            denom: NonZeroU64::new(self.nom)?,
+        })
+    }
+
+    #[inline(always)]
+    fn cross(&self, other: &Rate) -> (u128, u128) {
+        (
+            self.nom as u128 * other.denom.get() as u128,
+            other.nom as u128 * self.denom.get() as u128,
+        )
+    }
+}
+
+impl Default for Rate {
+    #[inline(always)]
+    fn default() -> Self {
+        Rate::ONE
+    }
+}
+
+impl PartialEq for Rate {
+    #[inline(always)]
+    fn eq(&self, other: &Rate) -> bool {
+        let (lhs, rhs) = self.cross(other);
+        lhs == rhs
+    }
+}
+
+impl Eq for Rate {}
+
+impl PartialOrd for Rate {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Rate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rate {
+    #[inline(always)]
+    fn cmp(&self, other: &Rate) -> Ordering {
+        let (lhs, rhs) = self.cross(other);
+        lhs.cmp(&rhs)
+    }
+}
+
+impl Mul for Rate {
+    type Output = Rate;
+
+    #[inline(always)]
+    fn mul(self, rhs: Rate) -> Rate {
+        self.checked_mul(rhs)
+            .expect("overflow when multiplying rates")
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.nom, self.denom)
+    }
+}
+
+/// Returns fractional part of non-negative value without `std`.
+fn fract(value: f64) -> f64 {
+    // Values this large have no fractional part.
+    if value >= (1u64 << f64::MANTISSA_DIGITS) as f64 {
+        return 0.0;
+    }
+    value - (value as u64) as f64
+}
+
+/// Returns non-negative value rounded up without `std`.
+fn ceil(value: f64) -> u64 {
+    let trunc = value as u64;
+    if (trunc as f64) < value {
+        trunc.saturating_add(1)
+    } else {
+        trunc
+    }
+}
+
+/// Returns fractional part of non-negative value without `std`.
+fn fract_f32(value: f32) -> f32 {
+    // Values this large have no fractional part.
+    if value >= (1u64 << f32::MANTISSA_DIGITS) as f32 {
+        return 0.0;
+    }
+    value - (value as u64) as f32
+}
+
+/// Returns non-negative value rounded up without `std`.
+fn ceil_f32(value: f32) -> u64 {
+    let trunc = value as u64;
+    if (trunc as f32) < value {
+        trunc.saturating_add(1)
+    } else {
+        trunc
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rate {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            (self.nom, self.denom).serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = alloc::string::String::deserialize(deserializer)?;
+            let (nom, denom) = s.split_once('/').unwrap_or((&s, "1"));
+            let nom = nom.trim().parse().map_err(serde::de::Error::custom)?;
+            let denom = denom.trim().parse().map_err(serde::de::Error::custom)?;
+            Ok(Rate::new(nom, denom))
+        } else {
+            let (nom, denom) = <(u64, NonZeroU64)>::deserialize(deserializer)?;
+            Ok(Rate::new(nom, denom))
+        }
+    }
+}
+
 /// Time measuring device.
 /// Uses system monotonic clock counter
 /// and yields `ClockStep`s for each step.
@@ -14,9 +300,14 @@ use crate::{gcd, span::TimeSpan, stamp::TimeStamp, ClockStep, Frequency, Frequen
 #[derive(Clone)] // Not Copy to avoid accidental copying.
 pub struct ClockRate {
     now: TimeStamp,
-    nom: u64,
-    denom: NonZeroU64,
-    until_next: u64,
+    rate: Rate,
+
+    /// Scaled nanoseconds accumulated towards the next clock nanosecond.
+    /// Always less than rate's denominator.
+    remainder: u64,
+
+    /// Real time stamp of the last `step_to` call.
+    last_real: Option<TimeStamp>,
 }
 
 impl Default for ClockRate {
@@ -32,19 +323,22 @@ impl ClockRate {
     pub fn new() -> Self {
         ClockRate {
             now: TimeStamp::start(),
-            nom: 1,
-            denom: NonZeroU64::new(1).unwrap(),
-            until_next: 0,
+            rate: Rate::ONE,
+            remainder: 0,
+            last_real: None,
         }
     }
 
     /// Resets the clock.
     /// Sets start to the given instant.
     /// And set clocks to start.
+    ///
+    /// Next [`ClockRate::step_to`] establishes new baseline.
     #[inline(always)]
     pub fn reset(&mut self) {
         self.now = TimeStamp::start();
-        self.until_next = 0;
+        self.remainder = 0;
+        self.last_real = None;
     }
 
     /// Sets current clock time to given time stamp.
@@ -65,9 +359,7 @@ impl ClockRate {
 
     /// Set rate to specified float value.
     pub fn set_rate(&mut self, rate: f32) {
-        let (nom, denom) = rate2ratio(rate);
-        self.nom = nom;
-        self.denom = denom;
+        self.set_rate_exact(Rate::from_f32(rate));
     }
 
     /// Set rate to specified float value.
@@ -78,13 +370,39 @@ impl ClockRate {
 
     /// Returns current rate.
     pub fn rate(&self) -> f64 {
-        self.nom as f64 / self.denom.get() as f64
+        self.rate.as_f64()
+    }
+
+    /// Set rate to specified exact rate.
+    ///
+    /// Progress towards the next clock nanosecond is preserved,
+    /// rescaled to the new rate and rounded down.
+    pub fn set_rate_exact(&mut self, rate: Rate) {
+        let remainder =
+            self.remainder as u128 * rate.denom().get() as u128 / self.rate.denom().get() as u128;
+
+        self.rate = rate;
+        self.remainder = remainder as u64;
+        debug_assert!(self.remainder < self.rate.denom().get());
+    }
+
+    /// Set rate to specified exact rate.
+    pub fn with_rate_exact(mut self, rate: Rate) -> Self {
+        self.set_rate_exact(rate);
+        self
+    }
+
+    /// Returns current exact rate.
+    pub fn rate_exact(&self) -> Rate {
+        self.rate
     }
 
     /// Set rate to specified ratio.
+    ///
+    /// Progress towards the next clock nanosecond is preserved,
+    /// rescaled to the new ratio and rounded down.
     pub fn set_rate_ratio(&mut self, nom: u64, denom: NonZeroU64) {
-        self.nom = nom;
-        self.denom = denom;
+        self.set_rate_exact(Rate::new(nom, denom));
     }
 
     /// Set rate to specified ratio.
@@ -95,32 +413,43 @@ impl ClockRate {
 
     /// Returns current rate ratio.
     pub fn rate_ratio(&mut self) -> (u64, NonZeroU64) {
-        (self.nom, self.denom)
+        (self.rate.nom(), self.rate.denom())
+    }
+
+    /// Discards progress towards the next clock nanosecond.
+    ///
+    /// Next step behaves as the first step of a new clock
+    /// with the same rate and current time stamp.
+    pub fn reset_phase(&mut self) {
+        self.remainder = 0;
     }
 
     /// Set rate to 0.
     pub fn pause(&mut self) {
-        self.nom = 0;
+        self.rate = Rate::new(0, self.rate.denom());
+    }
+
+    /// Returns `true` if rate is 0.
+    pub fn is_paused(&self) -> bool {
+        self.rate.nom() == 0
     }
 
     /// Advances the clock by given time span and returns `ClockStep` result.
     /// with new time stamp and time span since previous step.
+    ///
+    /// Clock time never drifts from the exact scaled time.
+    /// After any sequence of steps at constant rate the clock advanced by
+    /// `floor(total * nom / denom)` nanoseconds.
     pub fn step(&mut self, span: TimeSpan) -> ClockStep {
-        let nanos = span.as_nanos();
-        let nom_nanos = nanos * self.nom;
-
-        if self.until_next > nom_nanos {
-            // Same game nanosecond.
-            self.until_next -= nom_nanos;
-            return ClockStep {
-                now: self.now,
-                step: TimeSpan::ZERO,
-            };
-        }
+        debug_assert!(self.remainder < self.rate.denom().get());
+
+        let scaled = self.remainder as u128 + span.as_nanos() as u128 * self.rate.nom() as u128;
+        let denom = self.rate.denom().get() as u128;
 
-        let clock_nanos = (nom_nanos - self.until_next) / self.denom;
-        let nom_nanos_left = (nom_nanos - self.until_next) % self.denom;
-        self.until_next = self.denom.get() - nom_nanos_left;
+        let clock_nanos: u64 = (scaled / denom)
+            .try_into()
+            .expect("overflow when scaling time span by clock rate");
+        self.remainder = (scaled % denom) as u64;
 
         let clock_span = TimeSpan::new(clock_nanos);
         self.now += clock_span;
@@ -131,65 +460,411 @@ impl ClockRate {
         }
     }
 
+    /// Returns real time span after which clock reaches `game_deadline`
+    /// when advanced by [`ClockRate::step`] from its current state.
+    ///
+    /// This is exact inverse of scaling, i.e. the least real span
+    /// that advances the clock to the deadline, taking sub-nanosecond progress into account.
+    /// Returns zero if deadline is already reached.
+    /// Returns `None` if clock is paused or the span doesn't fit into `TimeSpan`.
+    pub fn real_time_until(&self, game_deadline: TimeStamp) -> Option<TimeSpan> {
+        let game = match game_deadline.checked_elapsed_since(self.now) {
+            None | Some(TimeSpan::ZERO) => return Some(TimeSpan::ZERO),
+            Some(game) => game,
+        };
+        if self.is_paused() {
+            return None;
+        }
+
+        // Least `real` such that `remainder + real * nom >= game * denom`.
+        let scaled = game.as_nanos() as u128 * self.rate.denom().get() as u128;
+        let real = scaled
+            .saturating_sub(self.remainder as u128)
+            .div_ceil(self.rate.nom() as u128);
+        Some(TimeSpan::new(real.try_into().ok()?))
+    }
+
+    /// Advances the clock to given real time stamp and returns `ClockStep` result.
+    ///
+    /// Real time span since previous call is scaled as in [`ClockRate::step`].
+    /// First call establishes the baseline and reports zero step.
+    /// Real time stamps earlier than the latest one result in zero step.
+    pub fn step_to(&mut self, real_now: TimeStamp) -> ClockStep {
+        let span = match self.last_real {
+            None => TimeSpan::ZERO,
+            Some(last) => real_now
+                .checked_elapsed_since(last)
+                .unwrap_or(TimeSpan::ZERO),
+        };
+        self.last_real = Some(self.last_real.map_or(real_now, |last| last.max(real_now)));
+        self.step(span)
+    }
+
+    /// Returns ticker that ticks with given frequency of this clock's time
+    /// while advanced by unscaled time spans.
+    ///
+    /// If scaled frequency can't be represented exactly,
+    /// it is approximated as described in [`Frequency::clamp_rate`].
+    /// Use [`ClockRate::try_ticker`] to get an error instead.
     pub fn ticker(&self, freq: Frequency) -> FrequencyTicker {
-        let gcd1 = gcd(self.nom, freq.period.get());
-        let nom = self.nom / gcd1;
-        let period = freq.period.get() / gcd1;
-
-        let gcd2 = gcd(freq.count, self.denom.get());
-        let count = freq.count / gcd2;
-        let denom = self.denom.get() / gcd2;
-
-        FrequencyTicker::new(
-            Frequency {
-                count: nom * count,
-                period: NonZeroU64::new(denom * period).unwrap(),
-            },
-            self.now,
-        )
+        FrequencyTicker::new(freq.clamp_rate(self.rate), self.now)
+    }
+
+    /// Returns ticker that ticks with given frequency of this clock's time
+    /// while advanced by unscaled time spans.
+    ///
+    /// Returns error if scaled frequency can't be represented exactly.
+    pub fn try_ticker(&self, freq: Frequency) -> Result<FrequencyTicker, FrequencyOutOfRange> {
+        let freq = self.scaled_frequency(freq).ok_or(FrequencyOutOfRange)?;
+        Ok(FrequencyTicker::new(freq, self.now))
+    }
+
+    /// Returns frequency scaled by clock rate.
+    /// Returns `None` if result doesn't fit into `Frequency`.
+    fn scaled_frequency(&self, freq: Frequency) -> Option<Frequency> {
+        // Reduce everything before multiplying so result is irreducible.
+        let rate = self.rate.reduced();
+        let nom = rate.nom();
+        let denom = rate.denom().get();
+
+        let gcd1 = gcd(nom, freq.period_nanos().get());
+        let nom = nom / gcd1;
+        let period = freq.period_nanos().get() / gcd1;
+
+        let gcd2 = gcd(freq.count(), denom);
+        let count = freq.count() / gcd2;
+        let denom = denom / gcd2;
+
+        let count = u64::try_from(nom as u128 * count as u128).ok()?;
+        let period = u64::try_from(denom as u128 * period as u128).ok()?;
+
+        Some(Frequency::from_raw(count, NonZeroU64::new(period)?))
     }
 }
 
-fn rate2ratio(rate: f32) -> (u64, NonZeroU64) {
-    let (n, d) = ftor(rate);
-    (n, NonZeroU64::new(d).unwrap())
+/// Error returned when frequency scaled by clock rate
+/// is outside of the range representable by [`Frequency`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrequencyOutOfRange;
+
+impl fmt::Display for FrequencyOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Frequency scaled by clock rate is not representable")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrequencyOutOfRange {}
+
+/// Wrapper over [`ClockRate`] that changes rate smoothly.
+///
+/// After [`RampingRate::ramp_to`] rate is interpolated linearly
+/// from the current rate towards the target rate over given span of real time,
+/// e.g. for cinematic slow-downs and smooth slow-to-stop.
+#[derive(Clone)]
+pub struct RampingRate {
+    clock: ClockRate,
+    from: Rate,
+    target: Rate,
+    duration: TimeSpan,
+    elapsed: TimeSpan,
 }
 
-fn ftor(value: f32) -> (u64, u64) {
-    const EPSILON: f32 = 1e-6;
-    const MAX_ITER: usize = 50;
+impl RampingRate {
+    /// Wraps clock. Clock's current rate is kept until ramp is started.
+    pub fn new(clock: ClockRate) -> Self {
+        let rate = clock.rate_exact();
+        RampingRate {
+            clock,
+            from: rate,
+            target: rate,
+            duration: TimeSpan::ZERO,
+            elapsed: TimeSpan::ZERO,
+        }
+    }
+
+    /// Starts changing rate from the current one to `target`
+    /// over `span` of real time.
+    ///
+    /// Zero `span` sets target rate immediately.
+    pub fn ramp_to(&mut self, target: Rate, span: TimeSpan) {
+        self.from = self.clock.rate_exact();
+        self.target = target;
+        self.duration = span;
+        self.elapsed = TimeSpan::ZERO;
+
+        if span == TimeSpan::ZERO {
+            self.clock.set_rate_exact(target);
+        }
+    }
+
+    /// Returns `true` if rate is still changing.
+    pub fn is_ramping(&self) -> bool {
+        self.elapsed < self.duration
+    }
 
-    let v = value.max(0.0);
+    /// Returns target rate of the ramp.
+    pub fn target(&self) -> Rate {
+        self.target
+    }
 
-    let mut d = 1;
-    let mut n = v;
+    /// Returns wrapped clock.
+    pub fn clock(&self) -> &ClockRate {
+        &self.clock
+    }
 
-    for _ in 0..MAX_ITER {
-        let f = n.fract();
-        if f < EPSILON {
-            break;
+    /// Returns wrapped clock.
+    ///
+    /// Setting rate directly doesn't stop the ramp.
+    pub fn clock_mut(&mut self) -> &mut ClockRate {
+        &mut self.clock
+    }
+
+    /// Returns wrapped clock.
+    pub fn into_inner(self) -> ClockRate {
+        self.clock
+    }
+
+    /// Advances the clock by given real time span and returns `ClockStep` result.
+    ///
+    /// Part of the span that falls into the ramp is scaled by the rate
+    /// interpolated at its midpoint. Rest of the span is scaled by the target rate.
+    pub fn step(&mut self, span: TimeSpan) -> ClockStep {
+        if !self.is_ramping() {
+            return self.clock.step(span);
         }
 
-        if d > u32::MAX as u64 {
-            break;
+        let ramp = span.min(self.duration - self.elapsed);
+        let mid = (self.elapsed + ramp / 2).as_secs_f64() / self.duration.as_secs_f64();
+        let from = self.from.as_f64();
+        let rate = from + (self.target.as_f64() - from) * mid;
+
+        self.clock.set_rate_exact(Rate::from_f64(rate));
+        let first = self.clock.step(ramp);
+        self.elapsed += ramp;
+
+        if self.is_ramping() {
+            return first;
         }
 
-        d = (d as f32 / f).ceil() as u64;
-        n = v * d as f32;
+        self.clock.set_rate_exact(self.target);
+        let rest = self.clock.step(span - ramp);
+
+        ClockStep {
+            now: rest.now,
+            step: first.step + rest.step,
+        }
     }
+}
 
-    let z = n.trunc() as u64;
+/// Interpolation of the rate from [`RateKeyframe`] to the next one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RateInterp {
+    /// Rate is held until the next keyframe.
+    Step,
 
-    let g = gcd(z, d);
-    return (z / g, d / g);
+    /// Rate changes linearly towards the rate of the next keyframe.
+    /// Behaves as [`RateInterp::Step`] for the last keyframe.
+    Linear,
 }
 
+/// Keyframe of the [`RateEnvelope`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateKeyframe {
+    /// Real time since start of the envelope when keyframe is reached.
+    pub at: TimeSpan,
+
+    /// Clock rate at the keyframe.
+    pub rate: f32,
+
+    /// Interpolation of the rate until the next keyframe.
+    pub interp: RateInterp,
+}
+
+/// Scripted rate changes of [`ClockRate`] over real time,
+/// e.g. for cutscenes that slow the time down and ramp it back.
+///
+/// Real time is counted from the envelope creation or [`RateEnvelope::clear`].
+/// Until the first keyframe clock's own rate is used,
+/// after the last keyframe its rate is held.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct RateEnvelope {
+    keyframes: Vec<RateKeyframe>,
+    elapsed: TimeSpan,
+}
+
+#[cfg(feature = "alloc")]
+impl Default for RateEnvelope {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl RateEnvelope {
+    /// Returns new envelope without keyframes.
+    pub fn new() -> Self {
+        RateEnvelope {
+            keyframes: Vec::new(),
+            elapsed: TimeSpan::ZERO,
+        }
+    }
+
+    /// Adds keyframe keeping keyframes sorted by time.
+    /// Keyframe at the same time as existing one replaces it.
+    pub fn add_keyframe(&mut self, keyframe: RateKeyframe) {
+        match self.keyframes.binary_search_by_key(&keyframe.at, |k| k.at) {
+            Ok(index) => self.keyframes[index] = keyframe,
+            Err(index) => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    /// Removes all keyframes and restarts real time of the envelope.
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+        self.elapsed = TimeSpan::ZERO;
+    }
+
+    /// Returns keyframes in order of their time.
+    pub fn keyframes(&self) -> &[RateKeyframe] {
+        &self.keyframes
+    }
+
+    /// Returns real time elapsed since start of the envelope.
+    pub fn elapsed(&self) -> TimeSpan {
+        self.elapsed
+    }
+
+    /// Returns keyframe that starts current segment.
+    /// Returns `None` before the first keyframe.
+    pub fn current_keyframe(&self) -> Option<&RateKeyframe> {
+        let index = self.keyframes.partition_point(|k| k.at <= self.elapsed);
+        index.checked_sub(1).map(|index| &self.keyframes[index])
+    }
+
+    /// Returns keyframe that ends current segment.
+    /// Returns `None` after the last keyframe.
+    pub fn next_keyframe(&self) -> Option<&RateKeyframe> {
+        let index = self.keyframes.partition_point(|k| k.at <= self.elapsed);
+        self.keyframes.get(index)
+    }
+
+    /// Returns rate of the envelope at given real time.
+    /// Returns `None` before the first keyframe.
+    pub fn rate_at(&self, at: TimeSpan) -> Option<f64> {
+        let index = self.keyframes.partition_point(|k| k.at <= at);
+        let current = self.keyframes[..index].last()?;
+
+        match (current.interp, self.keyframes.get(index)) {
+            (RateInterp::Linear, Some(next)) => {
+                let t = (at - current.at).as_secs_f64() / (next.at - current.at).as_secs_f64();
+                let from = current.rate as f64;
+                Some(from + (next.rate as f64 - from) * t)
+            }
+            _ => Some(current.rate as f64),
+        }
+    }
+
+    /// Advances the clock by given real time span and returns `ClockStep` result.
+    ///
+    /// Span is split at keyframes, so each part is scaled by the rate
+    /// of its own segment. Keyframe rates are converted with [`Rate::from_f32`].
+    ///
+    /// Within linear segment game time is the exact integral of the rate
+    /// since the segment start, rounded down to nanoseconds,
+    /// so it doesn't depend on how real time is split into steps.
+    /// Sub-nanosecond progress of the clock is reset in linear segments.
+    /// If exact integral doesn't fit into 128-bit arithmetic,
+    /// the part is scaled by the rate at its midpoint instead.
+    pub fn apply(&mut self, clock: &mut ClockRate, real_step: TimeSpan) -> ClockStep {
+        let mut remaining = real_step;
+        let mut step = TimeSpan::ZERO;
+
+        loop {
+            let span = match self.next_keyframe() {
+                None => remaining,
+                Some(next) => remaining.min(next.at - self.elapsed),
+            };
+
+            if let Some(rate) = self.rate_at(self.elapsed + span / 2) {
+                clock.set_rate_exact(Rate::from_f64(rate));
+            }
+
+            match self.linear_step(span) {
+                Some(game) => {
+                    clock.reset_phase();
+                    clock.set_now(clock.now() + game);
+                    step += game;
+                }
+                None => step += clock.step(span).step,
+            }
+
+            self.elapsed += span;
+            remaining -= span;
+
+            if remaining == TimeSpan::ZERO {
+                break;
+            }
+        }
+
+        ClockStep {
+            now: clock.now(),
+            step,
+        }
+    }
+
+    /// Returns game time for the next `span` of real time within linear segment.
+    /// Returns `None` outside of linear segment or if it can't be computed exactly.
+    fn linear_step(&self, span: TimeSpan) -> Option<TimeSpan> {
+        let index = self.keyframes.partition_point(|k| k.at <= self.elapsed);
+        let current = self.keyframes[..index].last()?;
+        let next = self.keyframes.get(index)?;
+        if current.interp != RateInterp::Linear {
+            return None;
+        }
+
+        let from = Rate::from_f32(current.rate);
+        let to = Rate::from_f32(next.rate);
+        let duration = (next.at - current.at).as_nanos();
+        let start = (self.elapsed - current.at).as_nanos();
+
+        let before = linear_integral(from, to, duration, start)?;
+        let after = linear_integral(from, to, duration, start + span.as_nanos())?;
+        Some(TimeSpan::new((after - before).try_into().ok()?))
+    }
+}
+
+/// Returns integral of the rate changing linearly from `from` to `to`
+/// over `duration` nanoseconds, taken over first `t` nanoseconds and rounded down.
+///
+/// Returns `None` if intermediate products don't fit.
+#[cfg(feature = "alloc")]
+fn linear_integral(from: Rate, to: Rate, duration: u64, t: u64) -> Option<i128> {
+    // With `from = a / b` and `to = c / d` integral is
+    // `(2 * duration * a * d * t + (c * b - a * d) * t^2) / (2 * b * d * duration)`.
+    let (a, b) = (from.nom() as i128, from.denom().get() as i128);
+    let (c, d) = (to.nom() as i128, to.denom().get() as i128);
+    let (duration, t) = (duration as i128, t as i128);
+
+    let ad = a.checked_mul(d)?;
+    let slope = c.checked_mul(b)?.checked_sub(ad)?;
+    let nom = (2 * duration)
+        .checked_mul(ad)?
+        .checked_mul(t)?
+        .checked_add(slope.checked_mul(t.checked_mul(t)?)?)?;
+    let denom = (2 * b).checked_mul(d)?.checked_mul(duration)?;
+
+    // Rate is never negative, so is the integral.
+    Some(nom / denom)
+}
 
 #[test]
 fn test_large() {
     fn check_ftor(v: f32) {
-        let (n, d) = ftor(v);
-        let e = (v - (n as f32 / d as f32)).abs();
+        let rate = Rate::from_f32(v);
+        let e = (v - (rate.nom() as f32 / rate.denom().get() as f32)).abs();
         assert!(e < 1e-6);
     }
     check_ftor(1.0);
@@ -199,3 +874,505 @@ fn test_large() {
     check_ftor(1.001);
     check_ftor(1234.1234);
 }
+
+#[test]
+fn test_rate_from_f32() {
+    let rate = |nom, denom| Rate::new(nom, NonZeroU64::new(denom).unwrap());
+
+    // Values `ClockRate::set_rate` has always produced.
+    assert_eq!(Rate::from_f32(0.5), rate(1, 2));
+    assert_eq!(Rate::from_f32(0.2), rate(1, 5));
+    assert_eq!(Rate::from_f32(1.0 / 3.0), rate(1, 3));
+    assert_eq!(Rate::from_f32(2.5), rate(5, 2));
+    assert_eq!(Rate::from_f32(-1.0), Rate::ZERO);
+    assert_eq!(Rate::from_f32(f32::NAN), Rate::ZERO);
+    assert_eq!(Rate::from_f32(f32::INFINITY), rate(u64::MAX, 1));
+    assert_eq!(ClockRate::new().with_rate(0.2).rate_exact(), rate(1, 5));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_rate_from_f32_matches_f32_path(value in 0.0f32..100_000.0) {
+        // Conversion as it was done before `Rate` existed.
+        fn ftor(value: f32) -> (u64, u64) {
+            let v = value.max(0.0);
+            let mut d = 1;
+            let mut n = v;
+            for _ in 0..50 {
+                let f = n.fract();
+                if f < 1e-6 || d > u32::MAX as u64 {
+                    break;
+                }
+                d = (d as f32 / f).ceil() as u64;
+                n = v * d as f32;
+            }
+            let z = n.trunc() as u64;
+            let g = gcd(z, d);
+            (z / g, d / g)
+        }
+
+        let rate = Rate::from_f32(value);
+        proptest::prop_assert_eq!((rate.nom(), rate.denom().get()), ftor(value));
+    }
+}
+
+#[test]
+fn test_rate_third_no_drift() {
+    let mut clock = ClockRate::new().with_rate_ratio(1, NonZeroU64::new(3).unwrap());
+
+    let mut total = 0;
+    let mut game = 0;
+    for i in 0..1_000_000u64 {
+        let nanos = 16_666_666 + i % 7 * 1_013 + i % 3;
+        total += nanos;
+        game += clock.step(TimeSpan::new(nanos)).step.as_nanos();
+    }
+
+    assert_eq!(game, total / 3);
+    assert_eq!(clock.now().nanos_since_start(), total / 3);
+}
+
+#[test]
+fn test_rate_exact_step_boundary() {
+    let mut clock = ClockRate::new().with_rate_ratio(1, NonZeroU64::new(3).unwrap());
+
+    assert_eq!(clock.step(TimeSpan::new(5)).step, TimeSpan::new(1));
+    assert_eq!(clock.step(TimeSpan::new(1)).step, TimeSpan::new(1));
+    assert_eq!(clock.step(TimeSpan::new(2)).step, TimeSpan::ZERO);
+    assert_eq!(clock.step(TimeSpan::new(1)).step, TimeSpan::new(1));
+}
+
+#[test]
+fn test_rate_change_keeps_phase() {
+    let mut clock = ClockRate::new().with_rate_ratio(1, NonZeroU64::new(4).unwrap());
+
+    // Half way to the next clock nanosecond.
+    assert_eq!(clock.step(TimeSpan::new(2)).step, TimeSpan::ZERO);
+
+    // Still half way after switching to 1/2 rate.
+    clock.set_rate_ratio(1, NonZeroU64::new(2).unwrap());
+    assert_eq!(clock.step(TimeSpan::new(1)).step, TimeSpan::new(1));
+}
+
+#[test]
+fn test_rate_reset_phase() {
+    let rate = Rate::new(2, NonZeroU64::new(3).unwrap());
+    let mut clock = ClockRate::new().with_rate_exact(rate);
+
+    // Two thirds of the way to the next clock nanosecond.
+    assert_eq!(clock.step(TimeSpan::new(4)).step, TimeSpan::new(2));
+    clock.reset_phase();
+
+    let mut fresh = ClockRate::new().with_rate_exact(rate).with_now(clock.now());
+    for nanos in [1, 1, 2, 5, 1000, 1] {
+        let span = TimeSpan::new(nanos);
+        assert_eq!(clock.step(span), fresh.step(span));
+    }
+
+    // Zero and tiny steps at high rate keep remainder bounded.
+    let mut clock = ClockRate::new().with_rate_ratio(u64::MAX, NonZeroU64::MAX);
+    for _ in 0..1000 {
+        clock.step(TimeSpan::ZERO);
+        clock.step(TimeSpan::NANOSECOND);
+    }
+    assert_eq!(clock.now(), TimeStamp::start() + TimeSpan::new(1000));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_rate_prop_no_drift(
+        nom in 0u64..1000,
+        denom in 1u64..1000,
+        steps in proptest::collection::vec(0u64..1_000_000_000, 0..200),
+    ) {
+        let mut clock = ClockRate::new().with_rate_ratio(nom, NonZeroU64::new(denom).unwrap());
+
+        let mut game = 0u128;
+        for &nanos in &steps {
+            let step = clock.step(TimeSpan::new(nanos));
+            game += step.step.as_nanos() as u128;
+            proptest::prop_assert_eq!(step.now, clock.now());
+        }
+
+        let total: u128 = steps.iter().map(|&nanos| nanos as u128).sum();
+        proptest::prop_assert_eq!(game, total * nom as u128 / denom as u128);
+    }
+
+    #[test]
+    fn test_rate_prop_rate_changes(
+        segments in proptest::collection::vec(
+            (0u64..100, 1u64..100, proptest::collection::vec(0u64..1_000_000_000, 0..50)),
+            1..4,
+        ),
+    ) {
+        let mut clock = ClockRate::new();
+
+        // Exact scaled time as a fraction over product of all denominators.
+        let common: u128 = segments.iter().map(|&(_, denom, _)| denom as u128).product();
+        let mut exact = 0u128;
+        let mut game = 0u128;
+
+        for (nom, denom, steps) in &segments {
+            clock.set_rate_ratio(*nom, NonZeroU64::new(*denom).unwrap());
+            for &nanos in steps {
+                game += clock.step(TimeSpan::new(nanos)).step.as_nanos() as u128;
+                exact += nanos as u128 * *nom as u128 * (common / *denom as u128);
+            }
+        }
+
+        // Each rate change may round down less than a nanosecond of progress.
+        let exact = exact / common;
+        let changes = segments.len() as u128;
+        proptest::prop_assert!(game <= exact);
+        proptest::prop_assert!(exact - game <= changes);
+    }
+}
+
+#[test]
+fn test_rate_ticker_extreme() {
+    let clock = ClockRate::new().with_rate(1000.0);
+    let mut ticker = clock.ticker(Frequency::from_ghz(1));
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND), 1_000_000_000_000);
+
+    let clock = ClockRate::new().with_rate_ratio(1, NonZeroU64::new(1_000_000).unwrap());
+    let mut ticker = clock.ticker(Frequency::from_hz(240));
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND), 0);
+    assert_eq!(ticker.tick_count(TimeSpan::SECOND * 999_999), 240);
+
+    // Multiplying without reducing the ratio first overflows.
+    let period = 9_300_000_000_000_000_001;
+    let freq = Frequency::new(
+        1,
+        crate::NonZeroTimeSpan::new(NonZeroU64::new(period).unwrap()),
+    );
+    let clock = ClockRate::new().with_rate_ratio(2000, NonZeroU64::new(2).unwrap());
+    let scaled = clock.scaled_frequency(freq).unwrap();
+    assert_eq!(
+        (scaled.count(), scaled.period_nanos().get()),
+        (1000, period)
+    );
+
+    let ticker = clock.ticker(freq);
+    assert_eq!(
+        ticker.next_tick(),
+        Some(TimeStamp::start() + TimeSpan::new(period.div_ceil(1000)))
+    );
+}
+
+#[test]
+fn test_rate_real_time_until() {
+    let start = TimeStamp::start();
+    let clock = ClockRate::new().with_rate(0.5);
+
+    let deadline = start + TimeSpan::SECOND;
+    assert_eq!(clock.real_time_until(deadline), Some(TimeSpan::SECOND * 2));
+    assert_eq!(clock.real_time_until(start), Some(TimeSpan::ZERO));
+
+    // Inverse is exact with sub-nanosecond progress.
+    let mut clock = ClockRate::new().with_rate_ratio(1, NonZeroU64::new(3).unwrap());
+    clock.step(TimeSpan::new(5));
+    for nanos in [1, 2, 3, 100, 1_000_000_007] {
+        let deadline = clock.now() + TimeSpan::new(nanos);
+        let real = clock.real_time_until(deadline).unwrap();
+
+        let mut before = clock.clone();
+        before.step(real - TimeSpan::NANOSECOND);
+        assert!(before.now() < deadline);
+
+        let mut after = clock.clone();
+        after.step(real);
+        assert_eq!(after.now(), deadline);
+    }
+
+    assert_eq!(
+        ClockRate::new()
+            .with_rate_ratio(1, NonZeroU64::new(u64::MAX).unwrap())
+            .real_time_until(TimeStamp::start() + TimeSpan::SECOND),
+        None
+    );
+
+    clock.pause();
+    assert_eq!(clock.real_time_until(clock.now() + TimeSpan::SECOND), None);
+    assert_eq!(clock.real_time_until(clock.now()), Some(TimeSpan::ZERO));
+}
+
+#[test]
+fn test_rate_try_ticker() {
+    // Ticks over a second computed independently.
+    fn expected(rate: Rate, freq: Frequency, span: TimeSpan) -> u128 {
+        span.as_nanos() as u128 * rate.nom() as u128 * freq.count() as u128
+            / (rate.denom().get() as u128 * freq.period_nanos().get() as u128)
+    }
+
+    for (rate, freq) in [
+        (Rate::from_f64(1000.0), Frequency::from_ghz(1)),
+        (
+            Rate::new(1, NonZeroU64::new(1_000_000).unwrap()),
+            Frequency::from_hz(240),
+        ),
+        (
+            Rate::new(3, NonZeroU64::new(7).unwrap()),
+            Frequency::new(
+                60000,
+                crate::NonZeroTimeSpan::new(NonZeroU64::new(1_001_000_000_000).unwrap()),
+            ),
+        ),
+    ] {
+        let clock = ClockRate::new().with_rate_exact(rate);
+        let mut ticker = clock.try_ticker(freq).unwrap();
+        for span in [TimeSpan::SECOND, TimeSpan::SECOND * 999_999] {
+            ticker.tick_count(span);
+            let elapsed = ticker.snapshot().now().elapsed_since_start();
+            assert_eq!(ticker.tick_index() as u128, expected(rate, freq, elapsed));
+        }
+    }
+
+    let clock = ClockRate::new().with_rate_ratio(1_000_003, NonZeroU64::new(1_000_000).unwrap());
+    let freq = Frequency::new(
+        1,
+        crate::NonZeroTimeSpan::new(NonZeroU64::new(100_000_000_000_000).unwrap()),
+    );
+    assert_eq!(clock.try_ticker(freq).err(), Some(FrequencyOutOfRange));
+}
+
+#[test]
+fn test_rate_ticker_unrepresentable() {
+    let clock = ClockRate::new().with_rate_ratio(1_000_003, NonZeroU64::new(1_000_000).unwrap());
+    let freq = Frequency::new(
+        1,
+        crate::NonZeroTimeSpan::new(NonZeroU64::new(100_000_000_000_000).unwrap()),
+    );
+    let mut ticker = clock.ticker(freq);
+    assert_eq!(ticker.tick_count(TimeSpan::new(99_999_800_000_000)), 1);
+
+    // Too fast to represent at all is clamped to the fastest frequency.
+    let clock = ClockRate::new().with_rate_ratio(u64::MAX, NonZeroU64::MIN);
+    let mut ticker = clock.ticker(Frequency::from_ghz(1));
+    assert_eq!(ticker.tick_count(TimeSpan::NANOSECOND), u64::MAX);
+}
+
+#[test]
+fn test_rate_value() {
+    let third = Rate::new(1, NonZeroU64::new(3).unwrap());
+    let two_sixths = Rate::new(2, NonZeroU64::new(6).unwrap());
+
+    assert_eq!(third, two_sixths);
+    assert!(third < Rate::ONE);
+    assert!(Rate::ZERO < third);
+    assert_eq!(two_sixths.reduced().nom(), 1);
+    assert_eq!(third.to_string(), "1/3");
+    assert_eq!(
+        Rate::from_f64(0.25),
+        Rate::new(1, NonZeroU64::new(4).unwrap())
+    );
+    assert_eq!(Rate::from_f32(2.0), Rate::new(2, NonZeroU64::MIN));
+    assert_eq!(Rate::from_f64(-1.0), Rate::ZERO);
+    assert_eq!(Rate::from_f64(f64::NAN), Rate::ZERO);
+    assert_eq!(Rate::from_f64(f64::INFINITY).nom(), u64::MAX);
+    assert!((Rate::from_f64(1.0 / 3.0).as_f64() - 1.0 / 3.0).abs() < 1e-6);
+
+    assert_eq!(third * Rate::new(3, NonZeroU64::MIN), Rate::ONE);
+    assert_eq!(third * Rate::ONE, third);
+    assert_eq!(third * Rate::ZERO, Rate::ZERO);
+    assert_eq!(third.inverse(), Some(Rate::new(3, NonZeroU64::MIN)));
+    assert_eq!(Rate::ZERO.inverse(), None);
+    assert_eq!(
+        Rate::new(u64::MAX, NonZeroU64::MIN).checked_mul(Rate::new(2, NonZeroU64::MIN)),
+        None
+    );
+
+    for value in [0.5, 1.0, 1.001, 3.75, 1234.1234, 1.0 / 7.0] {
+        let rate = Rate::from_f64(value);
+        assert_eq!(rate * rate.inverse().unwrap(), Rate::ONE);
+    }
+}
+
+#[test]
+fn test_rate_exact_clock() {
+    let mut clock = ClockRate::new().with_rate_exact(Rate::new(1, NonZeroU64::new(3).unwrap()));
+    assert_eq!(
+        clock.rate_exact(),
+        Rate::new(1, NonZeroU64::new(3).unwrap())
+    );
+    assert_eq!(clock.step(TimeSpan::new(7)).step, TimeSpan::new(2));
+
+    clock.set_rate(0.5);
+    assert_eq!(
+        clock.rate_exact(),
+        Rate::new(1, NonZeroU64::new(2).unwrap())
+    );
+    assert_eq!(clock.step(TimeSpan::new(4)).step, TimeSpan::new(2));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_rate_serde() {
+    let third = Rate::new(1, NonZeroU64::new(3).unwrap());
+    assert_eq!(serde_json::to_string(&third).unwrap(), "\"1/3\"");
+    assert_eq!(serde_json::from_str::<Rate>("\"1/3\"").unwrap(), third);
+    assert_eq!(
+        serde_json::from_str::<Rate>("\"2\"").unwrap(),
+        Rate::new(2, NonZeroU64::MIN)
+    );
+    assert!(serde_json::from_str::<Rate>("\"1/0\"").is_err());
+}
+
+#[test]
+fn test_rate_step_to() {
+    let rate = Rate::new(2, NonZeroU64::new(3).unwrap());
+    let mut absolute = ClockRate::new().with_rate_exact(rate);
+    let mut delta = ClockRate::new().with_rate_exact(rate);
+
+    let base = TimeStamp::start() + TimeSpan::SECOND;
+    let first = absolute.step_to(base);
+    assert_eq!(first.step, TimeSpan::ZERO);
+    assert_eq!(first.now, TimeStamp::start());
+
+    let mut prev = base;
+    for nanos in [5, 7, 1, 0, 16_666_667, 3] {
+        let real = prev + TimeSpan::new(nanos);
+        assert_eq!(absolute.step_to(real), delta.step(real - prev));
+        prev = real;
+    }
+
+    // Backward real time stamps do not move the clock.
+    let step = absolute.step_to(base);
+    assert_eq!(step.step, TimeSpan::ZERO);
+    assert_eq!(
+        absolute.step_to(prev + TimeSpan::new(3)),
+        delta.step(TimeSpan::new(3))
+    );
+
+    // Reset forgets the baseline, earlier real time is not treated as backward.
+    absolute.reset();
+    let first = absolute.step_to(base);
+    assert_eq!(first.step, TimeSpan::ZERO);
+    assert_eq!(first.now, TimeStamp::start());
+    let step = absolute.step_to(base + TimeSpan::new(3));
+    assert_eq!(step.step, TimeSpan::new(2));
+    assert_eq!(step.now, TimeStamp::start() + TimeSpan::new(2));
+}
+
+#[test]
+fn test_rate_is_paused() {
+    let mut clock = ClockRate::new();
+    assert!(!clock.is_paused());
+
+    clock.pause();
+    assert!(clock.is_paused());
+    assert_eq!(clock.step(TimeSpan::SECOND).step, TimeSpan::ZERO);
+
+    clock.set_rate(0.5);
+    assert!(!clock.is_paused());
+
+    clock.set_rate(0.0);
+    assert!(clock.is_paused());
+}
+
+#[test]
+fn test_ramping_rate() {
+    let mut ramp = RampingRate::new(ClockRate::new());
+    assert!(!ramp.is_ramping());
+    assert_eq!(ramp.step(TimeSpan::SECOND).step, TimeSpan::SECOND);
+
+    ramp.ramp_to(Rate::ZERO, TimeSpan::SECOND);
+    assert!(ramp.is_ramping());
+
+    let mut total = TimeSpan::ZERO;
+    for _ in 0..10 {
+        assert!(!ramp.clock().is_paused());
+        total += ramp.step(TimeSpan::MILLISECOND * 100).step;
+    }
+    assert!(!ramp.is_ramping());
+    assert!(ramp.clock().is_paused());
+    assert_eq!(ramp.clock().rate_exact(), Rate::ZERO);
+
+    // Linear ramp from 1 to 0 covers half of the real time.
+    let error = total.as_secs_f64() - 0.5;
+    assert!(error.abs() < 1e-6, "{}", total);
+    assert_eq!(ramp.step(TimeSpan::SECOND).step, TimeSpan::ZERO);
+
+    // Step crossing the end of the ramp continues at target rate.
+    ramp.ramp_to(Rate::new(2, NonZeroU64::MIN), TimeSpan::SECOND);
+    let step = ramp.step(TimeSpan::SECOND * 2);
+    assert!(!ramp.is_ramping());
+    assert_eq!(ramp.clock().rate_exact(), Rate::new(2, NonZeroU64::MIN));
+    let error = step.step.as_secs_f64() - 3.0;
+    assert!(error.abs() < 1e-6, "{}", step.step);
+
+    ramp.ramp_to(Rate::ONE, TimeSpan::ZERO);
+    assert!(!ramp.is_ramping());
+    assert_eq!(ramp.step(TimeSpan::SECOND).step, TimeSpan::SECOND);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_rate_envelope() {
+    let keyframe = |secs: u64, rate: f32, interp: RateInterp| RateKeyframe {
+        at: TimeSpan::SECOND * secs,
+        rate,
+        interp,
+    };
+
+    // Normal speed until 2s, slow motion for 3s, ramp back over 1s.
+    let mut envelope = RateEnvelope::new();
+    envelope.add_keyframe(keyframe(6, 1.0, RateInterp::Step));
+    envelope.add_keyframe(keyframe(2, 0.25, RateInterp::Step));
+    envelope.add_keyframe(keyframe(0, 1.0, RateInterp::Step));
+    envelope.add_keyframe(keyframe(5, 0.25, RateInterp::Linear));
+    assert_eq!(envelope.keyframes().len(), 4);
+    assert_eq!(
+        envelope.current_keyframe(),
+        Some(&keyframe(0, 1.0, RateInterp::Step))
+    );
+    assert_eq!(
+        envelope.next_keyframe(),
+        Some(&keyframe(2, 0.25, RateInterp::Step))
+    );
+    assert_eq!(envelope.rate_at(TimeSpan::MILLISECOND * 5500), Some(0.625));
+
+    // Game time at given real time in nanoseconds.
+    // Ramp from 1/4 to 1 over `D` nanoseconds gives `(2 * D * t + 3 * t^2) / (8 * D)`.
+    let analytic = |t: u64| {
+        const D: u128 = 1_000_000_000;
+        let second = TimeSpan::SECOND.as_nanos();
+        let slow = t.clamp(2 * second, 5 * second) - 2 * second;
+        let ramp = (t.clamp(5 * second, 6 * second) - 5 * second) as u128;
+        let after = t.max(6 * second) - 6 * second;
+        t.min(2 * second) + slow / 4 + ((2 * D * ramp + 3 * ramp * ramp) / (8 * D)) as u64 + after
+    };
+
+    let mut clock = ClockRate::new();
+    let mut real = TimeSpan::ZERO;
+    let mut game = TimeSpan::ZERO;
+    for i in 0..500u64 {
+        let step = TimeSpan::new(7_000_000 + i % 5 * 3_333_333 + i % 3 * 1_001);
+        let result = envelope.apply(&mut clock, step);
+        assert_eq!(result.now, clock.now());
+
+        real += step;
+        game += result.step;
+        assert_eq!(game.as_nanos(), analytic(real.as_nanos()), "at {}", real);
+    }
+
+    assert!(real > TimeSpan::SECOND * 6);
+    assert_eq!(envelope.elapsed(), real);
+    assert_eq!(
+        envelope.current_keyframe(),
+        Some(&keyframe(6, 1.0, RateInterp::Step))
+    );
+    assert_eq!(envelope.next_keyframe(), None);
+    assert_eq!(clock.rate_exact(), Rate::ONE);
+
+    // Without keyframes clock's own rate is used.
+    envelope.clear();
+    assert_eq!(envelope.current_keyframe(), None);
+    clock.set_rate_exact(Rate::new(1, NonZeroU64::new(2).unwrap()));
+    assert_eq!(
+        envelope.apply(&mut clock, TimeSpan::SECOND).step,
+        TimeSpan::MILLISECOND * 500
+    );
+    assert_eq!(envelope.elapsed(), TimeSpan::SECOND);
+}
@@ -0,0 +1,266 @@
+//! Contains `CallbackScheduler` type that delivers payloads at given time stamps.
+
+use alloc::collections::{BTreeSet, BinaryHeap};
+use core::cmp::{Ordering, Reverse};
+
+use crate::{span::TimeSpan, stamp::TimeStamp};
+
+/// Handle to the repeating entry of [`CallbackScheduler`].
+/// Used to cancel it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RepeatHandle(u64);
+
+struct Entry<T> {
+    at: TimeStamp,
+
+    /// Order of scheduling, breaks ties between equal time stamps.
+    seq: u64,
+
+    repeat: Option<(TimeSpan, u64)>,
+    payload: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+/// Schedules payloads to be delivered at given time stamps,
+/// once or repeatedly.
+///
+/// Due payloads are delivered by [`CallbackScheduler::advance`]
+/// in order of their time stamps.
+/// Payloads with equal time stamps are delivered in order they were scheduled.
+pub struct CallbackScheduler<T> {
+    now: TimeStamp,
+
+    /// `BinaryHeap` comes from `alloc`, so it serves `no_std` builds as well.
+    heap: BinaryHeap<Reverse<Entry<T>>>,
+    next_seq: u64,
+    next_repeat: u64,
+
+    /// Repeating entries that are not cancelled.
+    repeats: BTreeSet<u64>,
+}
+
+impl<T> CallbackScheduler<T> {
+    /// Returns new empty scheduler with current time stamp `now`.
+    pub fn new(now: TimeStamp) -> Self {
+        CallbackScheduler {
+            now,
+            heap: BinaryHeap::new(),
+            next_seq: 0,
+            next_repeat: 0,
+            repeats: BTreeSet::new(),
+        }
+    }
+
+    /// Returns time stamp scheduler is advanced to.
+    pub fn now(&self) -> TimeStamp {
+        self.now
+    }
+
+    /// Returns time stamp of the earliest scheduled payload.
+    ///
+    /// May return time stamp of cancelled repeating entry
+    /// that is discarded on the next advancement.
+    pub fn next_at(&self) -> Option<TimeStamp> {
+        self.heap.peek().map(|Reverse(entry)| entry.at)
+    }
+
+    /// Schedules `payload` to be delivered once at `at`.
+    ///
+    /// Payload scheduled at or before current time stamp
+    /// is delivered on the next advancement.
+    pub fn run_at(&mut self, at: TimeStamp, payload: T) {
+        self.push(at, None, payload);
+    }
+
+    /// Schedules `payload` to be delivered every `every` span,
+    /// starting one span after current time stamp.
+    ///
+    /// Deliveries do not drift, each deadline is computed from the previous deadline
+    /// rather than from time of delivery.
+    /// Repeating stops at the last deadline representable as `TimeStamp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    pub fn run_every(&mut self, every: TimeSpan, payload: T) -> RepeatHandle {
+        assert_ne!(every, TimeSpan::ZERO, "repeat period must not be zero");
+
+        let id = self.next_repeat;
+        self.next_repeat += 1;
+
+        if let Some(at) = self.now.add_span(every) {
+            self.repeats.insert(id);
+            self.push(at, Some((every, id)), payload);
+        }
+        RepeatHandle(id)
+    }
+
+    /// Cancels repeating entry.
+    /// Returns `false` if it was already cancelled.
+    pub fn cancel(&mut self, handle: RepeatHandle) -> bool {
+        self.repeats.remove(&handle.0)
+    }
+
+    /// Advances scheduler to `now` and calls `f` for each payload due
+    /// with its scheduled time stamp.
+    ///
+    /// Repeating entry is delivered once for each deadline passed.
+    pub fn advance(&mut self, now: TimeStamp, mut f: impl FnMut(TimeStamp, &T)) {
+        self.now = self.now.max(now);
+
+        while let Some(Reverse(top)) = self.heap.peek() {
+            if top.at > now {
+                break;
+            }
+
+            let Reverse(entry) = self.heap.pop().unwrap();
+            match entry.repeat {
+                None => f(entry.at, &entry.payload),
+                Some((every, id)) => {
+                    if !self.repeats.contains(&id) {
+                        continue;
+                    }
+
+                    f(entry.at, &entry.payload);
+
+                    match entry.at.add_span(every) {
+                        Some(next) => self.push(next, entry.repeat, entry.payload),
+                        None => {
+                            self.repeats.remove(&id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, at: TimeStamp, repeat: Option<(TimeSpan, u64)>, payload: T) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        self.heap.push(Reverse(Entry {
+            at,
+            seq,
+            repeat,
+            payload,
+        }));
+    }
+}
+
+#[test]
+fn test_scheduler_order() {
+    let start = TimeStamp::start();
+    let mut scheduler = CallbackScheduler::new(start);
+
+    scheduler.run_at(start + TimeSpan::SECOND * 2, "c");
+    scheduler.run_at(start + TimeSpan::SECOND, "a");
+    scheduler.run_at(start + TimeSpan::SECOND * 2, "d");
+    scheduler.run_at(start + TimeSpan::SECOND, "b");
+    scheduler.run_at(start + TimeSpan::SECOND * 3, "e");
+
+    let mut delivered = Vec::new();
+    scheduler.advance(start + TimeSpan::SECOND * 2, |at, &payload| {
+        delivered.push((at, payload))
+    });
+    assert_eq!(
+        delivered,
+        [
+            (start + TimeSpan::SECOND, "a"),
+            (start + TimeSpan::SECOND, "b"),
+            (start + TimeSpan::SECOND * 2, "c"),
+            (start + TimeSpan::SECOND * 2, "d"),
+        ]
+    );
+
+    delivered.clear();
+    scheduler.advance(start + TimeSpan::SECOND * 2, |at, &payload| {
+        delivered.push((at, payload))
+    });
+    assert!(delivered.is_empty());
+
+    scheduler.advance(start + TimeSpan::SECOND * 10, |at, &payload| {
+        delivered.push((at, payload))
+    });
+    assert_eq!(delivered, [(start + TimeSpan::SECOND * 3, "e")]);
+}
+
+#[test]
+fn test_scheduler_repeat() {
+    let start = TimeStamp::start();
+    let mut scheduler = CallbackScheduler::new(start);
+
+    let every = TimeSpan::SECOND / 3;
+    let repeat = scheduler.run_every(every, 1);
+    let other = scheduler.run_every(TimeSpan::SECOND, 2);
+
+    // Advance with irregular steps, deadlines must not drift.
+    let mut delivered = Vec::new();
+    let mut now = start;
+    for i in 0..10_000u64 {
+        now += TimeSpan::MILLISECOND * (1 + i % 37);
+        scheduler.advance(now, |at, &payload| {
+            if payload == 1 {
+                delivered.push(at)
+            }
+        });
+    }
+
+    let expected = now.elapsed_since(start).as_nanos() / every.as_nanos();
+    assert_eq!(delivered.len() as u64, expected);
+    for (i, at) in delivered.iter().enumerate() {
+        assert_eq!(*at, start + every * (i as u64 + 1));
+    }
+
+    assert!(scheduler.cancel(repeat));
+    assert!(!scheduler.cancel(repeat));
+
+    let mut count = 0;
+    scheduler.advance(now + TimeSpan::SECOND * 10, |_, &payload| {
+        assert_eq!(payload, 2);
+        count += 1;
+    });
+    assert_eq!(count, 10);
+
+    assert!(scheduler.cancel(other));
+    scheduler.advance(now + TimeSpan::SECOND * 20, |_, _| panic!("cancelled"));
+}
+
+#[test]
+fn test_scheduler_repeat_overflow() {
+    let now = TimeStamp::never() - TimeSpan::SECOND;
+    let mut scheduler = CallbackScheduler::new(now);
+
+    // First deadline is not representable, nothing is scheduled.
+    let never = scheduler.run_every(TimeSpan::SECOND * 2, 1);
+    assert_eq!(scheduler.next_at(), None);
+    assert!(!scheduler.cancel(never));
+
+    // Repeating stops at the last representable deadline.
+    let last = scheduler.run_every(TimeSpan::MILLISECOND * 600, 2);
+    let mut delivered = Vec::new();
+    scheduler.advance(TimeStamp::never(), |at, &payload| {
+        delivered.push((at, payload))
+    });
+    assert_eq!(delivered, [(now + TimeSpan::MILLISECOND * 600, 2)]);
+    assert_eq!(scheduler.next_at(), None);
+    assert!(!scheduler.cancel(last));
+}
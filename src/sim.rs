@@ -0,0 +1,238 @@
+//! Contains `SimClock` type that combines real time clock,
+//! time scaling and fixed update ticker.
+
+use std::time::Instant;
+
+use crate::{
+    clock::Clock,
+    freq::{Frequency, FrequencyTicker, FrequencyTickerIter},
+    rate::{ClockRate, Rate},
+    stamp::TimeStamp,
+    step::ClockStep,
+};
+
+/// Result of [`SimClock::step`].
+pub struct SimStep {
+    /// Step of the real time clock.
+    pub real: ClockStep,
+
+    /// Step of the game clock, scaled by the rate.
+    pub game: ClockStep,
+
+    /// Fixed updates that happened during this step, in game time.
+    pub fixed: FrequencyTickerIter,
+
+    /// Progress towards the next fixed update in `[0, 1)`,
+    /// for interpolation between fixed updates.
+    pub alpha: f32,
+}
+
+/// Simulation clock.
+///
+/// Combines real time [`Clock`], [`ClockRate`] for time scaling
+/// and [`FrequencyTicker`] for fixed updates.
+/// Ticker is advanced by steps of the game clock,
+/// so fixed updates follow scaled time exactly.
+pub struct SimClock {
+    real: Clock,
+    game: ClockRate,
+    fixed: FrequencyTicker,
+
+    /// Rate to restore when resumed.
+    rate: Rate,
+
+    /// Whether game clock is paused.
+    /// Tracked separately, since zero rate doesn't mean pause.
+    paused: bool,
+}
+
+impl SimClock {
+    /// Returns new simulation clock with rate of one
+    /// and fixed updates with given frequency.
+    pub fn new(fixed: Frequency) -> Self {
+        let game = ClockRate::new();
+        SimClock {
+            real: Clock::new(),
+            fixed: FrequencyTicker::new(fixed, game.now()),
+            game,
+            rate: Rate::ONE,
+            paused: false,
+        }
+    }
+
+    /// Advances the clock and returns steps of real and game clocks
+    /// with fixed updates.
+    pub fn step(&mut self) -> SimStep {
+        self.step_at(Instant::now())
+    }
+
+    /// Advances the clock to given instant and returns steps of real and game clocks
+    /// with fixed updates.
+    ///
+    /// See [`Clock::step_at`].
+    pub fn step_at(&mut self, instant: Instant) -> SimStep {
+        let real = self.real.step_at(instant);
+        let game = self.game.step(real.step);
+        let (fixed, alpha) = self.fixed.ticks_with_alpha(game.step);
+
+        SimStep {
+            real,
+            game,
+            fixed,
+            alpha,
+        }
+    }
+
+    /// Sets rate of the game clock.
+    ///
+    /// If clock is paused, rate is applied when it is resumed.
+    pub fn set_rate(&mut self, rate: Rate) {
+        self.rate = rate;
+        if !self.paused {
+            self.game.set_rate_exact(rate);
+        }
+    }
+
+    /// Returns rate of the game clock, ignoring pause.
+    pub fn rate(&self) -> Rate {
+        self.rate
+    }
+
+    /// Pauses the game clock.
+    /// Real clock keeps running.
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.game.pause();
+    }
+
+    /// Resumes the game clock with the last set rate.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.game.set_rate_exact(self.rate);
+    }
+
+    /// Returns `true` if the game clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets frequency of fixed updates.
+    pub fn set_fixed_frequency(&mut self, freq: Frequency) {
+        self.fixed.set_frequency(freq);
+    }
+
+    /// Returns frequency of fixed updates.
+    pub fn fixed_frequency(&self) -> Frequency {
+        self.fixed.frequency()
+    }
+
+    /// Returns time stamp of the real clock as of the last step.
+    pub fn now_real(&self) -> TimeStamp {
+        self.real.now()
+    }
+
+    /// Returns time stamp of the game clock as of the last step.
+    pub fn now_game(&self) -> TimeStamp {
+        self.game.now()
+    }
+
+    /// Returns real time clock.
+    pub fn real_clock(&self) -> &Clock {
+        &self.real
+    }
+}
+
+#[test]
+fn test_sim_clock() {
+    use crate::span::TimeSpan;
+
+    let mut sim = SimClock::new(Frequency::from_hz(10));
+    let base = sim.real_clock().stamp_instant(TimeStamp::start());
+    let at = |ms: u64| base + std::time::Duration::from_millis(ms);
+
+    let step = sim.step_at(at(75));
+    assert_eq!(step.real.step, TimeSpan::MILLISECOND * 75);
+    assert_eq!(step.game.step, TimeSpan::MILLISECOND * 75);
+    assert_eq!(step.fixed.ticks(), 0);
+    assert_eq!(step.alpha, 0.75);
+
+    let step = sim.step_at(at(250));
+    let fixed: Vec<_> = step.fixed.map(|tick| tick.now).collect();
+    assert_eq!(
+        fixed,
+        [
+            TimeStamp::start() + TimeSpan::MILLISECOND * 100,
+            TimeStamp::start() + TimeSpan::MILLISECOND * 200,
+        ]
+    );
+    assert_eq!(step.alpha, 0.5);
+
+    // Half speed, fixed updates follow game time.
+    sim.set_rate(Rate::new(1, core::num::NonZeroU64::new(2).unwrap()));
+    let step = sim.step_at(at(350));
+    assert_eq!(step.real.step, TimeSpan::MILLISECOND * 100);
+    assert_eq!(step.game.step, TimeSpan::MILLISECOND * 50);
+    assert_eq!(step.fixed.ticks(), 1);
+    assert_eq!(
+        sim.now_game(),
+        TimeStamp::start() + TimeSpan::MILLISECOND * 300
+    );
+
+    // Paused game clock doesn't advance, rate is kept.
+    sim.pause();
+    let step = sim.step_at(at(1350));
+    assert_eq!(step.real.step, TimeSpan::SECOND);
+    assert_eq!(step.game.step, TimeSpan::ZERO);
+    assert_eq!(step.fixed.ticks(), 0);
+
+    sim.resume();
+    assert!(!sim.is_paused());
+    let step = sim.step_at(at(1550));
+    assert_eq!(step.game.step, TimeSpan::MILLISECOND * 100);
+    assert_eq!(step.fixed.ticks(), 1);
+
+    sim.set_fixed_frequency(Frequency::from_hz(100));
+    let step = sim.step_at(at(1570));
+    assert_eq!(step.fixed.ticks(), 1);
+    assert_eq!(
+        sim.now_real(),
+        TimeStamp::start() + TimeSpan::MILLISECOND * 1570
+    );
+    assert_eq!(
+        sim.now_game(),
+        TimeStamp::start() + TimeSpan::MILLISECOND * 410
+    );
+}
+
+#[test]
+fn test_sim_clock_zero_rate() {
+    use crate::span::TimeSpan;
+
+    let mut sim = SimClock::new(Frequency::from_hz(10));
+    let base = sim.real_clock().stamp_instant(TimeStamp::start());
+    let at = |ms: u64| base + std::time::Duration::from_millis(ms);
+
+    // Zero rate stops the game clock, but is not a pause.
+    sim.set_rate(Rate::ZERO);
+    assert!(!sim.is_paused());
+    let step = sim.step_at(at(100));
+    assert_eq!(step.game.step, TimeSpan::ZERO);
+
+    sim.set_rate(Rate::ONE);
+    assert_eq!(sim.rate(), Rate::ONE);
+    let step = sim.step_at(at(200));
+    assert_eq!(step.game.step, TimeSpan::MILLISECOND * 100);
+    assert_eq!(step.fixed.ticks(), 1);
+
+    // Rate set while paused is applied on resume.
+    sim.pause();
+    sim.set_rate(Rate::ZERO);
+    sim.set_rate(Rate::new(2, core::num::NonZeroU64::MIN));
+    assert!(sim.is_paused());
+    let step = sim.step_at(at(300));
+    assert_eq!(step.game.step, TimeSpan::ZERO);
+
+    sim.resume();
+    let step = sim.step_at(at(400));
+    assert_eq!(step.game.step, TimeSpan::MILLISECOND * 200);
+}
@@ -0,0 +1,271 @@
+//! Contains `SnapshotBuffer` type that interpolates between time stamped samples.
+
+use alloc::collections::VecDeque;
+
+use crate::{span::TimeSpan, stamp::TimeStamp};
+
+/// Result of [`SnapshotBuffer::sample`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sample<'a, T> {
+    /// Query is earlier than the earliest sample.
+    /// Contains the earliest sample.
+    Before(&'a T),
+
+    /// Query matches time stamp of the sample exactly.
+    Exact(&'a T),
+
+    /// Query is between two samples.
+    Between {
+        /// Sample before the query.
+        a: &'a T,
+
+        /// Sample after the query.
+        b: &'a T,
+
+        /// Blend factor in `(0, 1)` from `a` to `b`.
+        alpha: f32,
+    },
+
+    /// Query is later than the latest sample.
+    /// Contains the latest sample.
+    After(&'a T),
+}
+
+/// Buffer of time stamped samples for snapshot interpolation.
+///
+/// Samples are kept sorted by time stamps.
+/// Once capacity is reached, the earliest sample is evicted on push.
+#[derive(Clone, Debug)]
+pub struct SnapshotBuffer<T> {
+    samples: VecDeque<(TimeStamp, T)>,
+    capacity: usize,
+}
+
+impl<T> SnapshotBuffer<T> {
+    /// Creates new empty buffer that holds at most `capacity` samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "snapshot buffer capacity must not be zero");
+
+        SnapshotBuffer {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns maximum number of samples in the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns number of samples in the buffer.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if buffer has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns time stamp of the earliest sample.
+    pub fn earliest(&self) -> Option<TimeStamp> {
+        self.samples.front().map(|(at, _)| *at)
+    }
+
+    /// Returns time stamp of the latest sample.
+    pub fn latest(&self) -> Option<TimeStamp> {
+        self.samples.back().map(|(at, _)| *at)
+    }
+
+    /// Returns iterator over samples in order of their time stamps.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (TimeStamp, &T)> + ExactSizeIterator {
+        self.samples.iter().map(|(at, value)| (*at, value))
+    }
+
+    /// Adds sample at given time stamp.
+    ///
+    /// Out of order sample is inserted at its place.
+    /// Sample with the same time stamp as existing one replaces it.
+    /// When buffer is full the earliest sample is evicted,
+    /// which may be the new sample itself.
+    pub fn push(&mut self, at: TimeStamp, value: T) {
+        // Fast path for in-order samples.
+        let index = match self.samples.back() {
+            Some((last, _)) if *last < at => self.samples.len(),
+            _ => self.samples.partition_point(|(stamp, _)| *stamp < at),
+        };
+
+        if let Some((stamp, existing)) = self.samples.get_mut(index) {
+            if *stamp == at {
+                *existing = value;
+                return;
+            }
+        }
+
+        if self.samples.len() == self.capacity {
+            if index == 0 {
+                return;
+            }
+            self.samples.pop_front();
+            self.samples.insert(index - 1, (at, value));
+        } else {
+            self.samples.insert(index, (at, value));
+        }
+    }
+
+    /// Returns samples around time stamp `at` with blend factor between them.
+    /// Returns `None` if buffer is empty.
+    pub fn sample(&self, at: TimeStamp) -> Option<Sample<'_, T>> {
+        let (first, first_value) = self.samples.front()?;
+        if at < *first {
+            return Some(Sample::Before(first_value));
+        }
+
+        // Index of the first sample after `at`, not zero.
+        let index = self.samples.partition_point(|(stamp, _)| *stamp <= at);
+        let (a, a_value) = &self.samples[index - 1];
+        if *a == at {
+            return Some(Sample::Exact(a_value));
+        }
+
+        match self.samples.get(index) {
+            None => Some(Sample::After(a_value)),
+            Some((b, b_value)) => Some(Sample::Between {
+                a: a_value,
+                b: b_value,
+                // Rounding to `f32` may produce `1` right before `b`.
+                alpha: at.inv_lerp(*a, *b).min(1.0f32.next_down()),
+            }),
+        }
+    }
+
+    /// Removes samples older than `span` relative to the latest sample.
+    pub fn prune_older_than(&mut self, span: TimeSpan) {
+        let Some(latest) = self.latest() else {
+            return;
+        };
+
+        while let Some((at, _)) = self.samples.front() {
+            if latest.elapsed_since(*at) <= span {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Removes all samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[test]
+fn test_snapshot_buffer_sample() {
+    let start = TimeStamp::start();
+    let at = |ms: u64| start + TimeSpan::MILLISECOND * ms;
+
+    let mut buffer = SnapshotBuffer::new(8);
+    assert_eq!(buffer.sample(at(0)), None);
+
+    buffer.push(at(100), 'a');
+    assert_eq!(buffer.sample(at(50)), Some(Sample::Before(&'a')));
+    assert_eq!(buffer.sample(at(100)), Some(Sample::Exact(&'a')));
+    assert_eq!(buffer.sample(at(150)), Some(Sample::After(&'a')));
+
+    buffer.push(at(200), 'b');
+    buffer.push(at(300), 'c');
+    assert_eq!(
+        buffer.sample(at(125)),
+        Some(Sample::Between {
+            a: &'a',
+            b: &'b',
+            alpha: 0.25
+        })
+    );
+    assert_eq!(buffer.sample(at(200)), Some(Sample::Exact(&'b')));
+    assert_eq!(
+        buffer.sample(at(200) + TimeSpan::NANOSECOND),
+        Some(Sample::Between {
+            a: &'b',
+            b: &'c',
+            alpha: 1e-8
+        })
+    );
+    assert_eq!(buffer.sample(at(300)), Some(Sample::Exact(&'c')));
+    assert_eq!(buffer.sample(at(301)), Some(Sample::After(&'c')));
+    assert_eq!(buffer.sample(TimeStamp::never()), Some(Sample::After(&'c')));
+    assert_eq!(buffer.sample(start), Some(Sample::Before(&'a')));
+
+    // Alpha stays below 1 right before the later sample on long ranges.
+    buffer.push(at(300) + TimeSpan::DAY, 'd');
+    let Some(Sample::Between { b, alpha, .. }) =
+        buffer.sample(at(300) + TimeSpan::DAY - TimeSpan::NANOSECOND)
+    else {
+        panic!("expected sample between 'c' and 'd'");
+    };
+    assert_eq!(b, &'d');
+    assert!(alpha < 1.0, "{}", alpha);
+}
+
+#[test]
+fn test_snapshot_buffer_push() {
+    let start = TimeStamp::start();
+    let at = |ms: u64| start + TimeSpan::MILLISECOND * ms;
+
+    let mut buffer = SnapshotBuffer::new(3);
+    buffer.push(at(300), 3);
+    buffer.push(at(100), 1);
+    buffer.push(at(200), 2);
+    assert_eq!(
+        buffer.iter().collect::<Vec<_>>(),
+        [(at(100), &1), (at(200), &2), (at(300), &3)]
+    );
+
+    // Same time stamp replaces the sample.
+    buffer.push(at(200), 20);
+    assert_eq!(buffer.sample(at(200)), Some(Sample::Exact(&20)));
+    assert_eq!(buffer.len(), 3);
+
+    // Full buffer evicts the earliest sample.
+    buffer.push(at(400), 4);
+    assert_eq!(buffer.earliest(), Some(at(200)));
+    buffer.push(at(250), 25);
+    assert_eq!(
+        buffer.iter().collect::<Vec<_>>(),
+        [(at(250), &25), (at(300), &3), (at(400), &4)]
+    );
+
+    // Sample earlier than all in full buffer is dropped.
+    buffer.push(at(50), 0);
+    assert_eq!(buffer.earliest(), Some(at(250)));
+    assert_eq!(buffer.len(), buffer.capacity());
+}
+
+#[test]
+fn test_snapshot_buffer_prune() {
+    let start = TimeStamp::start();
+    let at = |ms: u64| start + TimeSpan::MILLISECOND * ms;
+
+    let mut buffer = SnapshotBuffer::new(16);
+    buffer.prune_older_than(TimeSpan::ZERO);
+
+    for ms in (0..=500).step_by(100) {
+        buffer.push(at(ms), ms);
+    }
+
+    buffer.prune_older_than(TimeSpan::MILLISECOND * 200);
+    assert_eq!(buffer.earliest(), Some(at(300)));
+    assert_eq!(buffer.len(), 3);
+
+    buffer.prune_older_than(TimeSpan::ZERO);
+    assert_eq!(buffer.len(), 1);
+    assert_eq!(buffer.sample(at(0)), Some(Sample::Before(&500)));
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.latest(), None);
+}
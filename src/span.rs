@@ -14,6 +14,8 @@ use core::{
     time::Duration,
 };
 
+use crate::stamp::TimeStamp;
+
 /// An interval in between time stamps.
 /// This type is used to represent durations with nanosecond precision.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -144,6 +146,12 @@ impl TimeSpan {
     }
 }
 
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for TimeSpan {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for TimeSpan {}
+
 impl Debug for TimeSpan {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
@@ -164,23 +172,423 @@ impl Display for TimeSpan {
     }
 }
 
-#[derive(Debug)]
+/// Formats time span as seconds in exponential notation with `s` suffix,
+/// e.g. `1.6e-2s`. Precision is forwarded to `f64` formatting.
+impl fmt::LowerExp for TimeSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerExp::fmt(&self.as_secs_f64(), f)?;
+        f.write_str("s")
+    }
+}
+
+/// Formats time span as seconds in exponential notation with `s` suffix,
+/// e.g. `1.6E-2s`. Precision is forwarded to `f64` formatting.
+impl fmt::UpperExp for TimeSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperExp::fmt(&self.as_secs_f64(), f)?;
+        f.write_str("s")
+    }
+}
+
+impl TimeSpan {
+    /// Maximum length of the string produced by `Display` implementation.
+    pub const MAX_DISPLAY_LENGTH: usize = 19;
+
+    /// Maximum length of the string produced by alternate `Display` implementation,
+    /// i.e. `format!("{:#}", span)`.
+    pub const MAX_FULL_DISPLAY_LENGTH: usize = 25;
+
+    /// Formats time span as `Display` does into the buffer
+    /// and returns formatted string.
+    ///
+    /// Doesn't allocate.
+    pub fn display_to_buffer<'a>(&self, buf: &'a mut [u8; Self::MAX_DISPLAY_LENGTH]) -> &'a str {
+        write_to_buffer(buf, format_args!("{}", self))
+    }
+
+    /// Formats time span as alternate `Display` does into the buffer
+    /// and returns formatted string.
+    ///
+    /// Doesn't allocate.
+    pub fn display_full_to_buffer<'a>(
+        &self,
+        buf: &'a mut [u8; Self::MAX_FULL_DISPLAY_LENGTH],
+    ) -> &'a str {
+        write_to_buffer(buf, format_args!("{:#}", self))
+    }
+}
+
+fn write_to_buffer<'a>(buf: &'a mut [u8], args: fmt::Arguments<'_>) -> &'a str {
+    struct Writer<'a> {
+        buf: &'a mut [u8],
+        len: usize,
+    }
+
+    impl fmt::Write for Writer<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > self.buf.len() {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut writer = Writer { buf, len: 0 };
+    fmt::Write::write_fmt(&mut writer, args).expect("buffer is large enough for any time span");
+    let Writer { buf, len } = writer;
+
+    // Only whole `str`s are written.
+    core::str::from_utf8(&buf[..len]).unwrap()
+}
+
+impl TimeSpan {
+    /// Returns value that displays this span as time passed,
+    /// e.g. `3 minutes ago`, using [`RelativeFormat::DEFAULT`].
+    #[inline(always)]
+    pub fn display_relative(&self) -> impl Display {
+        RelativeFormat::DEFAULT.display(*self, false)
+    }
+
+    /// Returns value that displays moment `then` relative to `now`,
+    /// e.g. `3 minutes ago` or `in 2 hours`, using [`RelativeFormat::DEFAULT`].
+    #[inline(always)]
+    pub fn display_relative_to(now: TimeStamp, then: TimeStamp) -> impl Display {
+        RelativeFormat::DEFAULT.display_between(now, then)
+    }
+}
+
+impl TimeSpan {
+    /// Returns value that displays this span with adaptive unit selection,
+    /// same as `Display` implementation does.
+    #[inline(always)]
+    pub fn display_auto(self) -> impl Display {
+        self
+    }
+
+    /// Returns value that always displays this span in seconds
+    /// with `precision` digits after decimal point, e.g. `0.017s`.
+    ///
+    /// Value is rounded half-up.
+    #[inline(always)]
+    pub fn display_as_seconds(self, precision: usize) -> impl Display {
+        UnitDisplay {
+            span: self,
+            unit: Self::SECOND,
+            suffix: "s",
+            precision,
+        }
+    }
+
+    /// Returns value that always displays this span in milliseconds
+    /// with `precision` digits after decimal point, e.g. `16.7ms`.
+    ///
+    /// Value is rounded half-up.
+    #[inline(always)]
+    pub fn display_as_millis(self, precision: usize) -> impl Display {
+        UnitDisplay {
+            span: self,
+            unit: Self::MILLISECOND,
+            suffix: "ms",
+            precision,
+        }
+    }
+
+    /// Returns value that always displays this span in microseconds
+    /// with `precision` digits after decimal point, e.g. `16666.667us`.
+    ///
+    /// Value is rounded half-up.
+    #[inline(always)]
+    pub fn display_as_micros(self, precision: usize) -> impl Display {
+        UnitDisplay {
+            span: self,
+            unit: Self::MICROSECOND,
+            suffix: "us",
+            precision,
+        }
+    }
+}
+
+struct UnitDisplay {
+    span: TimeSpan,
+    unit: TimeSpan,
+    suffix: &'static str,
+    precision: usize,
+}
+
+impl Display for UnitDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Digits beyond nanosecond resolution are always zero.
+        let unit = self.unit.as_nanos() as u128;
+        let digits = self.precision.min(unit.ilog10() as usize);
+
+        let scale = 10u128.pow(digits as u32);
+        let value = (2 * self.span.as_nanos() as u128 * scale + unit) / (2 * unit);
+
+        write!(f, "{}", value / scale)?;
+        if self.precision > 0 {
+            write!(f, ".{:0digits$}", value % scale, digits = digits)?;
+            for _ in digits..self.precision {
+                f.write_str("0")?;
+            }
+        }
+        f.write_str(self.suffix)
+    }
+}
+
+impl TimeSpan {
+    /// Returns value that displays this span as time of day
+    /// for span since midnight, e.g. `13:05`, or `1:05 PM` if `twelve_hour` is `true`.
+    ///
+    /// Spans longer than a day wrap around, seconds are truncated.
+    #[inline(always)]
+    pub fn display_time_of_day(self, twelve_hour: bool) -> impl Display {
+        TimeOfDayDisplay {
+            span: self.rem_span(NonZeroTimeSpan::DAY),
+            twelve_hour,
+        }
+    }
+}
+
+struct TimeOfDayDisplay {
+    span: TimeSpan,
+    twelve_hour: bool,
+}
+
+impl Display for TimeOfDayDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hours = self.span.as_hours();
+        let minutes = self.span.as_minutes() % 60;
+
+        if self.twelve_hour {
+            let suffix = if hours < 12 { "AM" } else { "PM" };
+            let hours = match hours % 12 {
+                0 => 12,
+                hours => hours,
+            };
+            write!(f, "{}:{:02} {}", hours, minutes, suffix)
+        } else {
+            write!(f, "{:02}:{:02}", hours, minutes)
+        }
+    }
+}
+
+/// Options for coarse human-friendly relative display of time spans.
+///
+/// Spans shorter than `just_now` are displayed as `just now`.
+/// Other spans are displayed in the largest unit among seconds, minutes, hours and days
+/// that keeps the value at least one, e.g. `45 seconds`, `3 minutes`, `2 hours`, `5 days`.
+///
+/// Value is rounded half-up, so 89 minutes are `1 hour` and 90 minutes are `2 hours`,
+/// and rounding may carry into the next unit, e.g. 59.5 seconds are `1 minute`.
+/// With `truncate` set value is rounded down instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RelativeFormat {
+    /// Spans shorter than this are displayed as `just now`.
+    pub just_now: TimeSpan,
+
+    /// Round values down instead of half-up.
+    pub truncate: bool,
+}
+
+impl Default for RelativeFormat {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl RelativeFormat {
+    /// Default options. `just now` threshold is 5 seconds, values are rounded half-up.
+    pub const DEFAULT: Self = RelativeFormat {
+        just_now: TimeSpan::new(5_000_000_000),
+        truncate: false,
+    };
+
+    /// Returns value that displays `span` as time passed, e.g. `3 minutes ago`,
+    /// or as time remaining, e.g. `in 3 minutes`, if `future` is `true`.
+    #[inline(always)]
+    pub fn display(self, span: TimeSpan, future: bool) -> impl Display {
+        RelativeDisplay {
+            format: self,
+            span,
+            future,
+        }
+    }
+
+    /// Returns value that displays moment `then` relative to `now`.
+    #[inline(always)]
+    pub fn display_between(self, now: TimeStamp, then: TimeStamp) -> impl Display {
+        match then.checked_elapsed_since(now) {
+            Some(span) => self.display(span, true),
+            None => self.display(now - then, false),
+        }
+    }
+}
+
+struct RelativeDisplay {
+    format: RelativeFormat,
+    span: TimeSpan,
+    future: bool,
+}
+
+impl Display for RelativeDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.span < self.format.just_now {
+            return f.write_str("just now");
+        }
+
+        const UNITS: [(TimeSpan, u64, &str); 4] = [
+            (TimeSpan::SECOND, 60, "second"),
+            (TimeSpan::MINUTE, 60, "minute"),
+            (TimeSpan::HOUR, 24, "hour"),
+            (TimeSpan::DAY, u64::MAX, "day"),
+        ];
+
+        let nanos = self.span.as_nanos() as u128;
+        let mut value = 0;
+        let mut name = "";
+        for (unit, limit, unit_name) in UNITS {
+            let unit = unit.as_nanos() as u128;
+            value = if self.format.truncate {
+                nanos / unit
+            } else {
+                (2 * nanos + unit) / (2 * unit)
+            } as u64;
+            name = unit_name;
+
+            if value < limit {
+                break;
+            }
+        }
+
+        let plural = if value == 1 { "" } else { "s" };
+        if self.future {
+            write!(f, "in {} {}{}", value, name, plural)
+        } else {
+            write!(f, "{} {}{} ago", value, name, plural)
+        }
+    }
+}
+
+/// Error returned when parsing [`TimeSpan`] from string fails.
+///
+/// Every error points at the offending part of the input,
+/// see [`TimeSpanParseErr::span`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TimeSpanParseErr {
-    NonASCII,
+    /// Non-ASCII character in the input.
+    NonASCII { span: Range<usize> },
+
+    /// Input is longer than any valid time span string.
     StringTooLarge { len: usize },
-    IntParseError { source: core::num::ParseIntError },
+
+    /// Failed to parse integer component.
+    IntParseError {
+        source: core::num::ParseIntError,
+        span: Range<usize>,
+    },
+
+    /// Delimiter is not allowed at this position.
     UnexpectedDelimiter { delim: char, pos: usize },
-    UnexpectedEndOfString,
-    UnexpectedSuffix,
-    HoursOutOfBound { hours: u64 },
-    MinutesOutOfBound { minutes: u64 },
-    SecondsOutOfBound { seconds: u64 },
+
+    /// Input ended where more was expected.
+    UnexpectedEndOfString { pos: usize },
+
+    /// Unsupported unit suffix.
+    UnexpectedSuffix { span: Range<usize> },
+
+    /// Hours are out of range when days are specified.
+    HoursOutOfBound { hours: u64, span: Range<usize> },
+
+    /// Minutes are out of range when hours are specified.
+    MinutesOutOfBound { minutes: u64, span: Range<usize> },
+
+    /// Seconds are out of range when minutes are specified.
+    SecondsOutOfBound { seconds: u64, span: Range<usize> },
+
+    /// Time span doesn't fit into [`TimeSpan`].
+    Overflow { span: Range<usize> },
+
+    /// Unknown unit.
+    UnknownUnit { span: Range<usize> },
+
+    /// Time span is negative.
+    Negative { span: Range<usize> },
+}
+
+impl TimeSpanParseErr {
+    /// Returns byte range of the offending part of the input.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::StringTooLarge { len } => Some(MAX_TIME_SPAN_STRING..*len),
+            Self::UnexpectedDelimiter { delim, pos } => Some(*pos..*pos + delim.len_utf8()),
+            Self::UnexpectedEndOfString { pos } => Some(*pos..*pos),
+            Self::NonASCII { span }
+            | Self::IntParseError { span, .. }
+            | Self::UnexpectedSuffix { span }
+            | Self::HoursOutOfBound { span, .. }
+            | Self::MinutesOutOfBound { span, .. }
+            | Self::SecondsOutOfBound { span, .. }
+            | Self::Overflow { span }
+            | Self::UnknownUnit { span }
+            | Self::Negative { span } => Some(span.clone()),
+        }
+    }
+
+    /// Maps positions in the input with `start` and `end` functions.
+    fn map_positions(self, start: impl Fn(usize) -> usize, end: impl Fn(usize) -> usize) -> Self {
+        let map = |span: Range<usize>| {
+            if span.is_empty() {
+                start(span.start)..start(span.start)
+            } else {
+                start(span.start)..end(span.end)
+            }
+        };
+
+        match self {
+            Self::StringTooLarge { len } => Self::StringTooLarge { len },
+            Self::UnexpectedDelimiter { delim, pos } => Self::UnexpectedDelimiter {
+                delim,
+                pos: start(pos),
+            },
+            Self::UnexpectedEndOfString { pos } => Self::UnexpectedEndOfString { pos: start(pos) },
+            Self::NonASCII { span } => Self::NonASCII { span: map(span) },
+            Self::IntParseError { source, span } => Self::IntParseError {
+                source,
+                span: map(span),
+            },
+            Self::UnexpectedSuffix { span } => Self::UnexpectedSuffix { span: map(span) },
+            Self::HoursOutOfBound { hours, span } => Self::HoursOutOfBound {
+                hours,
+                span: map(span),
+            },
+            Self::MinutesOutOfBound { minutes, span } => Self::MinutesOutOfBound {
+                minutes,
+                span: map(span),
+            },
+            Self::SecondsOutOfBound { seconds, span } => Self::SecondsOutOfBound {
+                seconds,
+                span: map(span),
+            },
+            Self::Overflow { span } => Self::Overflow { span: map(span) },
+            Self::UnknownUnit { span } => Self::UnknownUnit { span: map(span) },
+            Self::Negative { span } => Self::Negative { span: map(span) },
+        }
+    }
 }
 
 impl fmt::Display for TimeSpanParseErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::NonASCII => f.write_str("Time spans encoded in strings are always ASCII"),
+            Self::NonASCII { span } => write!(
+                f,
+                "Time spans encoded in strings are always ASCII. Non-ASCII character at {}",
+                span.start
+            ),
             Self::StringTooLarge { len } => {
                 write!(
                     f,
@@ -188,26 +596,33 @@ impl fmt::Display for TimeSpanParseErr {
                     MAX_TIME_SPAN_STRING, len
                 )
             }
-            Self::IntParseError { .. } => f.write_str("Failed to parse integer"),
+            Self::IntParseError { span, .. } => {
+                write!(f, "Failed to parse integer at {}..{}", span.start, span.end)
+            }
             Self::UnexpectedDelimiter { delim, pos } => {
                 write!(f, "Unexpected delimiter '{}' at {}", delim, pos)
             }
-            Self::UnexpectedEndOfString => f.write_str("Unexpected end of string"),
-            Self::UnexpectedSuffix => {
-                f.write_str("Unexpected suffix. Only `s`, `ms` and `us` suffixes are supported")
-            }
-            Self::HoursOutOfBound { hours } => {
+            Self::UnexpectedEndOfString { .. } => f.write_str("Unexpected end of string"),
+            Self::UnexpectedSuffix { span } => write!(
+                f,
+                "Unexpected suffix at {}. Only `s`, `ms`, `us` and `ns` suffixes are supported",
+                span.start
+            ),
+            Self::HoursOutOfBound { hours, .. } => {
                 write!(f, "Hours must be in range 0-23 when days are specified. Value at hours position is '{}'", hours)
             }
-            Self::MinutesOutOfBound { minutes } => {
+            Self::MinutesOutOfBound { minutes, .. } => {
                 write!(f, "Minutes must be in range 0-59 when hours are specified. Value at minutes position is '{}'", minutes)
             }
-            Self::SecondsOutOfBound { seconds } => {
+            Self::SecondsOutOfBound { seconds, .. } => {
                 write!(
                     f,
                     "Seconds must be in range 0-59 when minutes are specified. Value at seconds position is '{}'", seconds
                 )
             }
+            Self::Overflow { .. } => f.write_str("Time span is too large to be represented"),
+            Self::UnknownUnit { span } => write!(f, "Unknown time unit at {}", span.start),
+            Self::Negative { .. } => f.write_str("Time span can't be negative"),
         }
     }
 }
@@ -216,7 +631,7 @@ impl fmt::Display for TimeSpanParseErr {
 impl std::error::Error for TimeSpanParseErr {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::IntParseError { source } => Some(source),
+            Self::IntParseError { source, .. } => Some(source),
             _ => None,
         }
     }
@@ -224,12 +639,86 @@ impl std::error::Error for TimeSpanParseErr {
 
 const MAX_TIME_SPAN_STRING: usize = 48;
 
+/// Returns range of `range` in `s` with surrounding whitespace trimmed.
+fn trim_range(s: &str, range: Range<usize>) -> Range<usize> {
+    let token = &s[range.clone()];
+    let start = range.start + (token.len() - token.trim_start().len());
+    let end = range.end - (token.len() - token.trim_end().len());
+    start..end.max(start)
+}
+
+/// Parses integer in `range` of `s` ignoring surrounding whitespace.
+fn parse_int_at(s: &str, range: Range<usize>) -> Result<(u64, Range<usize>), TimeSpanParseErr> {
+    let span = trim_range(s, range);
+    match s[span.clone()].parse() {
+        Ok(value) => Ok((value, span)),
+        Err(source) => Err(TimeSpanParseErr::IntParseError { source, span }),
+    }
+}
+
+/// Parses fraction digits in `range` of `s` ignoring surrounding whitespace
+/// and returns that fraction of `unit`.
+///
+/// Digits beyond nanosecond precision are truncated.
+fn parse_fract_at(
+    s: &str,
+    range: Range<usize>,
+    unit: TimeSpan,
+) -> Result<TimeSpan, TimeSpanParseErr> {
+    let span = trim_range(s, range);
+    if span.is_empty() || !s[span.clone()].bytes().all(|b| b.is_ascii_digit()) {
+        // Produces an error for malformed fraction.
+        parse_int_at(s, span.clone())?;
+    }
+
+    // More digits can't affect result of any unit up to `u64::MAX` nanoseconds.
+    let digits = span.len().min(19);
+    let (fract, _) = parse_int_at(s, span.start..span.start + digits)?;
+    let nanos = fract as u128 * unit.nanos as u128 / 10u128.pow(digits as u32);
+    Ok(TimeSpan::new(nanos as u64))
+}
+
+/// Unit suffixes accepted by `FromStr` implementation, matched case-insensitively.
+const UNIT_SUFFIXES: [(&str, TimeSpan); 4] = [
+    ("s", TimeSpan::SECOND),
+    ("ms", TimeSpan::MILLISECOND),
+    ("us", TimeSpan::MICROSECOND),
+    ("ns", TimeSpan::NANOSECOND),
+];
+
+fn unexpected_delimiter(pos: usize, delim: &str) -> TimeSpanParseErr {
+    TimeSpanParseErr::UnexpectedDelimiter {
+        delim: delim.chars().next().unwrap(),
+        pos,
+    }
+}
+
+/// Parses time span in one of the following forms:
+///
+/// * `S` - whole seconds, e.g. `90`.
+/// * `S.F` - seconds with fraction, e.g. `1.5`.
+/// * `N unit` or `N.F unit` - number with unit suffix `s`, `ms`, `us` or `ns`,
+///   e.g. `16ms`, `1.5 S` or `250 US`. Units are case-insensitive.
+/// * `M:S[.F]` - minutes and seconds, e.g. `2:11.011`.
+/// * `H:M:S[.F]` - hours, minutes and seconds, e.g. `1:02:11`.
+/// * `DdH:M[:S[.F]]` - days followed by `d`, `D`, `t` or `T`,
+///   hours, minutes and optional seconds, e.g. `1d00:00`.
+///
+/// Whitespace is allowed around numbers, separators and units.
+/// In composite forms all components except the first are bounded,
+/// e.g. minutes must be less than 60 when hours are specified.
+/// Fraction digits beyond nanosecond precision are truncated.
+///
+/// This is the grammar produced by both regular and alternate `Display` implementations.
+/// See [`TimeSpan::parse_lenient`] and [`TimeSpan::parse_go`] for other grammars.
 impl FromStr for TimeSpan {
     type Err = TimeSpanParseErr;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if !s.is_ascii() {
-            return Err(TimeSpanParseErr::NonASCII);
+        if let Some((pos, c)) = s.char_indices().find(|(_, c)| !c.is_ascii()) {
+            return Err(TimeSpanParseErr::NonASCII {
+                span: pos..pos + c.len_utf8(),
+            });
         }
 
         if s.len() > MAX_TIME_SPAN_STRING {
@@ -244,66 +733,77 @@ impl FromStr for TimeSpan {
             minutes: Option<Range<usize>>,
             seconds: Option<Range<usize>>,
             fract: Option<Range<usize>>,
-            denom: u32,
         }
 
         impl Ranges {
             fn parse(self, s: &str) -> Result<TimeSpan, TimeSpanParseErr> {
-                let seconds: u64 = self
-                    .seconds
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
+                let parse = |range: Option<Range<usize>>| match range {
+                    None => Ok((0, 0..0)),
+                    Some(range) => parse_int_at(s, range),
+                };
 
+                let (seconds, span) = parse(self.seconds)?;
                 if self.minutes.is_some() && seconds > 59 {
-                    return Err(TimeSpanParseErr::SecondsOutOfBound { seconds });
+                    return Err(TimeSpanParseErr::SecondsOutOfBound { seconds, span });
                 }
 
-                let minutes: u64 = self
-                    .minutes
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-
+                let (minutes, span) = parse(self.minutes)?;
                 if self.hours.is_some() && minutes > 59 {
-                    return Err(TimeSpanParseErr::MinutesOutOfBound { minutes });
+                    return Err(TimeSpanParseErr::MinutesOutOfBound { minutes, span });
                 }
 
-                let hours: u64 = self
-                    .hours
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-
+                let (hours, span) = parse(self.hours)?;
                 if self.days.is_some() && hours > 23 {
-                    return Err(TimeSpanParseErr::HoursOutOfBound { hours });
+                    return Err(TimeSpanParseErr::HoursOutOfBound { hours, span });
                 }
 
-                let days: u64 = self
-                    .days
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-
-                let fract: u64 = self
-                    .fract
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                let micros = if self.denom > 6 {
-                    fract / 10u64.pow(self.denom - 6)
-                } else {
-                    fract * 10u64.pow(6 - self.denom)
+                let (days, _) = parse(self.days)?;
+
+                let fract = match self.fract {
+                    None => TimeSpan::ZERO,
+                    Some(range) => parse_fract_at(s, range, TimeSpan::SECOND)?,
                 };
 
-                Ok(days * TimeSpan::DAY
-                    + hours * TimeSpan::HOUR
-                    + minutes * TimeSpan::MINUTE
-                    + seconds * TimeSpan::SECOND
-                    + micros * TimeSpan::MICROSECOND)
+                TimeSpan::DAY
+                    .checked_mul(days)
+                    .and_then(|span| span.checked_add(TimeSpan::HOUR.checked_mul(hours)?))
+                    .and_then(|span| span.checked_add(TimeSpan::MINUTE.checked_mul(minutes)?))
+                    .and_then(|span| span.checked_add(TimeSpan::SECOND.checked_mul(seconds)?))
+                    .and_then(|span| span.checked_add(fract))
+                    .ok_or_else(|| TimeSpanParseErr::Overflow {
+                        span: trim_range(s, 0..s.len()),
+                    })
             }
         }
 
+        // Parses number with optional fraction before unit suffix
+        // and multiplies it by the unit.
+        let with_suffix = |suffix: usize| {
+            let tail = trim_range(s, suffix..s.len());
+            let unit = match UNIT_SUFFIXES
+                .iter()
+                .find(|(name, _)| s[tail.clone()].eq_ignore_ascii_case(name))
+            {
+                None => return Err(TimeSpanParseErr::UnexpectedSuffix { span: tail }),
+                Some((_, unit)) => *unit,
+            };
+
+            let (int, fract) = match s[..suffix].find('.') {
+                None => (0..suffix, None),
+                Some(dot) => (0..dot, Some(dot + 1..suffix)),
+            };
+
+            let (value, span) = parse_int_at(s, int)?;
+            let fract = match fract {
+                None => TimeSpan::ZERO,
+                Some(range) => parse_fract_at(s, range, unit)?,
+            };
+            unit.checked_mul(value)
+                .and_then(|span| span.checked_add(fract))
+                .ok_or(TimeSpanParseErr::Overflow { span })
+        };
+        let is_suffix = |delim: &str| delim.as_bytes()[0].is_ascii_alphabetic();
+
         match seps.next() {
             Some((dh, "d" | "D" | "t" | "T")) => match seps.next() {
                 Some((hm, ":")) => match seps.next() {
@@ -313,7 +813,6 @@ impl FromStr for TimeSpan {
                         minutes: Some(hm + 1..s.len()),
                         seconds: None,
                         fract: None,
-                        denom: 0,
                     },
                     Some((ms, ":")) => match seps.next() {
                         None => Ranges {
@@ -322,66 +821,42 @@ impl FromStr for TimeSpan {
                             minutes: Some(hm + 1..ms),
                             seconds: Some(ms + 1..s.len()),
                             fract: None,
-                            denom: 0,
                         },
                         Some((sf, ".")) => {
                             if let Some((pos, delim)) = seps.next() {
-                                return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                                    delim: delim.chars().next().unwrap(),
-                                    pos,
-                                });
+                                return Err(unexpected_delimiter(pos, delim));
                             } else {
                                 Ranges {
                                     days: Some(0..dh),
                                     hours: Some(dh + 1..hm),
                                     minutes: Some(hm + 1..ms),
                                     seconds: Some(ms + 1..sf),
-                                    fract: Some(sf + 1..s.len().min(sf + 21)),
-                                    denom: (s.len() - sf - 1) as u32,
+                                    fract: Some(sf + 1..s.len()),
                                 }
                             }
                         }
 
-                        Some((pos, delim)) => {
-                            return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                                delim: delim.chars().next().unwrap(),
-                                pos,
-                            });
-                        }
+                        Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
                     },
-                    Some((pos, delim)) => {
-                        return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                            delim: delim.chars().next().unwrap(),
-                            pos,
-                        });
-                    }
+                    Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
                 },
-                Some((pos, delim)) => {
-                    return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                        delim: delim.chars().next().unwrap(),
-                        pos,
-                    });
-                }
+                Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
                 None => {
-                    return Err(TimeSpanParseErr::UnexpectedEndOfString);
+                    return Err(TimeSpanParseErr::UnexpectedEndOfString { pos: s.len() });
                 }
             },
             Some((hms, ":")) => match seps.next() {
                 Some((ms, ":")) => match seps.next() {
                     Some((sf, ".")) => {
                         if let Some((pos, delim)) = seps.next() {
-                            return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                                delim: delim.chars().next().unwrap(),
-                                pos,
-                            });
+                            return Err(unexpected_delimiter(pos, delim));
                         } else {
                             Ranges {
                                 days: None,
                                 hours: Some(0..hms),
                                 minutes: Some(hms + 1..ms),
                                 seconds: Some(ms + 1..sf),
-                                fract: Some(sf + 1..s.len().min(sf + 21)),
-                                denom: (s.len() - sf - 1) as u32,
+                                fract: Some(sf + 1..s.len()),
                             }
                         }
                     }
@@ -391,21 +866,12 @@ impl FromStr for TimeSpan {
                         minutes: Some(hms + 1..ms),
                         seconds: Some(ms + 1..s.len()),
                         fract: None,
-                        denom: 0,
                     },
-                    Some((pos, delim)) => {
-                        return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                            delim: delim.chars().next().unwrap(),
-                            pos,
-                        });
-                    }
+                    Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
                 },
                 Some((sf, ".")) => {
                     if let Some((pos, delim)) = seps.next() {
-                        return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                            delim: delim.chars().next().unwrap(),
-                            pos,
-                        });
+                        return Err(unexpected_delimiter(pos, delim));
                     } else {
                         Ranges {
                             days: None,
@@ -413,7 +879,6 @@ impl FromStr for TimeSpan {
                             minutes: Some(0..hms),
                             seconds: Some(hms + 1..sf),
                             fract: Some(sf + 1..s.len()),
-                            denom: (s.len() - sf - 1) as u32,
                         }
                     }
                 }
@@ -423,89 +888,232 @@ impl FromStr for TimeSpan {
                     minutes: Some(0..hms),
                     seconds: Some(hms + 1..s.len()),
                     fract: None,
-                    denom: 0,
                 },
-                Some((pos, delim)) => {
-                    return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                        delim: delim.chars().next().unwrap(),
-                        pos,
-                    });
-                }
+                Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
             },
 
-            Some((sf, ".")) => {
-                if let Some((pos, delim)) = seps.next() {
-                    return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                        delim: delim.chars().next().unwrap(),
-                        pos,
-                    });
-                } else {
-                    Ranges {
-                        days: None,
-                        hours: None,
-                        minutes: None,
-                        seconds: Some(0..sf),
-                        fract: Some(sf + 1..s.len()),
-                        denom: (s.len() - sf - 1) as u32,
-                    }
-                }
+            Some((sf, ".")) => match seps.next() {
+                None => Ranges {
+                    days: None,
+                    hours: None,
+                    minutes: None,
+                    seconds: Some(0..sf),
+                    fract: Some(sf + 1..s.len()),
+                },
+                Some((suffix, delim)) if is_suffix(delim) => return with_suffix(suffix),
+                Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
+            },
+
+            Some((suffix, delim)) if is_suffix(delim) => return with_suffix(suffix),
+
+            None => {
+                let (seconds, span) = parse_int_at(s, 0..s.len())?;
+                return Self::SECOND
+                    .checked_mul(seconds)
+                    .ok_or(TimeSpanParseErr::Overflow { span });
             }
 
-            Some((suffix, "s")) => {
-                if s[suffix..].trim() != "s" {
-                    return Err(TimeSpanParseErr::UnexpectedSuffix);
+            Some((pos, delim)) => return Err(unexpected_delimiter(pos, delim)),
+        }
+        .parse(s)
+    }
+}
+
+impl TimeSpan {
+    /// Parses time span in the format of Go's `time.ParseDuration`.
+    ///
+    /// String is a sequence of decimal numbers, each with optional fraction
+    /// and a unit suffix, such as `300ms`, `1.5h` or `2h45m`.
+    /// Valid units are `ns`, `us` (or `µs`), `ms`, `s`, `m` and `h`.
+    /// Unit-less `0` is also accepted.
+    ///
+    /// Leading `+` sign is allowed. Leading `-` sign is only allowed for zero spans,
+    /// since time spans can't be negative.
+    pub fn parse_go(s: &str) -> Result<TimeSpan, TimeSpanParseErr> {
+        let (negative, mut rest) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        if rest == "0" {
+            return Ok(TimeSpan::ZERO);
+        }
+        if rest.is_empty() {
+            return Err(TimeSpanParseErr::UnexpectedEndOfString { pos: s.len() });
+        }
+
+        let mut total: u64 = 0;
+        while !rest.is_empty() {
+            let pos = s.len() - rest.len();
+
+            let int_len = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let (int, tail) = rest.split_at(int_len);
+            let (fract, tail) = match tail.strip_prefix('.') {
+                None => ("", tail),
+                Some(tail) => {
+                    let fract_len = tail.bytes().take_while(u8::is_ascii_digit).count();
+                    tail.split_at(fract_len)
                 }
+            };
 
-                let seconds: u64 = s[..suffix]
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(seconds * Self::SECOND);
+            if int.is_empty() && fract.is_empty() {
+                return Err(match rest.chars().next() {
+                    Some(delim) => TimeSpanParseErr::UnexpectedDelimiter { delim, pos },
+                    None => TimeSpanParseErr::UnexpectedEndOfString { pos },
+                });
             }
 
-            Some((suffix, "m")) => {
-                if s[suffix..].trim() != "ms" {
-                    return Err(TimeSpanParseErr::UnexpectedSuffix);
+            let unit_pos = s.len() - tail.len();
+            let unit_len = tail
+                .find(|c: char| c == '.' || c.is_ascii_digit())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(unit_len);
+
+            let unit = match unit {
+                "" => return Err(TimeSpanParseErr::UnexpectedEndOfString { pos: unit_pos }),
+                "ns" => 1,
+                "us" | "\u{b5}s" | "\u{3bc}s" => 1_000,
+                "ms" => 1_000_000,
+                "s" => 1_000_000_000,
+                "m" => 60_000_000_000,
+                "h" => 3_600_000_000_000,
+                _ => {
+                    return Err(TimeSpanParseErr::UnknownUnit {
+                        span: unit_pos..unit_pos + unit_len,
+                    })
                 }
+            };
 
-                let millis: u64 = s[..suffix]
-                    .trim()
+            let int: u64 = match int {
+                "" => 0,
+                int => int
                     .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(millis * Self::MILLISECOND);
+                    .map_err(|source| TimeSpanParseErr::IntParseError {
+                        source,
+                        span: pos..pos + int.len(),
+                    })?,
+            };
+
+            // Digits beyond nanosecond precision of the largest unit can't affect result.
+            let fract = &fract[..fract.len().min(19)];
+            let mut fract_value: u128 = 0;
+            for b in fract.bytes() {
+                fract_value = fract_value * 10 + u128::from(b - b'0');
             }
+            let fract_nanos = fract_value * unit as u128 / 10u128.pow(fract.len() as u32);
+
+            total = int
+                .checked_mul(unit)
+                .and_then(|nanos| nanos.checked_add(fract_nanos as u64))
+                .and_then(|nanos| nanos.checked_add(total))
+                .ok_or(TimeSpanParseErr::Overflow {
+                    span: pos..unit_pos + unit_len,
+                })?;
+
+            rest = tail;
+        }
+
+        if negative && total != 0 {
+            return Err(TimeSpanParseErr::Negative { span: 0..1 });
+        }
+
+        Ok(TimeSpan::new(total))
+    }
+
+    /// Parses time span in a locale-tolerant way.
+    ///
+    /// Accepts the same grammar as strict [`FromStr`] implementation with following relaxations:
+    ///
+    /// * Underscores, regular, non-breaking and thin spaces placed between two digits
+    ///   are treated as digit grouping and ignored. `1 000 ms` and `1_000ms` are both one second.
+    /// * Comma placed between two digits and followed by exactly three digits
+    ///   is treated as digit grouping. So `1,500` is `1500` seconds and `1,000,000us` is one second.
+    /// * Otherwise single comma is treated as a decimal separator if there is no `.` in the string.
+    ///   So `1,5` and `1:30,25` are parsed as `1.5` and `1:30.25`.
+    ///
+    /// Any other comma is rejected.
+    ///
+    /// This function never panics, malformed input produces an error.
+    pub fn parse_lenient(s: &str) -> Result<TimeSpan, TimeSpanParseErr> {
+        let trimmed = s.trim();
+        let offset = s.len() - s.trim_start().len();
+        let s = trimmed;
+
+        let has_dot = s.contains('.');
+        let commas = s.matches(',').count();
+
+        let mut buf = [0u8; MAX_TIME_SPAN_STRING];
+
+        // Position of each normalized byte in the input.
+        let mut positions = [0usize; MAX_TIME_SPAN_STRING];
+        let mut len = 0;
+        let mut prev_digit = false;
+
+        let mut chars = s.char_indices().peekable();
+        while let Some((pos, c)) = chars.next() {
+            let next_digit = matches!(chars.peek(), Some((_, next)) if next.is_ascii_digit());
 
-            Some((suffix, "u")) => {
-                if s[suffix..].trim() != "us" {
-                    return Err(TimeSpanParseErr::UnexpectedSuffix);
+            let byte = match c {
+                '_' | ' ' | '\u{a0}' | '\u{2009}' | '\u{202f}' if prev_digit && next_digit => {
+                    continue;
                 }
+                ',' if prev_digit && next_digit => {
+                    let rest = &s.as_bytes()[pos + 1..];
+                    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                    if digits == 3 {
+                        continue;
+                    }
+                    if commas > 1 || has_dot {
+                        return Err(TimeSpanParseErr::UnexpectedDelimiter {
+                            delim: ',',
+                            pos: offset + pos,
+                        });
+                    }
+                    b'.'
+                }
+                c if c.is_ascii() => c as u8,
+                _ => {
+                    return Err(TimeSpanParseErr::NonASCII {
+                        span: offset + pos..offset + pos + c.len_utf8(),
+                    })
+                }
+            };
 
-                let micros: u64 = s[..suffix]
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(micros * Self::MICROSECOND);
+            if len == buf.len() {
+                return Err(TimeSpanParseErr::StringTooLarge { len: s.len() });
             }
+            buf[len] = byte;
+            positions[len] = pos;
+            len += 1;
+            prev_digit = byte.is_ascii_digit();
+        }
 
-            None => {
-                let seconds: u64 = s
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(seconds * Self::SECOND);
-            }
+        // Normalized bytes are ASCII, so each maps to a single byte of the input.
+        let start = |i: usize| offset + if i < len { positions[i] } else { s.len() };
+        let end = |i: usize| offset + positions[i - 1] + 1;
 
-            Some((pos, delim)) => {
-                return Err(TimeSpanParseErr::UnexpectedDelimiter {
-                    delim: delim.chars().next().unwrap(),
-                    pos,
-                });
-            }
+        match core::str::from_utf8(&buf[..len]) {
+            Ok(normalized) => normalized
+                .parse()
+                .map_err(|err: TimeSpanParseErr| err.map_positions(start, end)),
+            Err(_) => Err(TimeSpanParseErr::NonASCII {
+                span: offset..offset + s.len(),
+            }),
         }
-        .parse(s)
     }
 }
 
+/// Serializes `TimeSpan`.
+///
+/// Human-readable formats get a string in full format, e.g. `"0d00:00:01.000000001"`.
+/// Other formats get integer number of nanoseconds.
+///
+/// Both forms are lossless: every `TimeSpan` from [`TimeSpan::MIN`] to [`TimeSpan::MAX`]
+/// is deserialized back to the exact same value.
+///
+/// Earlier versions serialized human-readable form with regular `Display`, e.g. `"1.500s"`,
+/// which dropped digits below the displayed unit. Such strings are still deserialized.
 #[cfg(feature = "serde")]
 impl serde::Serialize for TimeSpan {
     #[inline]
@@ -513,32 +1121,57 @@ impl serde::Serialize for TimeSpan {
     where
         S: serde::Serializer,
     {
-        // Serialize in pretty format for human readable serializer
+        // Serialize in full format for human readable serializer.
+        // Unlike compact format it preserves nanoseconds and is always parsed back.
         if serializer.is_human_readable() {
-            serializer.serialize_str(&self.to_string())
+            serializer.collect_str(&format_args!("{:#}", self))
         } else {
             serializer.serialize_u64(self.nanos)
         }
     }
 }
 
+/// Deserializes `TimeSpan`.
+///
+/// Human-readable formats accept strings with encoded time span,
+/// numbers of seconds (integer or floating point)
+/// and `{ "secs": u64, "nanos": u32 }` maps as produced for `std::time::Duration`.
+///
+/// Other formats accept integer number of nanoseconds.
 #[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for TimeSpan {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        struct Visitor;
+        struct Visitor {
+            human_readable: bool,
+        }
 
         impl<'de> serde::de::Visitor<'de> for Visitor {
             type Value = TimeSpan;
 
             fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                fmt.write_str("String with encoded time span or integer representing nanoseconds")
+                if self.human_readable {
+                    fmt.write_str("String with encoded time span, number of seconds or map with `secs` and `nanos`")
+                } else {
+                    fmt.write_str(
+                        "String with encoded time span or integer representing nanoseconds",
+                    )
+                }
             }
 
-            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
-                Ok(TimeSpan { nanos: v })
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                if self.human_readable {
+                    TimeSpan::SECOND
+                        .checked_mul(v)
+                        .ok_or_else(|| E::custom("TimeSpan is out of range"))
+                } else {
+                    Ok(TimeSpan { nanos: v })
+                }
             }
 
             fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
@@ -548,41 +1181,224 @@ impl<'de> serde::Deserialize<'de> for TimeSpan {
                 if v < 0 {
                     Err(E::custom("TimeSpan cannot be negative"))
                 } else {
-                    Ok(TimeSpan { nanos: v as u64 })
+                    self.visit_u64(v as u64)
                 }
             }
 
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                if !v.is_finite() {
+                    return Err(E::custom("TimeSpan must be finite"));
+                }
+                if v < 0.0 {
+                    return Err(E::custom("TimeSpan cannot be negative"));
+                }
+
+                // Round to the nearest nanosecond.
+                let nanos = v * TimeSpan::SECOND.nanos as f64 + 0.5;
+                if nanos >= u64::MAX as f64 {
+                    return Err(E::custom("TimeSpan is out of range"));
+                }
+                Ok(TimeSpan {
+                    nanos: nanos as u64,
+                })
+            }
+
             fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
             {
                 v.parse().map_err(|err| E::custom(err))
             }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut secs = None;
+                let mut nanos = None;
+
+                while let Some(key) = map.next_key::<alloc::string::String>()? {
+                    match key.as_str() {
+                        "secs" => secs = Some(map.next_value::<u64>()?),
+                        "nanos" => nanos = Some(map.next_value::<u32>()?),
+                        _ => return Err(serde::de::Error::unknown_field(&key, &["secs", "nanos"])),
+                    }
+                }
+
+                let secs = secs.ok_or_else(|| serde::de::Error::missing_field("secs"))?;
+                let nanos = nanos.ok_or_else(|| serde::de::Error::missing_field("nanos"))?;
+
+                TimeSpan::SECOND
+                    .checked_mul(secs)
+                    .and_then(|span| span.checked_add(TimeSpan::new(nanos.into())))
+                    .ok_or_else(|| serde::de::Error::custom("TimeSpan is out of range"))
+            }
         }
 
         if deserializer.is_human_readable() {
-            deserializer.deserialize_str(Visitor)
+            deserializer.deserialize_any(Visitor {
+                human_readable: true,
+            })
         } else {
-            deserializer.deserialize_u64(Visitor)
+            deserializer.deserialize_u64(Visitor {
+                human_readable: false,
+            })
         }
     }
 }
 
-impl From<Duration> for TimeSpan {
-    #[inline]
-    fn from(duration: Duration) -> Self {
-        let nanos = duration.as_nanos();
-        debug_assert!(u64::MAX as u128 > nanos);
-        TimeSpan {
-            nanos: nanos as u64,
-        }
+#[cfg(feature = "num-traits")]
+impl num_traits::Zero for TimeSpan {
+    #[inline(always)]
+    fn zero() -> Self {
+        TimeSpan::ZERO
     }
-}
 
-impl From<TimeSpan> for Duration {
-    #[inline]
-    fn from(span: TimeSpan) -> Self {
-        Duration::new(span.as_seconds(), (span.as_nanos() % 1000000000) as u32)
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.nanos == 0
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::Bounded for TimeSpan {
+    #[inline(always)]
+    fn min_value() -> Self {
+        TimeSpan::MIN
+    }
+
+    #[inline(always)]
+    fn max_value() -> Self {
+        TimeSpan::MAX
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::SaturatingAdd for TimeSpan {
+    #[inline(always)]
+    fn saturating_add(&self, v: &Self) -> Self {
+        TimeSpan::saturating_add(*self, *v)
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::SaturatingSub for TimeSpan {
+    #[inline(always)]
+    fn saturating_sub(&self, v: &Self) -> Self {
+        TimeSpan::saturating_sub(*self, *v)
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::CheckedAdd for TimeSpan {
+    #[inline(always)]
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        TimeSpan::checked_add(*self, *v)
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::CheckedSub for TimeSpan {
+    #[inline(always)]
+    fn checked_sub(&self, v: &Self) -> Option<Self> {
+        TimeSpan::checked_sub(*self, *v)
+    }
+}
+
+impl From<Duration> for TimeSpan {
+    #[inline]
+    fn from(duration: Duration) -> Self {
+        let nanos = duration.as_nanos();
+        debug_assert!(u64::MAX as u128 > nanos);
+        TimeSpan {
+            nanos: nanos as u64,
+        }
+    }
+}
+
+impl From<TimeSpan> for Duration {
+    #[inline]
+    fn from(span: TimeSpan) -> Self {
+        Duration::new(span.as_seconds(), (span.as_nanos() % 1000000000) as u32)
+    }
+}
+
+/// Converts number of nanoseconds into time span.
+impl From<u64> for TimeSpan {
+    #[inline(always)]
+    fn from(nanos: u64) -> Self {
+        TimeSpan::new(nanos)
+    }
+}
+
+/// Converts time span into number of nanoseconds.
+impl From<TimeSpan> for u64 {
+    #[inline(always)]
+    fn from(span: TimeSpan) -> Self {
+        span.as_nanos()
+    }
+}
+
+/// Converts time span into number of nanoseconds.
+impl From<TimeSpan> for u128 {
+    #[inline(always)]
+    fn from(span: TimeSpan) -> Self {
+        span.as_nanos().into()
+    }
+}
+
+/// Converts time span into number of nanoseconds.
+impl From<TimeSpan> for i128 {
+    #[inline(always)]
+    fn from(span: TimeSpan) -> Self {
+        span.as_nanos().into()
+    }
+}
+
+/// Converts time span into number of nanoseconds.
+/// Fails if span is longer than `i64::MAX` nanoseconds.
+impl TryFrom<TimeSpan> for i64 {
+    type Error = TryFromIntError;
+
+    #[inline(always)]
+    fn try_from(span: TimeSpan) -> Result<Self, TryFromIntError> {
+        i64::try_from(span.as_nanos())
+    }
+}
+
+/// Converts number of nanoseconds into time span.
+/// Fails if value is negative.
+impl TryFrom<i64> for TimeSpan {
+    type Error = TryFromIntError;
+
+    #[inline(always)]
+    fn try_from(nanos: i64) -> Result<Self, TryFromIntError> {
+        u64::try_from(nanos).map(TimeSpan::new)
+    }
+}
+
+/// Converts number of nanoseconds into time span.
+/// Fails if value is negative or does not fit into `u64`.
+impl TryFrom<i128> for TimeSpan {
+    type Error = TryFromIntError;
+
+    #[inline(always)]
+    fn try_from(nanos: i128) -> Result<Self, TryFromIntError> {
+        u64::try_from(nanos).map(TimeSpan::new)
+    }
+}
+
+/// Converts number of nanoseconds into time span.
+/// Fails if value does not fit into `u64`.
+impl TryFrom<u128> for TimeSpan {
+    type Error = TryFromIntError;
+
+    #[inline(always)]
+    fn try_from(nanos: u128) -> Result<Self, TryFromIntError> {
+        u64::try_from(nanos).map(TimeSpan::new)
     }
 }
 
@@ -592,6 +1408,17 @@ impl TimeSpan {
     /// Represents duration between equal time points.
     pub const ZERO: Self = TimeSpan { nanos: 0 };
 
+    /// Smallest possible time span.
+    ///
+    /// Time spans are never negative, so this is the same as [`TimeSpan::ZERO`].
+    pub const MIN: Self = Self::ZERO;
+
+    /// Largest possible time span.
+    ///
+    /// Any `u64` number of nanoseconds is a valid time span,
+    /// so there are no unrepresentable values between `MIN` and `MAX`.
+    pub const MAX: Self = TimeSpan { nanos: u64::MAX };
+
     /// One nanosecond span.
     /// Minimal possible time span supported by this type.
     pub const NANOSECOND: Self = TimeSpan { nanos: 1 };
@@ -682,6 +1509,28 @@ impl TimeSpan {
         self.nanos / Self::SECOND.nanos
     }
 
+    /// Returns fractional part of this span in whole nanoseconds,
+    /// i.e. nanoseconds after [`TimeSpan::as_seconds`].
+    ///
+    /// Same as [`Duration::subsec_nanos`](core::time::Duration::subsec_nanos).
+    /// Time span is unsigned, so the remainder is never negative.
+    #[inline]
+    pub const fn subsec_nanos(&self) -> u32 {
+        (self.nanos % Self::SECOND.nanos) as u32
+    }
+
+    /// Returns fractional part of this span in whole microseconds.
+    #[inline]
+    pub const fn subsec_micros(&self) -> u32 {
+        self.subsec_nanos() / 1_000
+    }
+
+    /// Returns fractional part of this span in whole milliseconds.
+    #[inline]
+    pub const fn subsec_millis(&self) -> u32 {
+        self.subsec_nanos() / 1_000_000
+    }
+
     /// Returns number of whole minutes this value represents.
     #[inline]
     pub const fn as_minutes(&self) -> u64 {
@@ -719,6 +1568,30 @@ impl TimeSpan {
         self.nanos as f64 / Self::SECOND.nanos as f64
     }
 
+    /// Returns number of `unit`s in this span as floating point value.
+    ///
+    /// Generalizes [`TimeSpan::as_secs_f64`] to arbitrary unit.
+    /// Returns infinity for zero `unit`, or NaN if this span is zero too.
+    #[inline]
+    pub fn as_unit_f64(&self, unit: TimeSpan) -> f64 {
+        self.nanos as f64 / unit.nanos as f64
+    }
+
+    /// Returns span of `value` `unit`s, rounded to the nearest nanosecond.
+    ///
+    /// Result saturates to [`TimeSpan::MAX`] if it is too large.
+    /// Negative values and NaN produce [`TimeSpan::ZERO`].
+    #[inline]
+    pub fn from_unit_f64(value: f64, unit: TimeSpan) -> TimeSpan {
+        let nanos = value * unit.nanos as f64;
+        if nanos > 0.0 {
+            // Float to int cast saturates.
+            TimeSpan::new((nanos + 0.5) as u64)
+        } else {
+            TimeSpan::ZERO
+        }
+    }
+
     #[inline(always)]
     pub const fn checked_add(self, span: TimeSpan) -> Option<TimeSpan> {
         match self.nanos.checked_add(span.nanos) {
@@ -735,6 +1608,35 @@ impl TimeSpan {
         }
     }
 
+    /// Adds two spans, saturating at [`TimeSpan::MAX`].
+    #[inline(always)]
+    pub const fn saturating_add(self, span: TimeSpan) -> TimeSpan {
+        TimeSpan {
+            nanos: self.nanos.saturating_add(span.nanos),
+        }
+    }
+
+    /// Subtracts span from this one, saturating at [`TimeSpan::ZERO`].
+    #[inline(always)]
+    pub const fn saturating_sub(self, span: TimeSpan) -> TimeSpan {
+        TimeSpan {
+            nanos: self.nanos.saturating_sub(span.nanos),
+        }
+    }
+
+    /// Returns how much this measured span exceeds `budget`.
+    /// Returns `None` if it is within the budget.
+    #[inline(always)]
+    pub const fn over_budget(self, budget: TimeSpan) -> Option<TimeSpan> {
+        if self.nanos > budget.nanos {
+            Some(TimeSpan {
+                nanos: self.nanos - budget.nanos,
+            })
+        } else {
+            None
+        }
+    }
+
     #[inline(always)]
     pub const fn checked_mul(self, value: u64) -> Option<TimeSpan> {
         match self.nanos.checked_mul(value) {
@@ -743,6 +1645,61 @@ impl TimeSpan {
         }
     }
 
+    /// Appends variable-length encoding of this span to the buffer.
+    ///
+    /// Uses LEB128, so spans shorter than 128ns take one byte
+    /// and spans up to about 2ms take no more than three.
+    /// Use [`TimeSpan::decode_varint`] to decode.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn encode_varint(self, buf: &mut alloc::vec::Vec<u8>) {
+        crate::encode_varint(self.nanos, buf)
+    }
+
+    /// Decodes span encoded with [`TimeSpan::encode_varint`]
+    /// from the start of the buffer.
+    ///
+    /// Returns decoded span and number of bytes read,
+    /// or `None` if buffer is truncated or doesn't contain valid encoding.
+    /// Overlong encodings with redundant trailing bytes are not valid.
+    #[inline(always)]
+    pub fn decode_varint(buf: &[u8]) -> Option<(TimeSpan, usize)> {
+        let (nanos, len) = crate::decode_varint(buf)?;
+        Some((TimeSpan { nanos }, len))
+    }
+
+    /// Computes `addend + self * n` without intermediate overflow.
+    /// `n` may be negative.
+    ///
+    /// Returns `None` if result is negative or doesn't fit into `TimeSpan`.
+    #[inline(always)]
+    pub const fn checked_mul_add(self, n: i64, addend: TimeSpan) -> Option<TimeSpan> {
+        let nanos = addend.nanos as i128 + self.nanos as i128 * n as i128;
+        if nanos < 0 || nanos > u64::MAX as i128 {
+            return None;
+        }
+        Some(TimeSpan {
+            nanos: nanos as u64,
+        })
+    }
+
+    /// Computes `addend + self * n` without intermediate overflow.
+    /// `n` may be negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if result is negative or doesn't fit into `TimeSpan`.
+    #[inline(always)]
+    pub fn mul_add(self, n: i64, addend: TimeSpan) -> TimeSpan {
+        self.checked_mul_add(n, addend)
+            .expect("overflow when computing fused multiply-add of time spans")
+    }
+
+    /// Returns span divided by `value`, rounded down.
+    /// Returns `None` if `value` is zero.
+    ///
+    /// Spans are never negative, so the result equals Euclidean division,
+    /// see [`TimeSpan::rem`].
     #[inline(always)]
     pub const fn checked_div(self, value: u64) -> Option<TimeSpan> {
         match self.nanos.checked_div(value) {
@@ -751,12 +1708,15 @@ impl TimeSpan {
         }
     }
 
+    /// Returns span divided by `value`, rounded down.
     #[inline(always)]
     pub const fn div(self, value: NonZeroU64) -> TimeSpan {
         let nanos = self.nanos / value.get();
         TimeSpan { nanos }
     }
 
+    /// Returns number of whole `span`s in this span.
+    /// Returns `None` if `span` is zero.
     #[inline(always)]
     pub const fn checked_div_span(self, span: TimeSpan) -> Option<u64> {
         match self.nanos.checked_div(span.nanos) {
@@ -765,11 +1725,58 @@ impl TimeSpan {
         }
     }
 
+    /// Returns number of whole `span`s in this span.
     #[inline(always)]
     pub const fn div_span(self, span: NonZeroTimeSpan) -> u64 {
         self.nanos / span.nanos.get()
     }
 
+    /// Returns index of exponential bucket this span falls into.
+    ///
+    /// This is the smallest `k` such that `self <= base * 2^k`.
+    /// Zero span always falls into bucket `0`.
+    /// Result never exceeds `64`, as `base * 2^64` is larger than any span for non-zero `base`.
+    /// If `base` is zero, any non-zero span falls into bucket `64`.
+    #[inline]
+    pub const fn log2_bucket(self, base: TimeSpan) -> u32 {
+        if self.nanos == 0 {
+            return 0;
+        }
+        if base.nanos == 0 {
+            return 64;
+        }
+        // `ceil(self / base) - 1`
+        let q = (self.nanos - 1) / base.nanos;
+        64 - q.leading_zeros()
+    }
+
+    /// Returns bounds of `k`-th exponential bucket for given `base`.
+    ///
+    /// Bucket `k` contains spans `s` such that `lower < s <= upper`,
+    /// except bucket `0` that also contains zero span.
+    /// See [`TimeSpan::log2_bucket`].
+    /// Bounds saturate at [`TimeSpan::MAX`].
+    #[inline]
+    pub const fn bucket_bounds(base: TimeSpan, k: u32) -> (TimeSpan, TimeSpan) {
+        const fn shifted(base: TimeSpan, k: u32) -> TimeSpan {
+            let nanos = (base.nanos as u128) << if k > 64 { 64 } else { k };
+            if nanos > u64::MAX as u128 {
+                TimeSpan::MAX
+            } else {
+                TimeSpan {
+                    nanos: nanos as u64,
+                }
+            }
+        }
+
+        match k {
+            0 => (TimeSpan::ZERO, base),
+            k => (shifted(base, k - 1), shifted(base, k)),
+        }
+    }
+
+    /// Returns remainder of dividing span by `value`.
+    /// Returns `None` if `value` is zero.
     #[inline(always)]
     pub const fn checked_rem(self, value: u64) -> Option<TimeSpan> {
         match self.nanos.checked_rem(value) {
@@ -778,12 +1785,25 @@ impl TimeSpan {
         }
     }
 
+    /// Returns remainder of dividing span by `value`.
+    ///
+    /// Spans are never negative, so truncated remainder is the same as Euclidean one,
+    /// like `u64::rem_euclid`. Result is always in `[0, value)`
+    /// and `self == self.div(value) * value + self.rem(value)` holds.
+    /// The same applies to [`TimeSpan::rem_span`] and `%` operators.
+    ///
+    /// For phase math on offsets that may be negative,
+    /// like time stamps before the grid origin,
+    /// use [`TimeStamp::floor_to`](crate::TimeStamp::floor_to)
+    /// or `rem_euclid` on [`TimeStamp::signed_diff_nanos`](crate::TimeStamp::signed_diff_nanos).
     #[inline(always)]
     pub const fn rem(self, value: NonZeroU64) -> TimeSpan {
         let nanos = self.nanos % value.get();
         TimeSpan { nanos }
     }
 
+    /// Returns remainder of dividing span by `span`, always less than `span`.
+    /// Returns `None` if `span` is zero.
     #[inline(always)]
     pub const fn checked_rem_span(self, span: TimeSpan) -> Option<TimeSpan> {
         match self.nanos.checked_rem(span.nanos) {
@@ -792,12 +1812,54 @@ impl TimeSpan {
         }
     }
 
+    /// Returns remainder of dividing span by `span`, always less than `span`.
+    /// Same as Euclidean remainder, see [`TimeSpan::rem`].
     #[inline(always)]
     pub const fn rem_span(self, span: NonZeroTimeSpan) -> TimeSpan {
         let nanos = self.nanos % span.nanos.get();
         TimeSpan { nanos }
     }
 
+    /// Returns `true` if this span is a whole multiple of `unit`.
+    /// Only zero span is a multiple of zero unit.
+    #[inline(always)]
+    pub const fn is_multiple_of(self, unit: TimeSpan) -> bool {
+        self.nanos.is_multiple_of(unit.nanos)
+    }
+
+    /// Rounds this span down to a multiple of `unit`.
+    /// Returns span unchanged if `unit` is zero.
+    #[inline(always)]
+    pub const fn align_down(self, unit: TimeSpan) -> TimeSpan {
+        if unit.nanos == 0 {
+            return self;
+        }
+        TimeSpan {
+            nanos: self.nanos - self.nanos % unit.nanos,
+        }
+    }
+
+    /// Rounds this span up to a multiple of `unit`.
+    /// Returns span unchanged if `unit` is zero.
+    ///
+    /// Saturates to the largest multiple of `unit` that fits
+    /// if rounded value doesn't fit into `TimeSpan`.
+    #[inline(always)]
+    pub const fn align_up(self, unit: TimeSpan) -> TimeSpan {
+        if unit.nanos == 0 {
+            return self;
+        }
+        match self.nanos % unit.nanos {
+            0 => self,
+            rem => match self.nanos.checked_add(unit.nanos - rem) {
+                Some(nanos) => TimeSpan { nanos },
+                None => TimeSpan {
+                    nanos: self.nanos - rem,
+                },
+            },
+        }
+    }
+
     #[inline(always)]
     pub const fn hms(hours: u64, minutes: u64, seconds: u64) -> TimeSpan {
         TimeSpan {
@@ -816,6 +1878,109 @@ impl TimeSpan {
                 + seconds * Self::SECOND.nanos,
         }
     }
+
+    /// Returns time span of given hours, minutes and seconds validated at compile time.
+    ///
+    /// Minutes and seconds must be in `0..60`, otherwise compilation fails:
+    ///
+    /// ```
+    /// # use gametime::TimeSpan;
+    /// const TIMEOUTS: [TimeSpan; 2] = [TimeSpan::const_hms::<0, 1, 30>(), TimeSpan::const_hms::<25, 0, 0>()];
+    /// assert_eq!(TIMEOUTS[0], TimeSpan::hms(0, 1, 30));
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use gametime::TimeSpan;
+    /// const TIMEOUT: TimeSpan = TimeSpan::const_hms::<0, 60, 0>();
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use gametime::TimeSpan;
+    /// let timeout = TimeSpan::const_hms::<0, 0, 75>();
+    /// ```
+    ///
+    /// Overflow fails compilation too:
+    ///
+    /// ```compile_fail
+    /// # use gametime::TimeSpan;
+    /// let timeout = TimeSpan::const_hms::<{ u64::MAX }, 0, 0>();
+    /// ```
+    #[inline(always)]
+    pub const fn const_hms<const H: u64, const M: u64, const S: u64>() -> TimeSpan {
+        const {
+            assert!(M < 60, "minutes must be in 0..60");
+            assert!(S < 60, "seconds must be in 0..60");
+
+            let Some(hours) = H.checked_mul(Self::HOUR.nanos) else {
+                panic!("time span overflow");
+            };
+            match hours.checked_add(M * Self::MINUTE.nanos + S * Self::SECOND.nanos) {
+                Some(nanos) => TimeSpan { nanos },
+                None => panic!("time span overflow"),
+            }
+        }
+    }
+
+    /// Returns time span of given milliseconds validated at compile time.
+    ///
+    /// Overflow fails compilation:
+    ///
+    /// ```compile_fail
+    /// # use gametime::TimeSpan;
+    /// let timeout = TimeSpan::const_millis::<{ u64::MAX }>();
+    /// ```
+    #[inline(always)]
+    pub const fn const_millis<const MS: u64>() -> TimeSpan {
+        const {
+            match MS.checked_mul(Self::MILLISECOND.nanos) {
+                Some(nanos) => TimeSpan { nanos },
+                None => panic!("time span overflow"),
+            }
+        }
+    }
+
+    /// Returns sum of values in given units, e.g. days and hours edited separately.
+    ///
+    /// Values may be negative as long as the sum is not.
+    /// Returns `None` if sum is negative or doesn't fit into `TimeSpan`.
+    pub fn from_components(
+        components: impl IntoIterator<Item = (TimeSpanUnit, i64)>,
+    ) -> Option<TimeSpan> {
+        let mut nanos = 0i128;
+        for (unit, value) in components {
+            nanos = nanos.checked_add(unit.span().nanos as i128 * value as i128)?;
+        }
+        TimeSpan::try_from(nanos).ok()
+    }
+}
+
+/// Unit of time span, used to build spans from components.
+/// See [`TimeSpan::from_components`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeSpanUnit {
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Milli,
+    Micro,
+    Nano,
+}
+
+impl TimeSpanUnit {
+    /// Returns time span of one unit.
+    #[inline(always)]
+    pub const fn span(self) -> TimeSpan {
+        match self {
+            TimeSpanUnit::Day => TimeSpan::DAY,
+            TimeSpanUnit::Hour => TimeSpan::HOUR,
+            TimeSpanUnit::Minute => TimeSpan::MINUTE,
+            TimeSpanUnit::Second => TimeSpan::SECOND,
+            TimeSpanUnit::Milli => TimeSpan::MILLISECOND,
+            TimeSpanUnit::Micro => TimeSpan::MICROSECOND,
+            TimeSpanUnit::Nano => TimeSpan::NANOSECOND,
+        }
+    }
 }
 
 /// An interval in between different time stamps.
@@ -850,50 +2015,50 @@ impl NonZeroTimeSpan {
     /// One nanosecond span.
     /// Minimal possible time span supported by this type.
     pub const NANOSECOND: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(1) },
+        nanos: NonZeroU64::new(1).unwrap(),
     };
 
     /// One microsecond span.
     pub const MICROSECOND: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(1_000) },
+        nanos: NonZeroU64::new(1_000).unwrap(),
     };
 
     /// One millisecond span.
     pub const MILLISECOND: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(1_000_000) },
+        nanos: NonZeroU64::new(1_000_000).unwrap(),
     };
 
     /// One second span.
     pub const SECOND: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(1_000_000_000) },
+        nanos: NonZeroU64::new(1_000_000_000).unwrap(),
     };
 
     /// One minute span.
     pub const MINUTE: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(60_000_000_000) },
+        nanos: NonZeroU64::new(60_000_000_000).unwrap(),
     };
 
     /// One hour span.
     pub const HOUR: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(3_600_000_000_000) },
+        nanos: NonZeroU64::new(3_600_000_000_000).unwrap(),
     };
 
     /// One day span.
     pub const DAY: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(86_400_000_000_000) },
+        nanos: NonZeroU64::new(86_400_000_000_000).unwrap(),
     };
 
     /// One week.
     /// Defined as 7 days.
     pub const WEEK: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(604_800_000_000_000) },
+        nanos: NonZeroU64::new(604_800_000_000_000).unwrap(),
     };
 
     /// One Julian year.
     /// Average year length in Julian calendar.
     /// Defined as 365.25 days.
     pub const JULIAN_YEAR: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(31_557_600_000_000_000) },
+        nanos: NonZeroU64::new(31_557_600_000_000_000).unwrap(),
     };
 
     /// One Gregorian year.
@@ -901,13 +2066,13 @@ impl NonZeroTimeSpan {
     /// 3 days per 400 years shorter than Julian year.
     /// Defined as 365.2425 days.
     pub const GREGORIAN_YEAR: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(31_556_952_000_000) },
+        nanos: NonZeroU64::new(31_556_952_000_000).unwrap(),
     };
 
     /// One solar year (tropical year).
     /// Defined as 365.24219 days.
     pub const SOLAR_YEAR: Self = NonZeroTimeSpan {
-        nanos: unsafe { NonZeroU64::new_unchecked(31_556_925_216_000_000) },
+        nanos: NonZeroU64::new(31_556_925_216_000_000).unwrap(),
     };
 
     /// One year.
@@ -1656,3 +2821,1040 @@ fn test_span_parse() {
         2 * TimeSpan::MINUTE + 11 * TimeSpan::SECOND + 11 * TimeSpan::MILLISECOND
     );
 }
+
+#[test]
+fn test_span_parse_units() {
+    let parse = |s: &str| s.parse::<TimeSpan>().unwrap();
+    let ms = TimeSpan::MILLISECOND;
+
+    assert_eq!(parse("1 S"), TimeSpan::SECOND);
+    assert_eq!(parse("1s"), TimeSpan::SECOND);
+    assert_eq!(parse(" 1 s "), TimeSpan::SECOND);
+    assert_eq!(parse("500 MS"), 500 * ms);
+    assert_eq!(parse("500Ms"), 500 * ms);
+    assert_eq!(parse("500 mS"), 500 * ms);
+    assert_eq!(parse("250 US"), 250 * TimeSpan::MICROSECOND);
+    assert_eq!(parse("5ns"), TimeSpan::new(5));
+    assert_eq!(parse("5 Ns"), TimeSpan::new(5));
+
+    // Fraction with unit.
+    assert_eq!(parse("1.5s"), 1500 * ms);
+    assert_eq!(parse("1.5 MS"), TimeSpan::new(1_500_000));
+    assert_eq!(parse("16.700ms"), 16 * ms + 700 * TimeSpan::MICROSECOND);
+    assert_eq!(parse("0.0000000009ms"), TimeSpan::ZERO);
+    assert_eq!(parse("1.9ns"), TimeSpan::new(1));
+
+    // Whitespace around fraction.
+    assert_eq!(parse("1.5 "), 1500 * ms);
+    assert_eq!(parse("1. 5"), 1500 * ms);
+    assert_eq!(parse("1:00.5 "), TimeSpan::MINUTE + 500 * ms);
+
+    // Display output is parsed back.
+    for span in [
+        TimeSpan::new(5),
+        3 * TimeSpan::MICROSECOND,
+        16 * ms + 700 * TimeSpan::MICROSECOND,
+        1500 * ms,
+        TimeSpan::HOUR + 2 * TimeSpan::MINUTE,
+        TimeSpan::DAY + ms,
+    ] {
+        assert_eq!(parse(&span.to_string()), span);
+    }
+
+    assert!(matches!(
+        "1 sec".parse::<TimeSpan>(),
+        Err(TimeSpanParseErr::UnexpectedSuffix { .. })
+    ));
+    assert!(matches!(
+        "1 x".parse::<TimeSpan>(),
+        Err(TimeSpanParseErr::UnexpectedSuffix { .. })
+    ));
+    assert!("1.s".parse::<TimeSpan>().is_err());
+    assert!("1.5.5s".parse::<TimeSpan>().is_err());
+    assert!("1:30s".parse::<TimeSpan>().is_err());
+    assert!("s".parse::<TimeSpan>().is_err());
+}
+
+#[test]
+fn test_span_parse_nanos() {
+    assert_eq!(
+        "1.0000001".parse::<TimeSpan>().unwrap(),
+        TimeSpan::SECOND + 100 * TimeSpan::NANOSECOND
+    );
+    assert_eq!(
+        "1:00.123456789".parse::<TimeSpan>().unwrap(),
+        TimeSpan::MINUTE + TimeSpan::new(123_456_789)
+    );
+    assert_eq!(
+        "0.1234567891".parse::<TimeSpan>().unwrap(),
+        TimeSpan::new(123_456_789)
+    );
+
+    let span = TimeSpan::DAY + 3 * TimeSpan::HOUR + TimeSpan::new(1_000_000_001);
+    assert_eq!(format!("{:#}", span).parse::<TimeSpan>().unwrap(), span);
+}
+
+#[test]
+fn test_span_parse_go() {
+    let parse = TimeSpan::parse_go;
+
+    assert_eq!(parse("300ms").unwrap(), TimeSpan::MILLISECOND * 300);
+    assert_eq!(parse("1.5h").unwrap(), TimeSpan::MINUTE * 90);
+    assert_eq!(
+        parse("2h45m").unwrap(),
+        TimeSpan::HOUR * 2 + TimeSpan::MINUTE * 45
+    );
+    assert_eq!(
+        parse("2h45m30s").unwrap(),
+        TimeSpan::HOUR * 2 + TimeSpan::MINUTE * 45 + TimeSpan::SECOND * 30
+    );
+    assert_eq!(
+        parse("1h15m30.918273645s").unwrap(),
+        TimeSpan::HOUR + TimeSpan::MINUTE * 15 + TimeSpan::new(30_918_273_645)
+    );
+    assert_eq!(parse("1h30m").unwrap(), TimeSpan::MINUTE * 90);
+    assert_eq!(parse("0").unwrap(), TimeSpan::ZERO);
+    assert_eq!(parse("-0").unwrap(), TimeSpan::ZERO);
+    assert_eq!(parse("+5s").unwrap(), TimeSpan::SECOND * 5);
+    assert_eq!(parse(".5s").unwrap(), TimeSpan::MILLISECOND * 500);
+    assert_eq!(parse("1.s").unwrap(), TimeSpan::SECOND);
+    assert_eq!(parse("1ns").unwrap(), TimeSpan::NANOSECOND);
+    assert_eq!(parse("1.5us").unwrap(), TimeSpan::new(1500));
+    assert_eq!(parse("2\u{b5}s").unwrap(), TimeSpan::new(2000));
+    assert_eq!(parse("2\u{3bc}s").unwrap(), TimeSpan::new(2000));
+    assert_eq!(parse("0.1234567891s").unwrap(), TimeSpan::new(123_456_789));
+    assert_eq!(parse("-0s").unwrap(), TimeSpan::ZERO);
+
+    assert!(matches!(
+        parse(""),
+        Err(TimeSpanParseErr::UnexpectedEndOfString { pos: 0 })
+    ));
+    assert!(matches!(
+        parse("3"),
+        Err(TimeSpanParseErr::UnexpectedEndOfString { pos: 1 })
+    ));
+    assert!(matches!(
+        parse("-1.5h"),
+        Err(TimeSpanParseErr::Negative { .. })
+    ));
+    assert_eq!(
+        parse("1d"),
+        Err(TimeSpanParseErr::UnknownUnit { span: 1..2 })
+    );
+    assert!(matches!(
+        parse("1h.m"),
+        Err(TimeSpanParseErr::UnexpectedDelimiter { delim: '.', pos: 2 })
+    ));
+    assert!(matches!(
+        parse("s"),
+        Err(TimeSpanParseErr::UnexpectedDelimiter { delim: 's', pos: 0 })
+    ));
+    assert!(matches!(
+        parse("9999999999999h"),
+        Err(TimeSpanParseErr::Overflow { .. })
+    ));
+}
+
+#[test]
+fn test_span_parse_lenient() {
+    assert_eq!(
+        TimeSpan::parse_lenient("1,5").unwrap(),
+        TimeSpan::SECOND + 500 * TimeSpan::MILLISECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1:30,25").unwrap(),
+        TimeSpan::MINUTE + 30 * TimeSpan::SECOND + 250 * TimeSpan::MILLISECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1 000 ms").unwrap(),
+        TimeSpan::SECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1\u{a0}000 ms").unwrap(),
+        TimeSpan::SECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1\u{202f}000ms").unwrap(),
+        TimeSpan::SECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1_000_000us").unwrap(),
+        TimeSpan::SECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient(" 2:11.011 ").unwrap(),
+        "2:11.011".parse().unwrap()
+    );
+
+    // Comma followed by exactly three digits is digit grouping.
+    assert_eq!(
+        TimeSpan::parse_lenient("1,500").unwrap(),
+        1500 * TimeSpan::SECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1,000,000us").unwrap(),
+        TimeSpan::SECOND
+    );
+    assert_eq!(
+        TimeSpan::parse_lenient("1,5000").unwrap(),
+        TimeSpan::SECOND + 500 * TimeSpan::MILLISECOND
+    );
+
+    assert!(TimeSpan::parse_lenient("1,5,0").is_err());
+    assert!(TimeSpan::parse_lenient("1.5,0").is_err());
+    assert!(TimeSpan::parse_lenient("1  000").is_err());
+    assert!("1,5".parse::<TimeSpan>().is_err());
+}
+
+#[test]
+fn test_span_parse_error_spans() {
+    // Returns substring error points at.
+    fn at(s: &str, err: TimeSpanParseErr) -> &str {
+        &s[err.span().unwrap()]
+    }
+    fn strict(s: &str) -> TimeSpanParseErr {
+        s.parse::<TimeSpan>().unwrap_err()
+    }
+
+    let s = "1:2\u{b5}";
+    assert!(matches!(strict(s), TimeSpanParseErr::NonASCII { .. }));
+    assert_eq!(at(s, strict(s)), "\u{b5}");
+
+    let s = "1".repeat(50);
+    assert!(matches!(
+        strict(&s),
+        TimeSpanParseErr::StringTooLarge { len: 50 }
+    ));
+    assert_eq!(at(&s, strict(&s)).len(), 2);
+
+    let s = "1: 99999999999999999999 ";
+    assert!(matches!(strict(s), TimeSpanParseErr::IntParseError { .. }));
+    assert_eq!(at(s, strict(s)), "99999999999999999999");
+
+    let s = "1:2:3:4";
+    assert_eq!(
+        strict(s),
+        TimeSpanParseErr::UnexpectedDelimiter { delim: ':', pos: 5 }
+    );
+    assert_eq!(at(s, strict(s)), ":");
+
+    let s = "1d";
+    assert_eq!(
+        strict(s),
+        TimeSpanParseErr::UnexpectedEndOfString { pos: 2 }
+    );
+    assert_eq!(at(s, strict(s)), "");
+
+    let s = "10 sec";
+    assert!(matches!(
+        strict(s),
+        TimeSpanParseErr::UnexpectedSuffix { .. }
+    ));
+    assert_eq!(at(s, strict(s)), "sec");
+
+    let s = "1d 24:00";
+    assert!(matches!(
+        strict(s),
+        TimeSpanParseErr::HoursOutOfBound { hours: 24, .. }
+    ));
+    assert_eq!(at(s, strict(s)), "24");
+
+    let s = "1:60:00";
+    assert!(matches!(
+        strict(s),
+        TimeSpanParseErr::MinutesOutOfBound { minutes: 60, .. }
+    ));
+    assert_eq!(at(s, strict(s)), "60");
+
+    let s = "1:75";
+    assert!(matches!(
+        strict(s),
+        TimeSpanParseErr::SecondsOutOfBound { seconds: 75, .. }
+    ));
+    assert_eq!(at(s, strict(s)), "75");
+
+    let s = " 18446744073709551615 ms";
+    assert!(matches!(strict(s), TimeSpanParseErr::Overflow { .. }));
+    assert_eq!(at(s, strict(s)), "18446744073709551615");
+
+    let s = "999999999999d00:00";
+    assert_eq!(at(s, strict(s)), s);
+
+    let go = |s: &str| TimeSpan::parse_go(s).unwrap_err();
+
+    let s = "2h5x";
+    assert_eq!(at(s, go(s)), "x");
+    let s = "1h9999999999999m";
+    assert_eq!(at(s, go(s)), "9999999999999m");
+    let s = "-1s";
+    assert!(matches!(go(s), TimeSpanParseErr::Negative { .. }));
+    assert_eq!(at(s, go(s)), "-");
+
+    // Lenient parser reports positions in the original input.
+    let lenient = |s: &str| TimeSpan::parse_lenient(s).unwrap_err();
+
+    let s = "  1 000:75";
+    assert_eq!(at(s, lenient(s)), "75");
+    let s = " 1_000 sec";
+    assert_eq!(at(s, lenient(s)), "sec");
+    let s = "1,5,0";
+    assert_eq!(
+        lenient(s),
+        TimeSpanParseErr::UnexpectedDelimiter { delim: ',', pos: 1 }
+    );
+    let s = " 1:2\u{2009}x";
+    assert_eq!(at(s, lenient(s)), "\u{2009}");
+    let s = " 1d";
+    assert_eq!(at(s, lenient(s)), "");
+    assert_eq!(lenient(s).span(), Some(3..3));
+}
+
+#[test]
+fn test_span_parse_overflow() {
+    assert!(matches!(
+        "18446744073709551615s".parse::<TimeSpan>(),
+        Err(TimeSpanParseErr::Overflow { .. })
+    ));
+    assert!(matches!(
+        "999999999999d00:00".parse::<TimeSpan>(),
+        Err(TimeSpanParseErr::Overflow { .. })
+    ));
+    assert_eq!(
+        "0.000000000000000000000000001".parse::<TimeSpan>().unwrap(),
+        TimeSpan::ZERO
+    );
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_span_parse_never_panics(s in "[0-9 ,._:dDtTmshnu+\\-\u{a0}\u{202f}\u{b5}]{0,64}") {
+        let _ = s.parse::<TimeSpan>();
+        let _ = TimeSpan::parse_lenient(&s);
+        let _ = TimeSpan::parse_go(&s);
+    }
+
+    #[test]
+    fn test_span_parse_lenient_arbitrary(s in "\\PC*") {
+        let _ = TimeSpan::parse_lenient(&s);
+        let _ = TimeSpan::parse_go(&s);
+    }
+}
+
+#[test]
+fn test_span_saturating() {
+    assert_eq!(
+        TimeSpan::MAX.saturating_add(TimeSpan::SECOND),
+        TimeSpan::MAX
+    );
+    assert_eq!(
+        TimeSpan::SECOND.saturating_sub(TimeSpan::MINUTE),
+        TimeSpan::ZERO
+    );
+    assert_eq!(
+        TimeSpan::MINUTE.saturating_sub(TimeSpan::SECOND),
+        59 * TimeSpan::SECOND
+    );
+}
+
+#[cfg(feature = "num-traits")]
+#[test]
+fn test_span_num_traits() {
+    use num_traits::{Bounded, SaturatingAdd, Zero};
+
+    fn sum<T: Zero + Copy>(values: &[T]) -> T {
+        values.iter().fold(T::zero(), |acc, &v| acc + v)
+    }
+
+    fn saturating_sum<T: Zero + Bounded + SaturatingAdd>(values: &[T]) -> T {
+        values
+            .iter()
+            .fold(T::zero(), |acc, v| acc.saturating_add(v))
+    }
+
+    assert_eq!(TimeSpan::zero(), TimeSpan::ZERO);
+    assert!(TimeSpan::ZERO.is_zero());
+    assert!(!TimeSpan::NANOSECOND.is_zero());
+    assert_eq!(<TimeSpan as Bounded>::min_value(), TimeSpan::MIN);
+    assert_eq!(<TimeSpan as Bounded>::max_value(), TimeSpan::MAX);
+
+    assert_eq!(
+        sum(&[TimeSpan::SECOND, TimeSpan::MINUTE, TimeSpan::HOUR]),
+        TimeSpan::HOUR + TimeSpan::MINUTE + TimeSpan::SECOND
+    );
+    assert_eq!(sum::<TimeSpan>(&[]), TimeSpan::ZERO);
+    assert_eq!(
+        saturating_sum(&[TimeSpan::MAX, TimeSpan::SECOND]),
+        TimeSpan::MAX
+    );
+}
+
+#[test]
+fn test_span_int_conversions() {
+    assert_eq!(TimeSpan::from(0u64), TimeSpan::ZERO);
+    assert_eq!(TimeSpan::from(u64::MAX), TimeSpan::MAX);
+    assert_eq!(u64::from(TimeSpan::MAX), u64::MAX);
+    assert_eq!(u128::from(TimeSpan::MAX), u64::MAX as u128);
+    assert_eq!(i128::from(TimeSpan::MAX), u64::MAX as i128);
+    assert_eq!(i128::from(TimeSpan::ZERO), 0);
+
+    assert_eq!(i64::try_from(TimeSpan::ZERO), Ok(0));
+    assert_eq!(i64::try_from(TimeSpan::new(i64::MAX as u64)), Ok(i64::MAX));
+    assert!(i64::try_from(TimeSpan::new(i64::MAX as u64 + 1)).is_err());
+    assert!(i64::try_from(TimeSpan::MAX).is_err());
+
+    assert_eq!(TimeSpan::try_from(0i64), Ok(TimeSpan::ZERO));
+    assert_eq!(
+        TimeSpan::try_from(i64::MAX),
+        Ok(TimeSpan::new(i64::MAX as u64))
+    );
+    assert!(TimeSpan::try_from(-1i64).is_err());
+    assert!(TimeSpan::try_from(i64::MIN).is_err());
+
+    assert_eq!(TimeSpan::try_from(u64::MAX as i128), Ok(TimeSpan::MAX));
+    assert!(TimeSpan::try_from(u64::MAX as i128 + 1).is_err());
+    assert!(TimeSpan::try_from(-1i128).is_err());
+
+    assert_eq!(TimeSpan::try_from(u64::MAX as u128), Ok(TimeSpan::MAX));
+    assert!(TimeSpan::try_from(u64::MAX as u128 + 1).is_err());
+}
+
+#[test]
+fn test_span_log2_bucket() {
+    let base = TimeSpan::MILLISECOND;
+
+    assert_eq!(TimeSpan::ZERO.log2_bucket(base), 0);
+    assert_eq!(base.log2_bucket(base), 0);
+    assert_eq!((base + TimeSpan::NANOSECOND).log2_bucket(base), 1);
+    assert_eq!((2 * base).log2_bucket(base), 1);
+    assert_eq!((3 * base).log2_bucket(base), 2);
+    assert_eq!((4 * base).log2_bucket(base), 2);
+    assert_eq!((5 * base).log2_bucket(base), 3);
+    assert_eq!(TimeSpan::SECOND.log2_bucket(base), 10);
+    assert_eq!(TimeSpan::MAX.log2_bucket(TimeSpan::NANOSECOND), 64);
+    assert_eq!(TimeSpan::MAX.log2_bucket(TimeSpan::MAX), 0);
+    assert_eq!(TimeSpan::ZERO.log2_bucket(TimeSpan::ZERO), 0);
+    assert_eq!(TimeSpan::SECOND.log2_bucket(TimeSpan::ZERO), 64);
+
+    assert_eq!(TimeSpan::bucket_bounds(base, 0), (TimeSpan::ZERO, base));
+    assert_eq!(TimeSpan::bucket_bounds(base, 3), (4 * base, 8 * base));
+    assert_eq!(
+        TimeSpan::bucket_bounds(TimeSpan::NANOSECOND, 64),
+        (TimeSpan::new(1 << 63), TimeSpan::MAX)
+    );
+    assert_eq!(
+        TimeSpan::bucket_bounds(base, 100),
+        (TimeSpan::MAX, TimeSpan::MAX)
+    );
+
+    for span in [3 * base, 5 * base, TimeSpan::SECOND, TimeSpan::new(12345)] {
+        let (lower, upper) = TimeSpan::bucket_bounds(base, span.log2_bucket(base));
+        assert!(lower < span && span <= upper);
+    }
+}
+
+#[test]
+fn test_span_exp_fmt() {
+    let ms = TimeSpan::MILLISECOND * 16;
+    assert_eq!(format!("{:e}", ms), "1.6e-2s");
+    assert_eq!(format!("{:E}", ms), "1.6E-2s");
+    assert_eq!(format!("{:.3e}", ms), "1.600e-2s");
+
+    let days = TimeSpan::DAY * 3;
+    assert_eq!(format!("{:e}", days), "2.592e5s");
+    assert_eq!(format!("{:E}", days), "2.592E5s");
+
+    assert_eq!(format!("{:e}", TimeSpan::ZERO), "0e0s");
+    assert_eq!(format!("{:e}", TimeSpan::NANOSECOND), "1e-9s");
+}
+
+#[test]
+fn test_span_display_relative() {
+    let cases = [
+        (TimeSpan::ZERO, "just now"),
+        (TimeSpan::MILLISECOND * 4999, "just now"),
+        (TimeSpan::SECOND * 5, "5 seconds ago"),
+        (TimeSpan::SECOND * 45, "45 seconds ago"),
+        (TimeSpan::SECOND * 59, "59 seconds ago"),
+        (TimeSpan::MILLISECOND * 59_499, "59 seconds ago"),
+        (TimeSpan::MILLISECOND * 59_500, "1 minute ago"),
+        (TimeSpan::SECOND * 60, "1 minute ago"),
+        (TimeSpan::SECOND * 89, "1 minute ago"),
+        (TimeSpan::SECOND * 90, "2 minutes ago"),
+        (TimeSpan::MINUTE * 59, "59 minutes ago"),
+        (TimeSpan::MINUTE * 60, "1 hour ago"),
+        (TimeSpan::MINUTE * 89, "1 hour ago"),
+        (TimeSpan::MINUTE * 90, "2 hours ago"),
+        (TimeSpan::MINUTE * 156, "3 hours ago"),
+        (TimeSpan::HOUR * 23, "23 hours ago"),
+        (TimeSpan::MINUTE * (23 * 60 + 30), "1 day ago"),
+        (TimeSpan::HOUR * 36, "2 days ago"),
+        (TimeSpan::DAY * 5, "5 days ago"),
+        (TimeSpan::DAY * 400, "400 days ago"),
+    ];
+
+    for (span, expected) in cases {
+        assert_eq!(span.display_relative().to_string(), expected, "{:?}", span);
+    }
+
+    let truncate = RelativeFormat {
+        just_now: TimeSpan::SECOND,
+        truncate: true,
+    };
+    assert_eq!(
+        truncate.display(TimeSpan::SECOND * 3, false).to_string(),
+        "3 seconds ago"
+    );
+    assert_eq!(
+        truncate.display(TimeSpan::MINUTE * 119, true).to_string(),
+        "in 1 hour"
+    );
+    assert_eq!(
+        truncate
+            .display(TimeSpan::MILLISECOND * 59_999, true)
+            .to_string(),
+        "in 59 seconds"
+    );
+
+    let now = TimeStamp::start() + TimeSpan::DAY;
+    assert_eq!(
+        TimeSpan::display_relative_to(
+            now,
+            TimeStamp::start() + (TimeSpan::DAY - TimeSpan::MINUTE * 3)
+        )
+        .to_string(),
+        "3 minutes ago"
+    );
+    assert_eq!(
+        TimeSpan::display_relative_to(now, now + TimeSpan::HOUR * 2).to_string(),
+        "in 2 hours"
+    );
+    assert_eq!(
+        TimeSpan::display_relative_to(now, now).to_string(),
+        "just now"
+    );
+}
+
+#[test]
+fn test_span_display_to_buffer() {
+    let mut buf = [0; TimeSpan::MAX_DISPLAY_LENGTH];
+    let mut full = [0; TimeSpan::MAX_FULL_DISPLAY_LENGTH];
+
+    // Worst case is the largest number of days with milliseconds.
+    let worst = TimeSpan::MAX;
+    assert_eq!(worst.display_to_buffer(&mut buf), worst.to_string());
+    assert_eq!(worst.to_string().len(), TimeSpan::MAX_DISPLAY_LENGTH);
+    assert_eq!(
+        worst.display_full_to_buffer(&mut full),
+        format!("{:#}", worst)
+    );
+    assert_eq!(
+        format!("{:#}", worst).len(),
+        TimeSpan::MAX_FULL_DISPLAY_LENGTH
+    );
+
+    let mut nanos = 1u64;
+    while let Some(next) = nanos.checked_mul(7) {
+        for span in [TimeSpan::new(nanos), TimeSpan::new(nanos - 1)] {
+            assert_eq!(span.display_to_buffer(&mut buf), span.to_string());
+            assert_eq!(
+                span.display_full_to_buffer(&mut full),
+                format!("{:#}", span)
+            );
+        }
+        nanos = next;
+    }
+}
+
+#[test]
+fn test_span_const_constructors() {
+    const HMS: TimeSpan = TimeSpan::const_hms::<1, 2, 3>();
+    assert_eq!(HMS, TimeSpan::hms(1, 2, 3));
+    assert_eq!(TimeSpan::const_hms::<0, 59, 59>(), TimeSpan::hms(0, 59, 59));
+    assert_eq!(
+        TimeSpan::const_hms::<48, 0, 0>(),
+        TimeSpan::dhms(2, 0, 0, 0)
+    );
+    assert_eq!(
+        TimeSpan::const_millis::<1500>(),
+        TimeSpan::MILLISECOND * 1500
+    );
+    assert_eq!(
+        TimeSpan::const_millis::<{ u64::MAX / 1_000_000 }>(),
+        TimeSpan::MILLISECOND * (u64::MAX / 1_000_000)
+    );
+}
+
+#[test]
+fn test_span_div_rem_non_zero() {
+    let spans = [
+        TimeSpan::ZERO,
+        TimeSpan::NANOSECOND,
+        TimeSpan::SECOND + TimeSpan::new(7),
+        TimeSpan::MAX,
+    ];
+
+    for span in spans {
+        for divisor in [1, 3, 1000, u64::MAX] {
+            let non_zero = NonZeroU64::new(divisor).unwrap();
+            assert_eq!(span / non_zero, span / divisor);
+            assert_eq!(span % non_zero, span % divisor);
+
+            let mut div = span;
+            div /= non_zero;
+            assert_eq!(div, span / divisor);
+
+            let mut rem = span;
+            rem %= non_zero;
+            assert_eq!(rem, span % divisor);
+        }
+    }
+}
+
+#[test]
+fn test_span_div_rem_euclid() {
+    let values = [0, 1, 2, 5, 999, 1_000_000_007, u64::MAX - 1, u64::MAX];
+    for nanos in values {
+        let span = TimeSpan::new(nanos);
+        for divisor in values.into_iter().filter(|&d| d != 0) {
+            let value = NonZeroU64::new(divisor).unwrap();
+            let non_zero = NonZeroTimeSpan::new(value);
+            let rem = span.rem_span(non_zero);
+            assert_eq!(rem.as_nanos(), nanos.rem_euclid(divisor));
+            assert_eq!(span.div_span(non_zero), nanos.div_euclid(divisor));
+            assert_eq!(span % TimeSpan::new(divisor), rem);
+            assert!(rem < TimeSpan::new(divisor));
+
+            assert_eq!(span.rem(value).as_nanos(), nanos.rem_euclid(divisor));
+            assert_eq!(span.div(value).as_nanos(), nanos.div_euclid(divisor));
+            assert_eq!(span.div(value) * divisor + span.rem(value), span);
+        }
+        assert_eq!(span.checked_rem(0), None);
+        assert_eq!(span.checked_rem_span(TimeSpan::ZERO), None);
+    }
+}
+
+#[test]
+fn test_span_over_budget() {
+    let budget = TimeSpan::MILLISECOND * 16;
+    assert_eq!(TimeSpan::MILLISECOND.over_budget(budget), None);
+    assert_eq!(budget.over_budget(budget), None);
+    assert_eq!(
+        (TimeSpan::MILLISECOND * 20).over_budget(budget),
+        Some(TimeSpan::MILLISECOND * 4)
+    );
+}
+
+#[test]
+fn test_span_bounds() {
+    assert_eq!(TimeSpan::MIN, TimeSpan::ZERO);
+    assert_eq!(TimeSpan::MAX.as_nanos(), u64::MAX);
+    assert_eq!(TimeSpan::MAX.checked_add(TimeSpan::NANOSECOND), None);
+    assert_eq!(TimeSpan::MIN.checked_sub(TimeSpan::NANOSECOND), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_span_deserialize_json() {
+    let parse = |s: &str| serde_json::from_str::<TimeSpan>(s);
+
+    assert_eq!(
+        parse("\"1:30\"").unwrap(),
+        TimeSpan::MINUTE + 30 * TimeSpan::SECOND
+    );
+    assert_eq!(parse("2").unwrap(), 2 * TimeSpan::SECOND);
+    assert_eq!(parse("2.5").unwrap(), 2500 * TimeSpan::MILLISECOND);
+    assert_eq!(parse("0.000000001").unwrap(), TimeSpan::NANOSECOND);
+    assert_eq!(
+        parse(r#"{"secs": 3, "nanos": 500}"#).unwrap(),
+        3 * TimeSpan::SECOND + 500 * TimeSpan::NANOSECOND
+    );
+    assert_eq!(
+        parse(&serde_json::to_string(&Duration::new(7, 42)).unwrap()).unwrap(),
+        TimeSpan::from(Duration::new(7, 42))
+    );
+
+    assert!(parse("-1").is_err());
+    assert!(parse("-0.5").is_err());
+    assert!(parse("1e30").is_err());
+    assert!(parse(r#"{"secs": 3}"#).is_err());
+    assert!(parse(r#"{"secs": 3, "nanos": 0, "extra": 1}"#).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_span_serde_round_trip() {
+    let spans = [
+        TimeSpan::ZERO,
+        TimeSpan::NANOSECOND,
+        TimeSpan::SECOND + 100 * TimeSpan::NANOSECOND,
+        1500 * TimeSpan::MILLISECOND,
+        TimeSpan::DAY + TimeSpan::HOUR + TimeSpan::new(123_456_789),
+        TimeSpan::MAX,
+    ];
+
+    for span in spans {
+        let json = serde_json::to_string(&span).unwrap();
+        assert_eq!(serde_json::from_str::<TimeSpan>(&json).unwrap(), span);
+
+        let yaml = serde_yaml::to_string(&span).unwrap();
+        assert_eq!(serde_yaml::from_str::<TimeSpan>(&yaml).unwrap(), span);
+    }
+
+    assert_eq!(
+        serde_json::to_string(&(TimeSpan::SECOND + TimeSpan::NANOSECOND)).unwrap(),
+        "\"0d00:00:01.000000001\""
+    );
+
+    // Strings produced by earlier versions still deserialize.
+    for (old, span) in [
+        ("0", TimeSpan::ZERO),
+        ("1.500s", 1500 * TimeSpan::MILLISECOND),
+        ("2.250ms", TimeSpan::new(2_250_000)),
+        ("1:30.001", 90 * TimeSpan::SECOND + TimeSpan::MILLISECOND),
+        ("1d01:00", 25 * TimeSpan::HOUR),
+    ] {
+        let json = format!("\"{}\"", old);
+        assert_eq!(serde_json::from_str::<TimeSpan>(&json).unwrap(), span);
+        assert_eq!(format!("{}", span), old);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+proptest::proptest! {
+    #[test]
+    fn test_span_serde_lossless(nanos in proptest::prelude::any::<u64>()) {
+        let span = TimeSpan::new(nanos);
+
+        let json = serde_json::to_string(&span).unwrap();
+        proptest::prop_assert_eq!(serde_json::from_str::<TimeSpan>(&json).unwrap(), span);
+
+        let yaml = serde_yaml::to_string(&span).unwrap();
+        proptest::prop_assert_eq!(serde_yaml::from_str::<TimeSpan>(&yaml).unwrap(), span);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_span_serde_bounds() {
+    for span in [
+        TimeSpan::MIN,
+        TimeSpan::MAX,
+        TimeSpan::MAX - TimeSpan::NANOSECOND,
+    ] {
+        let json = serde_json::to_string(&span).unwrap();
+        assert_eq!(serde_json::from_str::<TimeSpan>(&json).unwrap(), span);
+    }
+
+    assert_eq!(
+        serde_json::to_string(&TimeSpan::MAX).unwrap(),
+        "\"213503d23:34:33.709551615\""
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_span_deserialize_yaml() {
+    #[derive(serde::Deserialize)]
+    struct Config {
+        timeout: TimeSpan,
+    }
+
+    let parse = |s: &str| serde_yaml::from_str::<Config>(s).map(|config| config.timeout);
+
+    assert_eq!(parse("timeout: 2.5").unwrap(), 2500 * TimeSpan::MILLISECOND);
+    assert_eq!(parse("timeout: 10").unwrap(), 10 * TimeSpan::SECOND);
+    assert_eq!(parse("timeout: 1:00:00").unwrap(), TimeSpan::HOUR);
+    assert_eq!(
+        parse("timeout: 500ms").unwrap(),
+        500 * TimeSpan::MILLISECOND
+    );
+    assert_eq!(
+        parse("timeout: { secs: 1, nanos: 1 }").unwrap(),
+        TimeSpan::SECOND + TimeSpan::NANOSECOND
+    );
+    assert!(parse("timeout: .nan").is_err());
+    assert!(parse("timeout: .inf").is_err());
+}
+
+#[test]
+fn test_display_time_of_day() {
+    let fmt = |span: TimeSpan, twelve_hour| span.display_time_of_day(twelve_hour).to_string();
+
+    assert_eq!(fmt(TimeSpan::hms(13, 0, 0), false), "13:00");
+    assert_eq!(fmt(TimeSpan::hms(13, 0, 0), true), "1:00 PM");
+    assert_eq!(fmt(TimeSpan::ZERO, false), "00:00");
+    assert_eq!(fmt(TimeSpan::ZERO, true), "12:00 AM");
+    assert_eq!(fmt(TimeSpan::hms(12, 0, 0), false), "12:00");
+    assert_eq!(fmt(TimeSpan::hms(12, 0, 0), true), "12:00 PM");
+    assert_eq!(fmt(TimeSpan::hms(0, 59, 59), true), "12:59 AM");
+    assert_eq!(fmt(TimeSpan::hms(11, 59, 59), true), "11:59 AM");
+    assert_eq!(fmt(TimeSpan::hms(23, 59, 59), false), "23:59");
+    assert_eq!(fmt(TimeSpan::hms(23, 59, 59), true), "11:59 PM");
+    assert_eq!(fmt(TimeSpan::hms(7, 5, 0), false), "07:05");
+    assert_eq!(fmt(TimeSpan::DAY, false), "00:00");
+    assert_eq!(fmt(TimeSpan::dhms(3, 18, 30, 0), true), "6:30 PM");
+}
+
+#[test]
+fn test_display_as_unit() {
+    let frame = TimeSpan::new(16_666_667);
+
+    assert_eq!(frame.display_as_millis(1).to_string(), "16.7ms");
+    assert_eq!(frame.display_as_millis(0).to_string(), "17ms");
+    assert_eq!(frame.display_as_millis(3).to_string(), "16.667ms");
+    assert_eq!(frame.display_as_seconds(3).to_string(), "0.017s");
+    assert_eq!(frame.display_as_micros(3).to_string(), "16666.667us");
+    assert_eq!(frame.display_as_micros(5).to_string(), "16666.66700us");
+    assert_eq!(frame.display_auto().to_string(), frame.to_string());
+
+    assert_eq!(
+        TimeSpan::new(999_900_000).display_as_seconds(3).to_string(),
+        "1.000s"
+    );
+    assert_eq!(
+        TimeSpan::new(999_400_000).display_as_seconds(3).to_string(),
+        "0.999s"
+    );
+    assert_eq!(
+        TimeSpan::new(9_999_500).display_as_millis(3).to_string(),
+        "10.000ms"
+    );
+    assert_eq!(TimeSpan::new(500).display_as_micros(0).to_string(), "1us");
+    assert_eq!(TimeSpan::new(499).display_as_micros(0).to_string(), "0us");
+    assert_eq!(TimeSpan::ZERO.display_as_millis(2).to_string(), "0.00ms");
+    assert_eq!(
+        TimeSpan::HOUR.display_as_seconds(12).to_string(),
+        "3600.000000000000s"
+    );
+    assert_eq!(
+        TimeSpan::MAX.display_as_seconds(9).to_string(),
+        "18446744073.709551615s"
+    );
+}
+
+#[test]
+fn test_mul_add() {
+    let period = TimeSpan::new(u64::MAX / 2 + 1);
+
+    // `period * 2` doesn't fit, `period` doesn't fit into `i64`.
+    assert_eq!(period.checked_mul(2), None);
+    assert_eq!(
+        period.checked_mul_add(-1, TimeSpan::MAX),
+        Some(TimeSpan::new(u64::MAX / 2))
+    );
+    assert_eq!(period.checked_mul_add(-2, TimeSpan::MAX), None);
+    assert_eq!(
+        period.mul_add(-1, TimeSpan::MAX - TimeSpan::new(u64::MAX / 2)),
+        TimeSpan::ZERO
+    );
+    assert_eq!(
+        TimeSpan::MAX
+            .mul_add(-1, TimeSpan::MAX)
+            .checked_add(TimeSpan::MAX),
+        Some(TimeSpan::MAX)
+    );
+
+    assert_eq!(
+        TimeSpan::SECOND.mul_add(3, TimeSpan::MILLISECOND),
+        TimeSpan::new(3_001_000_000)
+    );
+    assert_eq!(
+        TimeSpan::SECOND.checked_mul_add(-3, TimeSpan::MINUTE),
+        Some(TimeSpan::SECOND * 57)
+    );
+    assert_eq!(TimeSpan::SECOND.checked_mul_add(-3, TimeSpan::SECOND), None);
+    assert_eq!(TimeSpan::MAX.checked_mul_add(1, TimeSpan::NANOSECOND), None);
+    assert_eq!(TimeSpan::MAX.checked_mul_add(i64::MIN, TimeSpan::MAX), None);
+    assert_eq!(
+        TimeSpan::MAX.checked_mul_add(0, TimeSpan::MAX),
+        Some(TimeSpan::MAX)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_span_varint() {
+    let mut buf = Vec::new();
+    for span in [
+        TimeSpan::ZERO,
+        TimeSpan::new(127),
+        TimeSpan::new(128),
+        TimeSpan::MILLISECOND * 16,
+        TimeSpan::DAY,
+        TimeSpan::MAX,
+    ] {
+        buf.clear();
+        span.encode_varint(&mut buf);
+        assert_eq!(TimeSpan::decode_varint(&buf), Some((span, buf.len())));
+
+        for len in 0..buf.len() {
+            assert_eq!(TimeSpan::decode_varint(&buf[..len]), None);
+        }
+    }
+
+    // Three bytes hold spans below 2^21ns, about 2ms.
+    let mut three = Vec::new();
+    TimeSpan::new((1 << 21) - 1).encode_varint(&mut three);
+    assert_eq!(three.len(), 3);
+    three.clear();
+    TimeSpan::new(1 << 21).encode_varint(&mut three);
+    assert_eq!(three.len(), 4);
+
+    buf.clear();
+    TimeSpan::new(127).encode_varint(&mut buf);
+    assert_eq!(buf.len(), 1);
+    (TimeSpan::MILLISECOND * 16).encode_varint(&mut buf);
+    assert_eq!(buf.len(), 5);
+    TimeSpan::MAX.encode_varint(&mut buf);
+    assert_eq!(buf.len(), 15);
+
+    let (first, read) = TimeSpan::decode_varint(&buf).unwrap();
+    assert_eq!((first, read), (TimeSpan::new(127), 1));
+    let (second, read) = TimeSpan::decode_varint(&buf[1..]).unwrap();
+    assert_eq!((second, read), (TimeSpan::MILLISECOND * 16, 4));
+    assert_eq!(
+        TimeSpan::decode_varint(&buf[5..]),
+        Some((TimeSpan::MAX, 10))
+    );
+
+    // Value doesn't fit into 64 bits.
+    let mut overflow = [0xFF; 10];
+    overflow[9] = 0x02;
+    assert_eq!(TimeSpan::decode_varint(&overflow), None);
+    assert_eq!(TimeSpan::decode_varint(&[0x80; 11]), None);
+
+    // Overlong encodings are rejected.
+    assert_eq!(TimeSpan::decode_varint(&[0x00]), Some((TimeSpan::ZERO, 1)));
+    assert_eq!(TimeSpan::decode_varint(&[0x80, 0x00]), None);
+    assert_eq!(TimeSpan::decode_varint(&[0xFF, 0x80, 0x00]), None);
+    let mut overlong = [0xFF; 10];
+    overlong[8] = 0x80;
+    overlong[9] = 0x00;
+    assert_eq!(TimeSpan::decode_varint(&overlong), None);
+}
+
+#[test]
+fn test_span_align() {
+    let span = TimeSpan::MILLISECOND * 2600;
+
+    assert_eq!(span.align_down(TimeSpan::SECOND), TimeSpan::SECOND * 2);
+    assert_eq!(span.align_up(TimeSpan::SECOND), TimeSpan::SECOND * 3);
+    assert!(!span.is_multiple_of(TimeSpan::SECOND));
+    assert!(span.is_multiple_of(TimeSpan::MILLISECOND * 100));
+
+    let whole = TimeSpan::SECOND * 3;
+    assert_eq!(whole.align_down(TimeSpan::SECOND), whole);
+    assert_eq!(whole.align_up(TimeSpan::SECOND), whole);
+    assert!(whole.is_multiple_of(TimeSpan::SECOND));
+
+    assert_eq!(TimeSpan::ZERO.align_up(TimeSpan::SECOND), TimeSpan::ZERO);
+    assert!(TimeSpan::ZERO.is_multiple_of(TimeSpan::SECOND));
+    assert!(TimeSpan::ZERO.is_multiple_of(TimeSpan::ZERO));
+    assert!(!span.is_multiple_of(TimeSpan::ZERO));
+    assert_eq!(span.align_up(TimeSpan::ZERO), span);
+    assert_eq!(span.align_down(TimeSpan::ZERO), span);
+
+    // Saturates to the largest multiple that fits.
+    let aligned = TimeSpan::MAX.align_up(TimeSpan::SECOND);
+    assert_eq!(aligned, TimeSpan::MAX.align_down(TimeSpan::SECOND));
+    assert!(aligned.is_multiple_of(TimeSpan::SECOND));
+    assert_eq!(TimeSpan::MAX.align_up(TimeSpan::NANOSECOND), TimeSpan::MAX);
+}
+
+#[test]
+fn test_span_from_components() {
+    use TimeSpanUnit::*;
+
+    assert_eq!(TimeSpan::from_components([]), Some(TimeSpan::ZERO));
+    assert_eq!(
+        TimeSpan::from_components([(Day, 1), (Hour, 2), (Minute, 3), (Second, 4)]),
+        Some(TimeSpan::dhms(1, 2, 3, 4))
+    );
+    assert_eq!(
+        TimeSpan::from_components([(Second, 1), (Milli, 500), (Micro, 250), (Nano, 125)]),
+        Some(TimeSpan::new(1_500_250_125))
+    );
+    assert_eq!(
+        TimeSpan::from_components([(Minute, 2), (Second, 3), (Minute, 1)]),
+        Some(TimeSpan::hms(0, 3, 3))
+    );
+    assert_eq!(
+        TimeSpan::from_components([(Hour, 1), (Minute, -15)]),
+        Some(TimeSpan::MINUTE * 45)
+    );
+    assert_eq!(TimeSpan::from_components([(Hour, 1), (Minute, -61)]), None);
+    assert_eq!(TimeSpan::from_components([(Day, i64::MAX)]), None);
+    assert_eq!(
+        TimeSpan::from_components([(Day, i64::MAX), (Day, -i64::MAX), (Nano, 1)]),
+        Some(TimeSpan::NANOSECOND)
+    );
+
+    assert_eq!(Milli.span(), TimeSpan::MILLISECOND);
+}
+
+#[test]
+fn test_span_unit_f64() {
+    let unit = TimeSpan::MINUTE;
+
+    assert_eq!((TimeSpan::SECOND * 90).as_unit_f64(unit), 1.5);
+    assert_eq!(TimeSpan::HOUR.as_unit_f64(unit), 60.0);
+    assert_eq!(TimeSpan::ZERO.as_unit_f64(unit), 0.0);
+    assert_eq!(TimeSpan::SECOND.as_unit_f64(TimeSpan::ZERO), f64::INFINITY);
+
+    assert_eq!(TimeSpan::from_unit_f64(1.5, unit), TimeSpan::SECOND * 90);
+    assert_eq!(TimeSpan::from_unit_f64(0.25, unit), TimeSpan::SECOND * 15);
+    assert_eq!(TimeSpan::from_unit_f64(60.0, unit), TimeSpan::HOUR);
+    assert_eq!(TimeSpan::from_unit_f64(1.0 / 60.0, unit), TimeSpan::SECOND);
+
+    // Rounded to nearest nanosecond.
+    let third = TimeSpan::from_unit_f64(1.0 / 3.0, unit);
+    assert_eq!(third, TimeSpan::SECOND * 20);
+    assert_eq!(
+        TimeSpan::from_unit_f64(0.6, TimeSpan::NANOSECOND),
+        TimeSpan::NANOSECOND
+    );
+    assert_eq!(
+        TimeSpan::from_unit_f64(0.4, TimeSpan::NANOSECOND),
+        TimeSpan::ZERO
+    );
+
+    // Saturation.
+    assert_eq!(TimeSpan::from_unit_f64(1e30, unit), TimeSpan::MAX);
+    assert_eq!(TimeSpan::from_unit_f64(f64::INFINITY, unit), TimeSpan::MAX);
+    assert_eq!(TimeSpan::from_unit_f64(-1.0, unit), TimeSpan::ZERO);
+    assert_eq!(TimeSpan::from_unit_f64(f64::NAN, unit), TimeSpan::ZERO);
+
+    for minutes in [0u64, 1, 7, 1440, 525_600] {
+        let span = TimeSpan::MINUTE * minutes;
+        assert_eq!(TimeSpan::from_unit_f64(span.as_unit_f64(unit), unit), span);
+    }
+}
+
+#[test]
+fn test_span_subsec() {
+    let span = TimeSpan::MILLISECOND * 1500;
+    assert_eq!(span.as_seconds(), 1);
+    assert_eq!(span.subsec_nanos(), 500_000_000);
+    assert_eq!(span.subsec_micros(), 500_000);
+    assert_eq!(span.subsec_millis(), 500);
+
+    let span = TimeSpan::SECOND * 3 + TimeSpan::new(1_234_567);
+    assert_eq!(span.subsec_nanos(), 1_234_567);
+    assert_eq!(span.subsec_micros(), 1_234);
+    assert_eq!(span.subsec_millis(), 1);
+
+    assert_eq!(TimeSpan::SECOND.subsec_nanos(), 0);
+    assert_eq!(TimeSpan::ZERO.subsec_nanos(), 0);
+    assert_eq!(TimeSpan::MAX.subsec_nanos(), 709_551_615);
+
+    for span in [
+        TimeSpan::MILLISECOND * 1500,
+        TimeSpan::MAX,
+        TimeSpan::new(999),
+    ] {
+        let duration = core::time::Duration::new(span.as_seconds(), span.subsec_nanos());
+        assert_eq!(duration.as_nanos(), span.as_nanos() as u128);
+        assert_eq!(duration.subsec_millis(), span.subsec_millis());
+    }
+}
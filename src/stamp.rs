@@ -2,8 +2,11 @@
 //! traits and functions to work with it.
 
 use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
     num::NonZeroU64,
-    ops::{Add, AddAssign, Sub},
+    ops::{Add, AddAssign, Range, Sub, SubAssign},
     time::Duration,
 };
 
@@ -47,11 +50,22 @@ impl TimeStamp {
 
     /// Constructs time stamp from number of nanoseconds elapsed since reference point in time.
     ///
+    /// Unlike [`TimeStamp::from_elapsed`] returns an error describing the failure.
+    /// Fails only for `u64::MAX`.
+    #[inline(always)]
+    pub fn try_from_elapsed(nanos: u64) -> Result<Self, TimeStampOutOfRange> {
+        TimeStamp::from_elapsed(nanos).ok_or(TimeStampOutOfRange)
+    }
+
+    /// Constructs time stamp from raw representation.
+    /// Raw value is number of nanoseconds elapsed since reference point in time plus one.
+    ///
     /// # Safety
     ///
     /// `nanos` must not be 0.
     #[inline(always)]
     pub unsafe fn new_unchecked(nanos: u64) -> Self {
+        debug_assert_ne!(nanos, 0, "raw time stamp value must not be zero");
         TimeStamp {
             nanos: unsafe { NonZeroU64::new_unchecked(nanos) },
         }
@@ -125,6 +139,24 @@ impl TimeStamp {
         self.nanos.get() - 1
     }
 
+    /// Returns raw representation of the time stamp.
+    ///
+    /// Raw value is number of nanoseconds elapsed since reference point in time plus one.
+    /// Use [`TimeStamp::from_raw`] to restore time stamp from it.
+    #[inline(always)]
+    pub const fn as_raw(self) -> NonZeroU64 {
+        self.nanos
+    }
+
+    /// Constructs time stamp from raw representation returned by [`TimeStamp::as_raw`].
+    ///
+    /// Raw value is number of nanoseconds elapsed since reference point in time plus one.
+    #[inline(always)]
+    pub const fn from_raw(raw: NonZeroU64) -> Self {
+        TimeStamp { nanos: raw }
+    }
+
+    #[must_use]
     #[inline(always)]
     pub fn add_span(self, span: TimeSpan) -> Option<TimeStamp> {
         let nanos = self.nanos.get().checked_add(span.as_nanos())?;
@@ -134,6 +166,371 @@ impl TimeStamp {
             nanos: unsafe { NonZeroU64::new_unchecked(nanos) },
         })
     }
+
+    /// Returns time span between this and `other` time stamps regardless of their order.
+    #[must_use]
+    #[inline(always)]
+    pub const fn abs_diff(self, other: TimeStamp) -> TimeSpan {
+        TimeSpan::new(self.nanos.get().abs_diff(other.nanos.get()))
+    }
+
+    /// Returns signed difference `self - other` in nanoseconds,
+    /// positive if `self` is later than `other`.
+    ///
+    /// [`TimeSpan`] is unsigned, so the difference is returned as `i64`,
+    /// saturating at `i64::MIN` and `i64::MAX`.
+    /// It is inverse of [`TimeStamp::offset_nanos`].
+    #[must_use]
+    #[inline(always)]
+    pub const fn signed_diff_nanos(self, other: TimeStamp) -> i64 {
+        let diff = self.nanos.get() as i128 - other.nanos.get() as i128;
+        if diff > i64::MAX as i128 {
+            i64::MAX
+        } else if diff < i64::MIN as i128 {
+            i64::MIN
+        } else {
+            diff as i64
+        }
+    }
+
+    /// Compares time elapsed since `since` with `threshold`,
+    /// e.g. `now.compare_elapsed(start, timeout).is_ge()` checks for timeout.
+    ///
+    /// Never panics. If `since` is later than this time stamp
+    /// elapsed time is negative and is less than any threshold.
+    #[must_use]
+    #[inline(always)]
+    pub const fn compare_elapsed(self, since: TimeStamp, threshold: TimeSpan) -> Ordering {
+        let Some(elapsed) = self.checked_elapsed_since(since) else {
+            return Ordering::Less;
+        };
+
+        let (elapsed, threshold) = (elapsed.as_nanos(), threshold.as_nanos());
+        if elapsed < threshold {
+            Ordering::Less
+        } else if elapsed > threshold {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Returns time stamp shifted by signed number of nanoseconds.
+    /// Returns `None` if result is out of range representable by time stamp.
+    #[must_use]
+    #[inline(always)]
+    pub const fn offset_nanos(self, delta: i64) -> Option<TimeStamp> {
+        match self.nanos.get().checked_add_signed(delta) {
+            None => None,
+            Some(nanos) => match NonZeroU64::new(nanos) {
+                None => None,
+                Some(nanos) => Some(TimeStamp { nanos }),
+            },
+        }
+    }
+
+    /// Returns time stamp shifted by signed number of nanoseconds,
+    /// clamped to [`TimeStamp::start`] and [`TimeStamp::never`].
+    #[must_use]
+    #[inline(always)]
+    pub const fn saturating_offset_nanos(self, delta: i64) -> TimeStamp {
+        match NonZeroU64::new(self.nanos.get().saturating_add_signed(delta)) {
+            None => TimeStamp::start(),
+            Some(nanos) => TimeStamp { nanos },
+        }
+    }
+
+    /// Returns position of this time stamp relative to `from` and `to`
+    /// as fraction, `0` at `from` and `1` at `to`.
+    ///
+    /// Result is not clamped, stamps outside of the range produce values
+    /// below `0` or above `1`. Returns `0` if `from` equals `to`.
+    /// Never overflows.
+    #[inline]
+    pub fn inv_lerp(self, from: TimeStamp, to: TimeStamp) -> f32 {
+        let range = to.nanos.get() as i128 - from.nanos.get() as i128;
+        if range == 0 {
+            return 0.0;
+        }
+
+        let offset = self.nanos.get() as i128 - from.nanos.get() as i128;
+        (offset as f64 / range as f64) as f32
+    }
+
+    /// Returns time stamp halfway between this and `other`, rounded down.
+    /// Never overflows.
+    #[must_use]
+    #[inline(always)]
+    pub const fn midpoint(self, other: TimeStamp) -> TimeStamp {
+        let a = self.nanos.get();
+        let b = other.nanos.get();
+
+        // Both are non-zero, so is the midpoint.
+        let nanos = (a & b) + ((a ^ b) >> 1);
+        TimeStamp {
+            nanos: unsafe { NonZeroU64::new_unchecked(nanos) },
+        }
+    }
+
+    /// Returns latest time stamp not after this one that is
+    /// whole number of `period`s since [`TimeStamp::start`].
+    ///
+    /// Zero `period` returns this time stamp unchanged.
+    #[must_use]
+    #[inline(always)]
+    pub const fn floor_to(self, period: TimeSpan) -> TimeStamp {
+        let period = period.as_nanos();
+        if period == 0 {
+            return self;
+        }
+
+        let elapsed = self.nanos.get() - 1;
+        TimeStamp {
+            nanos: unsafe { NonZeroU64::new_unchecked(elapsed - elapsed % period + 1) },
+        }
+    }
+
+    /// Returns earliest time stamp not before this one that is
+    /// whole number of `period`s since [`TimeStamp::start`].
+    ///
+    /// Saturates at [`TimeStamp::never`].
+    /// Zero `period` returns this time stamp unchanged.
+    #[must_use]
+    #[inline(always)]
+    pub const fn ceil_to(self, period: TimeSpan) -> TimeStamp {
+        let period = period.as_nanos();
+        if period == 0 {
+            return self;
+        }
+
+        let elapsed = (self.nanos.get() - 1) as u128;
+        let ceiled = elapsed.div_ceil(period as u128) * period as u128;
+        if ceiled >= u64::MAX as u128 {
+            return TimeStamp::never();
+        }
+
+        TimeStamp {
+            nanos: unsafe { NonZeroU64::new_unchecked(ceiled as u64 + 1) },
+        }
+    }
+
+    /// Returns time stamp `span` earlier than this one.
+    /// Returns `None` if result would be earlier than [`TimeStamp::start`].
+    #[must_use]
+    #[inline(always)]
+    pub fn sub_span(self, span: TimeSpan) -> Option<TimeStamp> {
+        let nanos = self.nanos.get().checked_sub(span.as_nanos())?;
+        NonZeroU64::new(nanos).map(|nanos| TimeStamp { nanos })
+    }
+
+    /// Appends variable-length encoding of this time stamp to the buffer.
+    ///
+    /// Uses LEB128 of the raw value. Use [`TimeStamp::decode_varint`] to decode.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn encode_varint(self, buf: &mut alloc::vec::Vec<u8>) {
+        crate::encode_varint(self.nanos.get(), buf)
+    }
+
+    /// Decodes time stamp encoded with [`TimeStamp::encode_varint`]
+    /// from the start of the buffer.
+    ///
+    /// Returns decoded time stamp and number of bytes read,
+    /// or `None` if buffer is truncated or doesn't contain valid time stamp.
+    /// Overlong encodings with redundant trailing bytes are not valid.
+    #[inline(always)]
+    pub fn decode_varint(buf: &[u8]) -> Option<(TimeStamp, usize)> {
+        let (raw, len) = crate::decode_varint(buf)?;
+        Some((TimeStamp::from_raw(NonZeroU64::new(raw)?), len))
+    }
+
+    /// Returns time stamp `n` spans after this one,
+    /// or `-n` spans before if `n` is negative.
+    ///
+    /// Computed without intermediate overflow.
+    /// Returns `None` if result doesn't fit into `TimeStamp`.
+    #[inline(always)]
+    pub fn add_scaled_span(self, span: TimeSpan, n: i64) -> Option<TimeStamp> {
+        let nanos = self.nanos.get() as i128 + span.as_nanos() as i128 * n as i128;
+        if nanos < 1 || nanos > u64::MAX as i128 {
+            return None;
+        }
+        Some(TimeStamp {
+            // Safety: checked above.
+            nanos: unsafe { NonZeroU64::new_unchecked(nanos as u64) },
+        })
+    }
+
+    /// Translates this time stamp from time line where `old_epoch` happens
+    /// to time line where the same moment is `new_epoch`,
+    /// preserving offset from the epoch.
+    ///
+    /// Returns `None` if translated time stamp is not representable.
+    #[inline]
+    pub const fn rebase(self, old_epoch: TimeStamp, new_epoch: TimeStamp) -> Option<TimeStamp> {
+        let nanos = self.nanos.get() as i128 - old_epoch.nanos.get() as i128
+            + new_epoch.nanos.get() as i128;
+        if nanos < 1 || nanos > u64::MAX as i128 {
+            return None;
+        }
+        Some(TimeStamp {
+            // Safety: checked above.
+            nanos: unsafe { NonZeroU64::new_unchecked(nanos as u64) },
+        })
+    }
+
+    /// Returns value that displays this time stamp as signed offset from `epoch`,
+    /// e.g. `1:05` after `epoch` or `-5s` before it.
+    ///
+    /// Offset is formatted as `TimeSpan`, including alternate format.
+    #[inline(always)]
+    pub fn display_relative_to(self, epoch: TimeStamp) -> impl fmt::Display {
+        match self.checked_elapsed_since(epoch) {
+            Some(span) => OffsetDisplay {
+                span,
+                negative: false,
+            },
+            None => OffsetDisplay {
+                span: epoch.elapsed_since(self),
+                negative: true,
+            },
+        }
+    }
+
+    /// Returns value that displays this time stamp as UTC wall-clock time of day,
+    /// e.g. `12:34:56.789`, using wall anchor of the global reference point.
+    ///
+    /// Returns `None` if wall anchor is not available.
+    #[cfg(feature = "global_reference")]
+    #[inline(always)]
+    pub fn display_wall(&self) -> Option<impl fmt::Display> {
+        Some(self.display_wall_with(global_reference::wall_anchor()?))
+    }
+
+    /// Returns value that displays this time stamp as UTC wall-clock time of day,
+    /// e.g. `12:34:56.789`, given wall-clock time of the reference point
+    /// as duration since Unix epoch.
+    ///
+    /// Leap seconds are not accounted for, as in Unix time.
+    #[inline(always)]
+    pub fn display_wall_with(&self, anchor: Duration) -> impl fmt::Display {
+        WallDisplay {
+            nanos: anchor.as_nanos() + self.nanos_since_start() as u128,
+        }
+    }
+
+    /// Returns iterator over `count` evenly spaced time stamps
+    /// from `range.start` to `range.end` inclusive.
+    ///
+    /// Each time stamp is rounded to the nearest nanosecond independently,
+    /// so rounding errors do not accumulate and both ends are exact.
+    /// Yields only `range.start` if `count` is 1 and nothing if `count` is 0.
+    /// If `range.end` is before `range.start` time stamps go backwards.
+    pub fn sample(range: Range<TimeStamp>, count: usize) -> impl Iterator<Item = TimeStamp> {
+        let start = range.start.nanos.get() as i128;
+        let delta = range.end.nanos.get() as i128 - start;
+        let intervals = count.saturating_sub(1).max(1) as i128;
+
+        (0..count).map(move |i| {
+            let offset = (2 * delta * i as i128 + intervals).div_euclid(2 * intervals);
+            let nanos = (start + offset) as u64;
+
+            // Safety: value lies between two non-zero values.
+            TimeStamp {
+                nanos: unsafe { NonZeroU64::new_unchecked(nanos) },
+            }
+        })
+    }
+}
+
+/// Displays time elapsed since reference point.
+///
+/// With `global_reference` feature it displays wall-clock time instead
+/// when enabled with [`global_reference::set_display_wall`].
+impl fmt::Display for TimeStamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "global_reference")]
+        if global_reference::display_wall() {
+            return self.fmt_with_anchor(global_reference::wall_anchor(), f);
+        }
+
+        self.fmt_with_anchor(None, f)
+    }
+}
+
+impl TimeStamp {
+    /// Formats wall-clock time if `anchor` is provided
+    /// and time elapsed since reference point otherwise.
+    fn fmt_with_anchor(&self, anchor: Option<Duration>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match anchor {
+            Some(anchor) => fmt::Display::fmt(&self.display_wall_with(anchor), f),
+            None => fmt::Display::fmt(&self.elapsed_since_start(), f),
+        }
+    }
+}
+
+/// Formats time elapsed since reference point as seconds in exponential notation,
+/// see [`TimeSpan`]'s `LowerExp` implementation.
+impl fmt::LowerExp for TimeStamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerExp::fmt(&self.elapsed_since_start(), f)
+    }
+}
+
+/// Formats time elapsed since reference point as seconds in exponential notation,
+/// see [`TimeSpan`]'s `UpperExp` implementation.
+impl fmt::UpperExp for TimeStamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperExp::fmt(&self.elapsed_since_start(), f)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::NoUninit for TimeStamp {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::CheckedBitPattern for TimeStamp {
+    type Bits = u64;
+
+    #[inline(always)]
+    fn is_valid_bit_pattern(bits: &u64) -> bool {
+        *bits != 0
+    }
+}
+
+struct WallDisplay {
+    /// Nanoseconds since Unix epoch.
+    nanos: u128,
+}
+
+impl fmt::Display for WallDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = (self.nanos / 1_000_000_000 % 86_400) as u32;
+        let millis = (self.nanos % 1_000_000_000 / 1_000_000) as u32;
+
+        write!(
+            f,
+            "{:02}:{:02}:{:02}.{:03}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            millis
+        )
+    }
+}
+
+struct OffsetDisplay {
+    span: TimeSpan,
+    negative: bool,
+}
+
+impl fmt::Display for OffsetDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        fmt::Display::fmt(&self.span, f)
+    }
 }
 
 impl Add<TimeSpan> for TimeStamp {
@@ -161,6 +558,29 @@ impl AddAssign<TimeSpan> for TimeStamp {
     }
 }
 
+impl Sub<TimeSpan> for TimeStamp {
+    type Output = TimeStamp;
+
+    #[inline(always)]
+    fn sub(self, rhs: TimeSpan) -> Self {
+        self.sub_span(rhs)
+            .expect("underflow when subtracting time span from time stamp")
+    }
+}
+
+impl SubAssign<TimeSpan> for TimeStamp {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: TimeSpan) {
+        *self = *self - rhs;
+    }
+}
+
+/// Returns time span elapsed since `rhs`.
+///
+/// # Panics
+///
+/// Panics if `rhs` is later than `self`.
+/// Use [`TimeStamp::checked_elapsed_since`] to handle this case.
 impl Sub<TimeStamp> for TimeStamp {
     type Output = TimeSpan;
 
@@ -170,6 +590,119 @@ impl Sub<TimeStamp> for TimeStamp {
     }
 }
 
+/// A fixed point in time relative to the reference point in time
+/// with 128-bit range.
+///
+/// Unlike [`TimeStamp`] it can't overflow in any practical scenario
+/// and is suitable for processes that run for centuries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LongTimeStamp {
+    /// Number of nanoseconds elapsed from reference point in time.
+    nanos: u128,
+}
+
+impl LongTimeStamp {
+    /// Constructs the smallest possible time stamp.
+    #[inline(always)]
+    pub const fn start() -> Self {
+        LongTimeStamp { nanos: 0 }
+    }
+
+    /// Constructs time stamp from number of nanoseconds elapsed since reference point in time.
+    #[inline(always)]
+    pub const fn from_elapsed(nanos: u128) -> Self {
+        LongTimeStamp { nanos }
+    }
+
+    /// Returns number of nanoseconds elapsed since reference point in time.
+    #[inline(always)]
+    pub const fn nanos_since_start(self) -> u128 {
+        self.nanos
+    }
+
+    /// Returns time span elapsed since earlier time stamp.
+    /// Returns `None` if `earlier` is later than `self`
+    /// or elapsed time does not fit into [`TimeSpan`].
+    #[inline(always)]
+    pub fn checked_elapsed_since(self, earlier: LongTimeStamp) -> Option<TimeSpan> {
+        let nanos = self.nanos.checked_sub(earlier.nanos)?;
+        u64::try_from(nanos).ok().map(TimeSpan::new)
+    }
+
+    #[inline(always)]
+    pub fn add_span(self, span: TimeSpan) -> Option<LongTimeStamp> {
+        let nanos = self.nanos.checked_add(span.as_nanos() as u128)?;
+        Some(LongTimeStamp { nanos })
+    }
+
+    /// Converts to [`TimeStamp`] with the same reference point.
+    /// Returns `None` if value is out of range representable by [`TimeStamp`].
+    #[inline(always)]
+    pub fn to_stamp(self) -> Option<TimeStamp> {
+        u64::try_from(self.nanos)
+            .ok()
+            .and_then(TimeStamp::from_elapsed)
+    }
+}
+
+impl From<TimeStamp> for LongTimeStamp {
+    #[inline(always)]
+    fn from(stamp: TimeStamp) -> Self {
+        LongTimeStamp {
+            nanos: stamp.nanos_since_start() as u128,
+        }
+    }
+}
+
+impl Add<TimeSpan> for LongTimeStamp {
+    type Output = LongTimeStamp;
+
+    #[inline(always)]
+    fn add(self, rhs: TimeSpan) -> Self {
+        self.add_span(rhs)
+            .expect("overflow when adding time span to time stamp")
+    }
+}
+
+impl AddAssign<TimeSpan> for LongTimeStamp {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: TimeSpan) {
+        *self = *self + rhs;
+    }
+}
+
+/// Converts number of nanoseconds elapsed since reference point in time into time stamp.
+/// Fails for `u64::MAX` which is not representable.
+impl TryFrom<u64> for TimeStamp {
+    type Error = TimeStampOutOfRange;
+
+    #[inline(always)]
+    fn try_from(nanos: u64) -> Result<Self, TimeStampOutOfRange> {
+        TimeStamp::try_from_elapsed(nanos)
+    }
+}
+
+/// Converts time stamp into number of nanoseconds elapsed since reference point in time.
+impl From<TimeStamp> for u64 {
+    #[inline(always)]
+    fn from(stamp: TimeStamp) -> Self {
+        stamp.nanos_since_start()
+    }
+}
+
+/// Error returned when value is outside of the range representable by [`TimeStamp`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeStampOutOfRange;
+
+impl fmt::Display for TimeStampOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Value is out of range representable by time stamp")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TimeStampOutOfRange {}
+
 #[cold]
 #[inline(always)]
 fn impressive() -> ! {
@@ -180,17 +713,55 @@ fn impressive() -> ! {
 
 #[cfg(feature = "global_reference")]
 pub mod global_reference {
-    use core::mem::MaybeUninit;
-    use std::{sync::Once, time::Instant};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            OnceLock,
+        },
+        time::{Duration, Instant, SystemTime},
+    };
 
-    static GLOBAL_REFERENCE_INIT: Once = Once::new();
-    static mut GLOBAL_REFERENCE: MaybeUninit<Instant> = MaybeUninit::uninit();
+    static GLOBAL_REFERENCE: OnceLock<Instant> = OnceLock::new();
+
+    /// Wall-clock time of the reference point since Unix epoch.
+    static WALL_ANCHOR: OnceLock<Option<Duration>> = OnceLock::new();
+
+    static DISPLAY_WALL: AtomicBool = AtomicBool::new(false);
 
     fn get_or_init(value: Instant) -> Instant {
-        GLOBAL_REFERENCE_INIT.call_once(|| unsafe {
-            GLOBAL_REFERENCE.write(value);
-        });
-        unsafe { *GLOBAL_REFERENCE.assume_init_ref() }
+        *GLOBAL_REFERENCE.get_or_init(|| {
+            WALL_ANCHOR.get_or_init(|| {
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .ok()
+            });
+            value
+        })
+    }
+
+    /// Returns wall-clock time of the reference point as duration since Unix epoch.
+    ///
+    /// Anchor is captured when reference point is initialized.
+    /// Returns `None` if system time is before Unix epoch.
+    #[inline(always)]
+    pub fn wall_anchor() -> Option<Duration> {
+        get();
+        WALL_ANCHOR.get().copied().flatten()
+    }
+
+    /// Makes `Display` implementation of [`TimeStamp`](crate::TimeStamp)
+    /// use wall-clock time when wall anchor is available.
+    /// See [`TimeStamp::display_wall`](crate::TimeStamp::display_wall).
+    #[inline(always)]
+    pub fn set_display_wall(enabled: bool) {
+        DISPLAY_WALL.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if `Display` implementation of [`TimeStamp`](crate::TimeStamp)
+    /// uses wall-clock time.
+    #[inline(always)]
+    pub fn display_wall() -> bool {
+        DISPLAY_WALL.load(Ordering::Relaxed)
     }
 
     #[inline(always)]
@@ -205,3 +776,495 @@ pub mod global_reference {
         (now, reference)
     }
 }
+
+#[test]
+fn test_stamp_int_conversions() {
+    assert_eq!(TimeStamp::try_from(0u64), Ok(TimeStamp::start()));
+    assert_eq!(TimeStamp::try_from(u64::MAX - 1), Ok(TimeStamp::never()));
+    assert_eq!(TimeStamp::try_from(u64::MAX), Err(TimeStampOutOfRange));
+    assert_eq!(u64::from(TimeStamp::start()), 0);
+    assert_eq!(u64::from(TimeStamp::never()), u64::MAX - 1);
+}
+
+#[test]
+fn test_stamp_constructor_bounds() {
+    for nanos in [i64::MAX as u64 - 1, i64::MAX as u64, u64::MAX - 1] {
+        let stamp = TimeStamp::from_elapsed(nanos).unwrap();
+        assert_eq!(stamp.nanos_since_start(), nanos);
+        assert_eq!(TimeStamp::try_from_elapsed(nanos), Ok(stamp));
+        assert_eq!(TimeStamp::try_from(nanos), Ok(stamp));
+        assert_eq!(
+            TimeStamp::from_duration(Duration::from_nanos(nanos)),
+            Some(stamp)
+        );
+        assert_eq!(
+            TimeStamp::from_observed_duration(Duration::from_nanos(nanos)),
+            stamp
+        );
+        assert_eq!(unsafe { TimeStamp::new_unchecked(nanos + 1) }, stamp);
+    }
+
+    assert_eq!(
+        TimeStamp::try_from_elapsed(u64::MAX - 1),
+        Ok(TimeStamp::never())
+    );
+    assert_eq!(TimeStamp::from_elapsed(u64::MAX), None);
+    assert_eq!(
+        TimeStamp::try_from_elapsed(u64::MAX),
+        Err(TimeStampOutOfRange)
+    );
+    assert_eq!(
+        TimeStamp::from_duration(Duration::from_nanos(u64::MAX)),
+        None
+    );
+    assert_eq!(
+        unsafe { TimeStamp::new_unchecked(u64::MAX) },
+        TimeStamp::never()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_stamp_observed_duration_overflow() {
+    let _ = TimeStamp::from_observed_duration(Duration::from_nanos(u64::MAX));
+}
+
+#[test]
+fn test_stamp_sub_span() {
+    let stamp = TimeStamp::start() + TimeSpan::SECOND;
+    assert_eq!(stamp - TimeSpan::SECOND, TimeStamp::start());
+    assert_eq!(stamp.sub_span(TimeSpan::SECOND), Some(TimeStamp::start()));
+    assert_eq!(
+        stamp.sub_span(TimeSpan::SECOND + TimeSpan::NANOSECOND),
+        None
+    );
+    assert_eq!(TimeStamp::never().sub_span(TimeSpan::MAX), None);
+
+    let mut stamp = stamp;
+    stamp -= TimeSpan::MILLISECOND;
+    assert_eq!(stamp.elapsed_since_start(), TimeSpan::MILLISECOND * 999);
+}
+
+#[test]
+#[should_panic(expected = "underflow when subtracting time span from time stamp")]
+fn test_stamp_sub_span_underflow() {
+    let _ = TimeStamp::start() + TimeSpan::SECOND - TimeSpan::SECOND * 2;
+}
+
+#[test]
+#[should_panic(expected = "overflow when calculating time span elapsed since earlier")]
+fn test_stamp_sub_stamp_underflow() {
+    let _ = TimeStamp::start() - (TimeStamp::start() + TimeSpan::NANOSECOND);
+}
+
+#[test]
+fn test_stamp_offset_nanos() {
+    let stamp = TimeStamp::start() + TimeSpan::MICROSECOND;
+    assert_eq!(
+        stamp.offset_nanos(500),
+        Some(stamp + TimeSpan::NANOSECOND * 500)
+    );
+    assert_eq!(stamp.offset_nanos(-1000), Some(TimeStamp::start()));
+    assert_eq!(stamp.offset_nanos(-1001), None);
+    assert_eq!(TimeStamp::never().offset_nanos(1), None);
+    assert_eq!(
+        TimeStamp::never().offset_nanos(i64::MIN),
+        TimeStamp::from_elapsed(u64::MAX - 1 - (1 << 63))
+    );
+
+    assert_eq!(stamp.saturating_offset_nanos(-1001), TimeStamp::start());
+    assert_eq!(stamp.saturating_offset_nanos(i64::MIN), TimeStamp::start());
+    assert_eq!(
+        TimeStamp::never().saturating_offset_nanos(i64::MAX),
+        TimeStamp::never()
+    );
+    assert_eq!(
+        stamp.saturating_offset_nanos(-1),
+        stamp.offset_nanos(-1).unwrap()
+    );
+}
+
+#[test]
+fn test_stamp_floor_ceil() {
+    let start = TimeStamp::start();
+    let stamp = start + TimeSpan::MILLISECOND * 1700;
+
+    assert_eq!(stamp.floor_to(TimeSpan::SECOND), start + TimeSpan::SECOND);
+    assert_eq!(
+        stamp.ceil_to(TimeSpan::SECOND),
+        start + TimeSpan::SECOND * 2
+    );
+    assert_eq!(
+        stamp.floor_to(TimeSpan::MILLISECOND * 500),
+        start + TimeSpan::MILLISECOND * 1500
+    );
+
+    // Aligned stamps are unchanged.
+    let aligned = start + TimeSpan::SECOND * 3;
+    assert_eq!(aligned.floor_to(TimeSpan::SECOND), aligned);
+    assert_eq!(aligned.ceil_to(TimeSpan::SECOND), aligned);
+    assert_eq!(start.floor_to(TimeSpan::SECOND), start);
+    assert_eq!(start.ceil_to(TimeSpan::SECOND), start);
+
+    assert_eq!(stamp.floor_to(TimeSpan::ZERO), stamp);
+    assert_eq!(stamp.ceil_to(TimeSpan::ZERO), stamp);
+
+    let late = TimeStamp::never() - TimeSpan::SECOND;
+    assert_eq!(late.ceil_to(TimeSpan::HOUR), TimeStamp::never());
+    assert_eq!(
+        TimeStamp::never().ceil_to(TimeSpan::NANOSECOND),
+        TimeStamp::never()
+    );
+    assert!(late.floor_to(TimeSpan::HOUR) <= late);
+}
+
+#[test]
+fn test_stamp_signed_diff() {
+    let a = TimeStamp::start() + TimeSpan::MILLISECOND * 10;
+    let b = TimeStamp::start() + TimeSpan::MILLISECOND * 25;
+
+    assert_eq!(b.signed_diff_nanos(a), 15_000_000);
+    assert_eq!(a.signed_diff_nanos(b), -15_000_000);
+    assert_eq!(a.signed_diff_nanos(a), 0);
+    assert_eq!(a.offset_nanos(b.signed_diff_nanos(a)), Some(b));
+
+    assert_eq!(a.abs_diff(b), TimeSpan::MILLISECOND * 15);
+    assert_eq!(b.abs_diff(a), TimeSpan::MILLISECOND * 15);
+    assert_eq!(a.abs_diff(a), TimeSpan::ZERO);
+
+    let start = TimeStamp::start();
+    let never = TimeStamp::never();
+    assert_eq!(never.signed_diff_nanos(start), i64::MAX);
+    assert_eq!(start.signed_diff_nanos(never), i64::MIN);
+    assert_eq!(never.abs_diff(start), TimeSpan::new(u64::MAX - 1));
+}
+
+#[test]
+fn test_stamp_compare_elapsed() {
+    let start = TimeStamp::start() + TimeSpan::SECOND;
+    let timeout = TimeSpan::MILLISECOND * 100;
+
+    let under = start + TimeSpan::MILLISECOND * 99;
+    let at = start + timeout;
+    let over = start + TimeSpan::MILLISECOND * 101;
+    assert_eq!(under.compare_elapsed(start, timeout), Ordering::Less);
+    assert_eq!(at.compare_elapsed(start, timeout), Ordering::Equal);
+    assert_eq!(over.compare_elapsed(start, timeout), Ordering::Greater);
+    assert!(at.compare_elapsed(start, timeout).is_ge());
+
+    // Negative elapsed time is less than any threshold.
+    assert_eq!(
+        TimeStamp::start().compare_elapsed(start, TimeSpan::ZERO),
+        Ordering::Less
+    );
+    assert_eq!(
+        start.compare_elapsed(start, TimeSpan::ZERO),
+        Ordering::Equal
+    );
+    assert_eq!(
+        TimeStamp::never().compare_elapsed(TimeStamp::start(), TimeSpan::MAX),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_stamp_inv_lerp() {
+    let start = TimeStamp::start();
+    let a = start + TimeSpan::SECOND;
+    let b = start + TimeSpan::SECOND * 3;
+
+    assert_eq!(a.inv_lerp(a, b), 0.0);
+    assert_eq!(b.inv_lerp(a, b), 1.0);
+    assert_eq!((start + TimeSpan::MILLISECOND * 1500).inv_lerp(a, b), 0.25);
+    assert_eq!(start.inv_lerp(a, b), -0.5);
+    assert_eq!(b.inv_lerp(b, a), 0.0);
+    assert_eq!(a.inv_lerp(b, a), 1.0);
+    assert_eq!(b.inv_lerp(a, a), 0.0);
+
+    let never = TimeStamp::never();
+    assert_eq!(start.midpoint(never).inv_lerp(start, never), 0.5);
+    assert_eq!(
+        never.inv_lerp(start, start + TimeSpan::NANOSECOND),
+        (u64::MAX - 1) as f32
+    );
+}
+
+#[test]
+fn test_stamp_rebase() {
+    let start = TimeStamp::start();
+    let never = TimeStamp::never();
+    let at = |ms: u64| start + TimeSpan::MILLISECOND * ms;
+
+    // Server epoch at 10s is client epoch at 3s.
+    let server = at(10_000);
+    let client = at(3_000);
+    assert_eq!(at(12_500).rebase(server, client), Some(at(5_500)));
+    assert_eq!(at(5_500).rebase(client, server), Some(at(12_500)));
+    assert_eq!(at(9_000).rebase(server, client), Some(at(2_000)));
+    assert_eq!(at(10_000).rebase(server, client), Some(client));
+    assert_eq!(at(7_000).rebase(server, client), Some(start));
+    assert_eq!(at(6_999).rebase(server, client), None);
+    assert_eq!(never.rebase(client, server), None);
+    assert_eq!(never.rebase(server, server), Some(never));
+    assert_eq!(never.rebase(never, start), Some(start));
+    assert_eq!(start.rebase(never, start), None);
+
+    assert_eq!(
+        at(12_500)
+            .rebase(server, client)
+            .unwrap()
+            .display_relative_to(client)
+            .to_string(),
+        at(12_500).display_relative_to(server).to_string()
+    );
+    assert_eq!(at(9_000).display_relative_to(server).to_string(), "-1s");
+    assert_eq!(
+        format!("{:#}", at(9_000).display_relative_to(server)),
+        "-0d00:00:01.000000000"
+    );
+}
+
+#[test]
+fn test_stamp_exp_fmt() {
+    let stamp = TimeStamp::start() + TimeSpan::MILLISECOND * 1250;
+    assert_eq!(format!("{:e}", stamp), "1.25e0s");
+    assert_eq!(format!("{:.1E}", stamp), "1.2E0s");
+}
+
+#[test]
+fn test_stamp_midpoint() {
+    let start = TimeStamp::start();
+    let never = TimeStamp::never();
+    let mid = start.midpoint(never);
+    assert_eq!(mid, never.midpoint(start));
+    assert_eq!(mid.nanos_since_start(), (u64::MAX - 1) / 2);
+    assert_eq!(never.midpoint(never), never);
+    assert_eq!(start.midpoint(start), start);
+
+    let a = start + TimeSpan::MILLISECOND * 10;
+    let b = start + TimeSpan::MILLISECOND * 30;
+    assert_eq!(a.midpoint(b), start + TimeSpan::MILLISECOND * 20);
+    assert_eq!(a.midpoint(a + TimeSpan::NANOSECOND), a);
+}
+
+#[test]
+fn test_stamp_raw() {
+    assert_eq!(TimeStamp::start().as_raw().get(), 1);
+    assert_eq!(TimeStamp::never().as_raw().get(), u64::MAX);
+
+    for stamp in [
+        TimeStamp::start(),
+        TimeStamp::start() + TimeSpan::SECOND,
+        TimeStamp::never(),
+    ] {
+        assert_eq!(TimeStamp::from_raw(stamp.as_raw()), stamp);
+        assert_eq!(stamp.as_raw().get(), stamp.nanos_since_start() + 1);
+    }
+}
+
+#[test]
+fn test_long_stamp() {
+    let stamp = TimeStamp::start() + TimeSpan::SECOND;
+    let long = LongTimeStamp::from(stamp);
+    assert_eq!(long.nanos_since_start(), 1_000_000_000);
+    assert_eq!(long.to_stamp(), Some(stamp));
+
+    let far = LongTimeStamp::from_elapsed(u64::MAX as u128 * 3);
+    assert_eq!(far.to_stamp(), None);
+    assert_eq!(
+        (far + TimeSpan::DAY).checked_elapsed_since(far),
+        Some(TimeSpan::DAY)
+    );
+    assert_eq!(far.checked_elapsed_since(LongTimeStamp::start()), None);
+    assert_eq!(LongTimeStamp::start().checked_elapsed_since(far), None);
+}
+
+#[test]
+fn test_sample() {
+    let start = TimeStamp::start() + TimeSpan::SECOND;
+    let end = start + TimeSpan::new(10);
+
+    assert_eq!(TimeStamp::sample(start..end, 0).count(), 0);
+    assert_eq!(
+        TimeStamp::sample(start..end, 1).collect::<Vec<_>>(),
+        [start]
+    );
+    assert_eq!(
+        TimeStamp::sample(start..end, 2).collect::<Vec<_>>(),
+        [start, end]
+    );
+
+    let samples: Vec<_> = TimeStamp::sample(start..end, 6).collect();
+    let expected: Vec<_> = (0..6).map(|i| start + TimeSpan::new(i * 2)).collect();
+    assert_eq!(samples, expected);
+
+    let samples: Vec<_> = TimeStamp::sample(start..end, 4)
+        .map(|stamp| stamp.elapsed_since(start).as_nanos())
+        .collect();
+    assert_eq!(samples, [0, 3, 7, 10]);
+
+    let samples: Vec<_> = TimeStamp::sample(end..start, 4)
+        .map(|stamp| stamp.elapsed_since(start).as_nanos())
+        .collect();
+    assert_eq!(samples, [10, 7, 3, 0]);
+
+    let frame = TimeSpan::SECOND / 3;
+    let end = start + frame * 1000;
+    let samples: Vec<_> = TimeStamp::sample(start..end, 1001).collect();
+    assert_eq!(samples.first(), Some(&start));
+    assert_eq!(samples.last(), Some(&end));
+    for (i, pair) in samples.windows(2).enumerate() {
+        assert_eq!(pair[1] - pair[0], frame, "sample {}", i);
+    }
+
+    let samples: Vec<_> = TimeStamp::sample(TimeStamp::start()..TimeStamp::never(), 3).collect();
+    assert_eq!(samples[0], TimeStamp::start());
+    assert_eq!(samples[2], TimeStamp::never());
+}
+
+#[test]
+fn test_add_scaled_span() {
+    let start = TimeStamp::start() + TimeSpan::SECOND;
+    let period = TimeSpan::MILLISECOND * 16;
+
+    assert_eq!(
+        start.add_scaled_span(period, 1000),
+        Some(start + TimeSpan::SECOND * 16)
+    );
+    assert_eq!(
+        start.add_scaled_span(period, -62),
+        Some(TimeStamp::start() + TimeSpan::MILLISECOND * 8)
+    );
+    assert_eq!(start.add_scaled_span(period, -63), None);
+    assert_eq!(start.add_scaled_span(period, 0), Some(start));
+
+    // `period * n` doesn't fit into `i64`, but the result fits.
+    let period = TimeSpan::new(u64::MAX / 2);
+    assert!(i64::try_from(period * 2).is_err());
+    assert_eq!(
+        TimeStamp::never().add_scaled_span(period, -2),
+        Some(TimeStamp::start())
+    );
+    assert_eq!(TimeStamp::never().add_scaled_span(period, 1), None);
+    assert_eq!(TimeStamp::start().add_scaled_span(TimeSpan::MAX, 1), None);
+    assert_eq!(
+        TimeStamp::start().add_scaled_span(TimeSpan::MAX, i64::MIN),
+        None
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_stamp_varint() {
+    let mut buf = Vec::new();
+    for stamp in [
+        TimeStamp::start(),
+        TimeStamp::start() + TimeSpan::new(126),
+        TimeStamp::start() + TimeSpan::new(127),
+        TimeStamp::start() + TimeSpan::HOUR,
+        TimeStamp::never(),
+    ] {
+        buf.clear();
+        stamp.encode_varint(&mut buf);
+        assert_eq!(TimeStamp::decode_varint(&buf), Some((stamp, buf.len())));
+
+        for len in 0..buf.len() {
+            assert_eq!(TimeStamp::decode_varint(&buf[..len]), None);
+        }
+    }
+
+    buf.clear();
+    TimeStamp::start().encode_varint(&mut buf);
+    assert_eq!(buf, [1]);
+
+    // Zero is not a valid raw time stamp.
+    assert_eq!(TimeStamp::decode_varint(&[0]), None);
+    assert_eq!(TimeStamp::decode_varint(&[0x80, 0x00]), None);
+
+    // Overlong encoding of a valid time stamp.
+    assert_eq!(TimeStamp::decode_varint(&[0x81, 0x00]), None);
+}
+
+#[test]
+fn test_display_relative_to_epoch() {
+    let epoch = TimeStamp::start() + TimeSpan::MINUTE;
+
+    assert_eq!(epoch.display_relative_to(epoch).to_string(), "0");
+    assert_eq!(
+        (epoch + TimeSpan::SECOND * 65)
+            .display_relative_to(epoch)
+            .to_string(),
+        "1:05"
+    );
+    assert_eq!(
+        (TimeStamp::start() + TimeSpan::SECOND * 55)
+            .display_relative_to(epoch)
+            .to_string(),
+        "-5s"
+    );
+    assert_eq!(
+        TimeStamp::start().display_relative_to(epoch).to_string(),
+        "-1:00"
+    );
+    assert_eq!(
+        format!("{:#}", TimeStamp::start().display_relative_to(epoch)),
+        "-0d00:01:00.000000000"
+    );
+}
+
+#[test]
+fn test_display_wall() {
+    // 2024-01-01T23:59:58.5Z
+    let anchor = Duration::from_secs(1_704_153_598) + Duration::from_millis(500);
+    let stamp = TimeStamp::start();
+
+    assert_eq!(stamp.display_wall_with(anchor).to_string(), "23:59:58.500");
+    assert_eq!(
+        (stamp + TimeSpan::MILLISECOND * 1499)
+            .display_wall_with(anchor)
+            .to_string(),
+        "23:59:59.999"
+    );
+    assert_eq!(
+        (stamp + TimeSpan::MILLISECOND * 1500)
+            .display_wall_with(anchor)
+            .to_string(),
+        "00:00:00.000"
+    );
+    assert_eq!(
+        (stamp + TimeSpan::DAY * 365 + TimeSpan::HOUR * 12)
+            .display_wall_with(anchor)
+            .to_string(),
+        "11:59:58.500"
+    );
+    assert_eq!(
+        stamp.display_wall_with(Duration::ZERO).to_string(),
+        "00:00:00.000"
+    );
+
+    assert_eq!((stamp + TimeSpan::SECOND).to_string(), "1s");
+}
+
+#[cfg(feature = "global_reference")]
+#[test]
+fn test_display_wall_global() {
+    let stamp = TimeStamp::now();
+    let anchor = global_reference::wall_anchor().unwrap();
+    let wall = stamp.display_wall().unwrap().to_string();
+    assert_eq!(wall, stamp.display_wall_with(anchor).to_string());
+
+    // Global switch is not toggled here,
+    // as other tests check `Display` output concurrently.
+    struct WithAnchor(TimeStamp, Option<Duration>);
+
+    impl fmt::Display for WithAnchor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt_with_anchor(self.1, f)
+        }
+    }
+
+    assert!(!global_reference::display_wall());
+    assert_eq!(WithAnchor(stamp, Some(anchor)).to_string(), wall);
+    assert_eq!(WithAnchor(stamp, None).to_string(), stamp.to_string());
+    assert_eq!(stamp.to_string(), stamp.elapsed_since_start().to_string());
+}
@@ -0,0 +1,772 @@
+//! Contains types to collect statistics over time spans.
+
+use crate::{span::TimeSpan, stamp::TimeStamp};
+
+/// Histogram of time spans with `N` exponential buckets.
+///
+/// Bucket `k` counts spans in range of [`TimeSpan::bucket_bounds`] for histogram's base.
+/// Last bucket also counts all spans that are larger than its upper bound.
+#[derive(Clone, Debug)]
+pub struct TimeSpanHistogram<const N: usize> {
+    base: TimeSpan,
+    counts: [u64; N],
+    total: u64,
+}
+
+impl<const N: usize> TimeSpanHistogram<N> {
+    /// Creates new empty histogram with given base.
+    /// First bucket counts spans up to `base`,
+    /// each next bucket has twice larger upper bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new(base: TimeSpan) -> Self {
+        assert!(N > 0, "histogram must have at least one bucket");
+
+        TimeSpanHistogram {
+            base,
+            counts: [0; N],
+            total: 0,
+        }
+    }
+
+    /// Returns base of the histogram.
+    pub fn base(&self) -> TimeSpan {
+        self.base
+    }
+
+    /// Records time span.
+    pub fn record(&mut self, span: TimeSpan) {
+        let k = (span.log2_bucket(self.base) as usize).min(N - 1);
+        self.counts[k] += 1;
+        self.total += 1;
+    }
+
+    /// Returns number of spans recorded in `k`-th bucket.
+    /// Returns zero if `k` is out of bounds.
+    pub fn count(&self, k: usize) -> u64 {
+        self.counts.get(k).copied().unwrap_or(0)
+    }
+
+    /// Returns total number of recorded spans.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Clears all buckets.
+    pub fn clear(&mut self) {
+        self.counts = [0; N];
+        self.total = 0;
+    }
+
+    /// Returns estimated value of given percentile.
+    ///
+    /// `p` is a fraction in range `0.0..=1.0` and is clamped to it.
+    /// Value is linearly interpolated within the bucket that contains the percentile,
+    /// so estimation error is bounded by bucket width.
+    /// Spans counted in the last bucket above its upper bound are estimated as that bound.
+    ///
+    /// Returns `None` if histogram is empty.
+    pub fn percentile_estimate(&self, p: f64) -> Option<TimeSpan> {
+        if self.total == 0 {
+            return None;
+        }
+
+        let p = if p > 1.0 {
+            1.0
+        } else if p >= 0.0 {
+            p
+        } else {
+            // Negative or NaN.
+            0.0
+        };
+
+        // Rank of the sample, rounded up, in range `1..=total`.
+        let exact = p * self.total as f64;
+        let mut rank = exact as u64;
+        if (rank as f64) < exact {
+            rank += 1;
+        }
+        let rank = rank.clamp(1, self.total);
+
+        let mut before = 0;
+        for (k, &count) in self.counts.iter().enumerate() {
+            if before + count >= rank {
+                let (lower, upper) = TimeSpan::bucket_bounds(self.base, k as u32);
+                let width = (upper - lower).as_nanos() as u128;
+                let offset = width * (rank - before) as u128 / count as u128;
+                return Some(lower + TimeSpan::new(offset as u64));
+            }
+            before += count;
+        }
+
+        unreachable!("rank never exceeds total count")
+    }
+}
+
+/// Collects last `N` frame times for profiling statistics.
+///
+/// Statistics are computed over the window of recorded steps,
+/// oldest steps are replaced once the window is full.
+#[derive(Clone, Debug)]
+pub struct FrameTimer<const N: usize> {
+    steps: [TimeSpan; N],
+    next: usize,
+    len: usize,
+}
+
+impl<const N: usize> Default for FrameTimer<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> FrameTimer<N> {
+    /// Creates new frame timer with empty window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new() -> Self {
+        assert!(N > 0, "frame timer window must not be empty");
+
+        FrameTimer {
+            steps: [TimeSpan::ZERO; N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Records frame time.
+    pub fn record(&mut self, step: TimeSpan) {
+        self.steps[self.next] = step;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Returns number of frame times in the window.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no frame times are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears the window.
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    fn window(&self) -> &[TimeSpan] {
+        &self.steps[..self.len]
+    }
+
+    /// Returns average frame time over the window.
+    /// Returns zero if window is empty.
+    pub fn average(&self) -> TimeSpan {
+        if self.len == 0 {
+            return TimeSpan::ZERO;
+        }
+
+        let sum: u128 = self
+            .window()
+            .iter()
+            .map(|step| step.as_nanos() as u128)
+            .sum();
+        TimeSpan::new((sum / self.len as u128) as u64)
+    }
+
+    /// Returns shortest frame time in the window.
+    /// Returns zero if window is empty.
+    pub fn min_step(&self) -> TimeSpan {
+        self.window()
+            .iter()
+            .copied()
+            .min()
+            .unwrap_or(TimeSpan::ZERO)
+    }
+
+    /// Returns longest frame time in the window.
+    /// Returns zero if window is empty.
+    pub fn max_step(&self) -> TimeSpan {
+        self.window()
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(TimeSpan::ZERO)
+    }
+
+    /// Returns `p`-th percentile of frame times in the window
+    /// using nearest-rank method, e.g. `0.99` for frame time of 1% low FPS.
+    ///
+    /// `p` is a fraction in range `0.0..=1.0` and is clamped to it.
+    /// Returns zero if window is empty.
+    ///
+    /// Each call copies the whole window of `N` spans onto the stack
+    /// and selects the rank in linear time, so with large `N`
+    /// compute percentiles once per frame rather than on every query.
+    pub fn percentile(&self, p: f32) -> TimeSpan {
+        if self.len == 0 {
+            return TimeSpan::ZERO;
+        }
+
+        let p = if p > 1.0 {
+            1.0
+        } else if p >= 0.0 {
+            p
+        } else {
+            // Negative or NaN.
+            0.0
+        };
+
+        // Rank of the sample, rounded up, in range `1..=len`.
+        let exact = p * self.len as f32;
+        let mut rank = exact as usize;
+        if (rank as f32) < exact {
+            rank += 1;
+        }
+        let rank = rank.clamp(1, self.len);
+
+        let mut scratch = self.steps;
+        *scratch[..self.len].select_nth_unstable(rank - 1).1
+    }
+}
+
+/// Uniform random sample of at most `N` time spans out of all pushed ones.
+///
+/// Uses reservoir sampling, so every pushed span has equal chance to be in the sample
+/// regardless of how many spans were pushed.
+/// This allows unbiased percentile estimation over arbitrary long sessions
+/// in fixed memory.
+///
+/// Randomness comes from internal xorshift generator,
+/// reservoirs with the same seed pushed with the same spans have equal samples.
+#[derive(Clone, Debug)]
+pub struct TimeSpanReservoir<const N: usize> {
+    samples: [TimeSpan; N],
+    total: u64,
+    rng: u64,
+}
+
+impl<const N: usize> Default for TimeSpanReservoir<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> TimeSpanReservoir<N> {
+    const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Creates new empty reservoir with default seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new() -> Self {
+        Self::with_seed(Self::DEFAULT_SEED)
+    }
+
+    /// Creates new empty reservoir with given seed of the random generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn with_seed(seed: u64) -> Self {
+        assert!(N > 0, "reservoir capacity must not be zero");
+
+        TimeSpanReservoir {
+            samples: [TimeSpan::ZERO; N],
+            total: 0,
+            // Xorshift state must not be zero.
+            rng: if seed == 0 { Self::DEFAULT_SEED } else { seed },
+        }
+    }
+
+    /// Returns next random number of xorshift64* generator.
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Pushes time span into the reservoir.
+    ///
+    /// Once reservoir is full, span replaces random sample
+    /// with probability `N / len_total()`.
+    pub fn push(&mut self, span: TimeSpan) {
+        let seen = self.total;
+        self.total = self.total.saturating_add(1);
+
+        if seen < N as u64 {
+            self.samples[seen as usize] = span;
+            return;
+        }
+
+        // Uniform index in `0..=seen`.
+        let index = ((self.next_random() as u128 * (seen as u128 + 1)) >> 64) as u64;
+        if index < N as u64 {
+            self.samples[index as usize] = span;
+        }
+    }
+
+    /// Returns number of spans in the sample.
+    pub fn len(&self) -> usize {
+        self.total.min(N as u64) as usize
+    }
+
+    /// Returns `true` if no spans were pushed.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Returns number of spans pushed since creation or last clear.
+    pub fn len_total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns spans in the sample in unspecified order.
+    pub fn samples(&self) -> &[TimeSpan] {
+        &self.samples[..self.len()]
+    }
+
+    /// Removes all spans.
+    /// Random generator is not reset.
+    pub fn clear(&mut self) {
+        self.total = 0;
+    }
+
+    /// Returns estimated `p`-th percentile of all pushed spans
+    /// using nearest-rank method over the sample.
+    ///
+    /// `p` is a fraction in range `0.0..=1.0` and is clamped to it.
+    /// Returns `None` if no spans were pushed.
+    pub fn percentile(&self, p: f64) -> Option<TimeSpan> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+
+        let p = if p > 1.0 {
+            1.0
+        } else if p >= 0.0 {
+            p
+        } else {
+            // Negative or NaN.
+            0.0
+        };
+
+        let mut sorted = self.samples;
+        let sorted = &mut sorted[..len];
+        sorted.sort_unstable();
+
+        // Rank of the sample, rounded up, in range `1..=len`.
+        let exact = p * len as f64;
+        let mut rank = exact as usize;
+        if (rank as f64) < exact {
+            rank += 1;
+        }
+        let rank = rank.clamp(1, len);
+
+        Some(sorted[rank - 1])
+    }
+}
+
+/// Tracks minimum and maximum of time spans pushed within sliding time window,
+/// e.g. the worst frame time over the last second for dynamic resolution scaling.
+///
+/// Samples older than `window` relative to the latest pushed time stamp are evicted.
+/// At most `N` most recent samples are considered,
+/// older ones are evicted even if they are still within the window.
+///
+/// Updates take amortized constant time using monotonic queues.
+#[derive(Clone, Debug)]
+pub struct SlidingWindowExtrema<const N: usize> {
+    window: TimeSpan,
+    latest: Option<TimeStamp>,
+
+    /// Number of pushed samples.
+    pushed: u64,
+
+    /// Samples with decreasing values, front is the maximum.
+    max: MonotonicQueue<N>,
+
+    /// Samples with increasing values, front is the minimum.
+    min: MonotonicQueue<N>,
+}
+
+impl<const N: usize> SlidingWindowExtrema<N> {
+    /// Creates new empty tracker with given window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new(window: TimeSpan) -> Self {
+        assert!(N > 0, "sliding window must hold at least one sample");
+
+        SlidingWindowExtrema {
+            window,
+            latest: None,
+            pushed: 0,
+            max: MonotonicQueue::new(),
+            min: MonotonicQueue::new(),
+        }
+    }
+
+    /// Returns length of the window.
+    pub fn window(&self) -> TimeSpan {
+        self.window
+    }
+
+    /// Pushes `value` sampled at `at` and evicts samples that left the window.
+    ///
+    /// Time stamps are expected to be non-decreasing,
+    /// time stamp earlier than the latest one is treated as the latest one.
+    pub fn push(&mut self, at: TimeStamp, value: TimeSpan) {
+        let at = self.latest.map_or(at, |latest| latest.max(at));
+        self.latest = Some(at);
+
+        let sample = WindowSample {
+            at,
+            value,
+            index: self.pushed,
+        };
+        self.pushed += 1;
+
+        let window = self.window;
+        let pushed = self.pushed;
+        let expired = |sample: &WindowSample| {
+            sample.index + (N as u64) < pushed || at.elapsed_since(sample.at) > window
+        };
+
+        self.max.evict(expired);
+        self.max.push(sample, |back| back.value <= value);
+
+        self.min.evict(expired);
+        self.min.push(sample, |back| back.value >= value);
+    }
+
+    /// Returns maximum of values in the window.
+    /// Returns `None` if window is empty.
+    pub fn max(&self) -> Option<TimeSpan> {
+        self.max.front().map(|sample| sample.value)
+    }
+
+    /// Returns minimum of values in the window.
+    /// Returns `None` if window is empty.
+    pub fn min(&self) -> Option<TimeSpan> {
+        self.min.front().map(|sample| sample.value)
+    }
+
+    /// Removes all samples.
+    pub fn clear(&mut self) {
+        self.latest = None;
+        self.max.clear();
+        self.min.clear();
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct WindowSample {
+    at: TimeStamp,
+    value: TimeSpan,
+    index: u64,
+}
+
+/// Ring buffer of samples used as monotonic queue.
+#[derive(Clone, Debug)]
+struct MonotonicQueue<const N: usize> {
+    samples: [WindowSample; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> MonotonicQueue<N> {
+    fn new() -> Self {
+        MonotonicQueue {
+            samples: [WindowSample {
+                at: TimeStamp::start(),
+                value: TimeSpan::ZERO,
+                index: 0,
+            }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn front(&self) -> Option<&WindowSample> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(&self.samples[self.head])
+    }
+
+    /// Removes samples from the front while they are expired.
+    fn evict(&mut self, expired: impl Fn(&WindowSample) -> bool) {
+        while let Some(front) = self.front() {
+            if !expired(front) {
+                break;
+            }
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+        }
+    }
+
+    /// Removes samples from the back while they are dominated by the new sample
+    /// and pushes it to the back.
+    ///
+    /// Expired samples must be evicted first, so there is room for the new sample.
+    fn push(&mut self, sample: WindowSample, dominated: impl Fn(&WindowSample) -> bool) {
+        while self.len > 0 {
+            let back = (self.head + self.len - 1) % N;
+            if !dominated(&self.samples[back]) {
+                break;
+            }
+            self.len -= 1;
+        }
+
+        debug_assert!(self.len < N, "expired samples must be evicted before push");
+        self.samples[(self.head + self.len) % N] = sample;
+        self.len += 1;
+    }
+
+    fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+#[test]
+fn test_histogram_buckets() {
+    let ms = TimeSpan::MILLISECOND;
+    let mut histogram = TimeSpanHistogram::<8>::new(ms);
+
+    for span in [
+        TimeSpan::ZERO,
+        ms,
+        3 * ms / 2,
+        2 * ms,
+        3 * ms,
+        4 * ms,
+        5 * ms,
+        TimeSpan::SECOND,
+    ] {
+        histogram.record(span);
+    }
+
+    assert_eq!(histogram.total(), 8);
+    assert_eq!(histogram.count(0), 2);
+    assert_eq!(histogram.count(1), 2);
+    assert_eq!(histogram.count(2), 2);
+    assert_eq!(histogram.count(3), 1);
+    assert_eq!(histogram.count(6), 0);
+    // One second is in bucket 10, counted in the last one.
+    assert_eq!(histogram.count(7), 1);
+    assert_eq!(histogram.count(8), 0);
+}
+
+#[test]
+fn test_histogram_percentile() {
+    let ms = TimeSpan::MILLISECOND;
+    let mut histogram = TimeSpanHistogram::<8>::new(ms);
+    assert_eq!(histogram.percentile_estimate(0.5), None);
+
+    for _ in 0..50 {
+        histogram.record(ms);
+        histogram.record(3 * ms);
+    }
+
+    assert_eq!(histogram.percentile_estimate(0.5), Some(ms));
+    assert_eq!(histogram.percentile_estimate(0.25), Some(ms / 2));
+    assert_eq!(histogram.percentile_estimate(0.75), Some(3 * ms));
+    assert_eq!(histogram.percentile_estimate(1.0), Some(4 * ms));
+    assert_eq!(histogram.percentile_estimate(2.0), Some(4 * ms));
+    assert_eq!(histogram.percentile_estimate(0.0), Some(ms / 50));
+    assert_eq!(histogram.percentile_estimate(f64::NAN), Some(ms / 50));
+
+    histogram.clear();
+    assert_eq!(histogram.total(), 0);
+    assert_eq!(histogram.percentile_estimate(0.5), None);
+}
+
+#[test]
+fn test_frame_timer() {
+    let ms = TimeSpan::MILLISECOND;
+    let mut timer = FrameTimer::<100>::new();
+    assert!(timer.is_empty());
+    assert_eq!(timer.percentile(0.99), TimeSpan::ZERO);
+    assert_eq!(timer.max_step(), TimeSpan::ZERO);
+
+    // Frame times 1..=100ms in shuffled order.
+    for i in 0..100u64 {
+        timer.record(ms * (i * 37 % 100 + 1));
+    }
+
+    assert_eq!(timer.len(), 100);
+    assert_eq!(timer.min_step(), ms);
+    assert_eq!(timer.max_step(), ms * 100);
+    assert_eq!(timer.average(), ms * 101 / 2);
+    assert_eq!(timer.percentile(0.99), ms * 99);
+    assert_eq!(timer.percentile(0.5), ms * 50);
+    assert_eq!(timer.percentile(0.0), ms);
+    assert_eq!(timer.percentile(1.0), ms * 100);
+    assert_eq!(timer.percentile(f32::NAN), ms);
+
+    // Window slides over old frame times.
+    for _ in 0..99 {
+        timer.record(ms * 16);
+    }
+    timer.record(ms * 50);
+    assert_eq!(timer.min_step(), ms * 16);
+    assert_eq!(timer.max_step(), ms * 50);
+    assert_eq!(timer.percentile(0.99), ms * 16);
+    assert_eq!(timer.percentile(1.0), ms * 50);
+
+    timer.clear();
+    assert!(timer.is_empty());
+    assert_eq!(timer.average(), TimeSpan::ZERO);
+}
+
+#[test]
+fn test_sliding_window_extrema() {
+    let ms = TimeSpan::MILLISECOND;
+    let start = TimeStamp::start();
+    let mut extrema = SlidingWindowExtrema::<16>::new(ms * 100);
+    assert_eq!(extrema.max(), None);
+    assert_eq!(extrema.min(), None);
+
+    extrema.push(start, ms * 16);
+    extrema.push(start + ms * 16, ms * 40);
+    extrema.push(start + ms * 56, ms * 10);
+    assert_eq!(extrema.max(), Some(ms * 40));
+    assert_eq!(extrema.min(), Some(ms * 10));
+
+    // Spike leaves the window after 100 ms.
+    extrema.push(start + ms * 117, ms * 20);
+    assert_eq!(extrema.max(), Some(ms * 20));
+    assert_eq!(extrema.min(), Some(ms * 10));
+
+    extrema.push(start + ms * 200, ms * 30);
+    assert_eq!(extrema.max(), Some(ms * 30));
+    assert_eq!(extrema.min(), Some(ms * 20));
+
+    extrema.clear();
+    assert_eq!(extrema.max(), None);
+
+    // Capacity limits number of samples.
+    let mut extrema = SlidingWindowExtrema::<2>::new(TimeSpan::SECOND);
+    extrema.push(start, ms * 50);
+    extrema.push(start, ms * 10);
+    extrema.push(start, ms * 20);
+    assert_eq!(extrema.max(), Some(ms * 20));
+    assert_eq!(extrema.min(), Some(ms * 10));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_sliding_window_extrema_prop(
+        window in 0u64..100,
+        pushes in proptest::collection::vec((0u64..30, 0u64..1000), 0..200),
+    ) {
+        const N: usize = 8;
+
+        let window = TimeSpan::new(window);
+        let mut extrema = SlidingWindowExtrema::<N>::new(window);
+        let mut samples = Vec::new();
+        let mut now = TimeStamp::start();
+
+        for (delta, value) in pushes {
+            now += TimeSpan::new(delta);
+            let value = TimeSpan::new(value);
+            extrema.push(now, value);
+            samples.push((now, value));
+
+            let recent = &samples[samples.len().saturating_sub(N)..];
+            let in_window = || {
+                recent
+                    .iter()
+                    .filter(|(at, _)| now.elapsed_since(*at) <= window)
+                    .map(|(_, value)| *value)
+            };
+
+            proptest::prop_assert_eq!(extrema.max(), in_window().max());
+            proptest::prop_assert_eq!(extrema.min(), in_window().min());
+        }
+    }
+}
+
+#[test]
+fn test_reservoir() {
+    let mut reservoir = TimeSpanReservoir::<4>::new();
+    assert!(reservoir.is_empty());
+    assert_eq!(reservoir.percentile(0.5), None);
+
+    let ms = TimeSpan::MILLISECOND;
+    for i in 1..=3 {
+        reservoir.push(ms * i);
+    }
+    assert_eq!(reservoir.len(), 3);
+    assert_eq!(reservoir.samples(), [ms, ms * 2, ms * 3]);
+    assert_eq!(reservoir.percentile(0.5), Some(ms * 2));
+    assert_eq!(reservoir.percentile(2.0), Some(ms * 3));
+    assert_eq!(reservoir.percentile(f64::NAN), Some(ms));
+
+    for i in 4..=100 {
+        reservoir.push(ms * i);
+    }
+    assert_eq!(reservoir.len(), 4);
+    assert_eq!(reservoir.len_total(), 100);
+    assert!(reservoir
+        .samples()
+        .iter()
+        .all(|&s| s >= ms && s <= ms * 100));
+
+    // Same seed, same sample.
+    let mut other = TimeSpanReservoir::<4>::new();
+    for i in 1..=100 {
+        other.push(ms * i);
+    }
+    assert_eq!(reservoir.samples(), other.samples());
+
+    reservoir.clear();
+    assert!(reservoir.is_empty());
+    assert_eq!(reservoir.samples(), []);
+}
+
+#[test]
+fn test_reservoir_percentile_estimate() {
+    const TOTAL: u64 = 1_000_000;
+    let us = TimeSpan::MICROSECOND;
+
+    for seed in [1, 42, 0xDEAD_BEEF] {
+        // Ascending order is the worst case for biased sampling.
+        let mut reservoir = TimeSpanReservoir::<1024>::with_seed(seed);
+        for i in 0..TOTAL {
+            reservoir.push(us * i);
+        }
+        assert_eq!(reservoir.len_total(), TOTAL);
+
+        let check = |p: f64, tolerance: f64| {
+            let estimate = reservoir.percentile(p).unwrap().as_nanos() as f64;
+            let truth = (us * (TOTAL as f64 * p) as u64).as_nanos() as f64;
+            let error = (estimate - truth).abs() / (us * TOTAL).as_nanos() as f64;
+            assert!(
+                error < tolerance,
+                "p{} error {} with seed {}",
+                p,
+                error,
+                seed
+            );
+        };
+        check(0.5, 0.05);
+        check(0.99, 0.01);
+        check(0.1, 0.03);
+    }
+}
@@ -0,0 +1,300 @@
+//! Contains `ClockStep` type produced by clocks and tickers.
+
+use core::{fmt, num::NonZeroU64};
+
+use crate::{span::TimeSpan, stamp::TimeStamp};
+
+/// Result of `Clock` step.
+/// Contains time stamp corresponding to "now"
+/// and time span since previous step.
+///
+/// `Debug` prints compact time spans, e.g. `ClockStep { now: 1.250s, step: 16.700ms }`,
+/// with time stamp printed as time elapsed since start.
+/// Alternate `Debug` prints raw nanoseconds.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct ClockStep {
+    /// TimeStamp corresponding to "now".
+    pub now: TimeStamp,
+    pub step: TimeSpan,
+}
+
+impl ClockStep {
+    /// Returns step that does not advance time.
+    #[inline(always)]
+    pub fn zero_at(now: TimeStamp) -> Self {
+        ClockStep {
+            now,
+            step: TimeSpan::ZERO,
+        }
+    }
+
+    /// Splits step into "now" time stamp and time span since previous step.
+    #[inline(always)]
+    pub fn into_parts(self) -> (TimeStamp, TimeSpan) {
+        (self.now, self.step)
+    }
+
+    /// Returns value that displays step as one-liner for frame logs,
+    /// e.g. `+16.700ms @ 1.250s`.
+    #[inline(always)]
+    pub fn log_fmt(&self) -> impl fmt::Display {
+        StepLog { step: *self }
+    }
+
+    /// Returns time stamp corresponding to "now".
+    #[inline(always)]
+    pub const fn now(&self) -> TimeStamp {
+        self.now
+    }
+
+    /// Returns time span since previous step.
+    #[inline(always)]
+    pub const fn step(&self) -> TimeSpan {
+        self.step
+    }
+
+    /// Returns step scaled by `nom / denom` on the time line tracked by `carry`.
+    ///
+    /// Sub-nanosecond remainder is kept in `carry`, so after any sequence of steps
+    /// scaled by the same ratio the time line advanced by
+    /// `floor(total * nom / denom)` nanoseconds.
+    /// Useful for sub-systems running with their own time multiplier
+    /// without carrying a full `ClockRate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if scaled step doesn't fit into `TimeSpan`
+    /// or time line overflows.
+    pub fn scaled(self, nom: u64, denom: NonZeroU64, carry: &mut StepCarry) -> ClockStep {
+        let scaled = carry.remainder as u128 + self.step.as_nanos() as u128 * nom as u128;
+        let denom = denom.get() as u128;
+
+        let nanos: u64 = (scaled / denom)
+            .try_into()
+            .expect("overflow when scaling clock step");
+        carry.remainder = (scaled % denom) as u64;
+
+        let step = TimeSpan::new(nanos);
+        carry.now += step;
+
+        ClockStep {
+            now: carry.now,
+            step,
+        }
+    }
+
+    /// Returns step scaled by `factor` on the time line tracked by `carry`.
+    ///
+    /// Factor is converted into exact rational rate with [`Rate::from_f32`],
+    /// see [`ClockStep::scaled`].
+    ///
+    /// [`Rate::from_f32`]: crate::Rate::from_f32
+    pub fn scaled_f32(self, factor: f32, carry: &mut StepCarry) -> ClockStep {
+        let rate = crate::rate::Rate::from_f32(factor);
+        self.scaled(rate.nom(), rate.denom(), carry)
+    }
+}
+
+/// Time line of steps scaled with [`ClockStep::scaled`].
+///
+/// Holds time stamp of the scaled time line
+/// and sub-nanosecond remainder of scaling.
+/// Remainder is only meaningful for the ratio it was produced with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepCarry {
+    now: TimeStamp,
+
+    /// Scaled nanoseconds accumulated towards the next nanosecond of the time line.
+    remainder: u64,
+}
+
+impl StepCarry {
+    /// Returns time line starting at `now`.
+    #[inline(always)]
+    pub const fn new(now: TimeStamp) -> Self {
+        StepCarry { now, remainder: 0 }
+    }
+
+    /// Returns time stamp of the scaled time line.
+    #[inline(always)]
+    pub const fn now(&self) -> TimeStamp {
+        self.now
+    }
+
+    /// Returns sub-nanosecond remainder in units of `1 / denom` nanoseconds.
+    #[inline(always)]
+    pub const fn remainder(&self) -> u64 {
+        self.remainder
+    }
+}
+
+impl Default for StepCarry {
+    #[inline(always)]
+    fn default() -> Self {
+        StepCarry::new(TimeStamp::start())
+    }
+}
+
+impl fmt::Debug for ClockStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClockStep")
+            .field("now", &self.now.elapsed_since_start())
+            .field("step", &self.step)
+            .finish()
+    }
+}
+
+struct StepLog {
+    step: ClockStep,
+}
+
+impl fmt::Display for StepLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "+{} @ {}",
+            self.step.step,
+            self.step.now.elapsed_since_start()
+        )
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::NoUninit for ClockStep {}
+
+impl From<ClockStep> for (TimeStamp, TimeSpan) {
+    #[inline(always)]
+    fn from(step: ClockStep) -> Self {
+        step.into_parts()
+    }
+}
+
+impl From<ClockStep> for TimeStamp {
+    #[inline(always)]
+    fn from(step: ClockStep) -> Self {
+        step.now
+    }
+}
+
+impl From<ClockStep> for TimeSpan {
+    #[inline(always)]
+    fn from(step: ClockStep) -> Self {
+        step.step
+    }
+}
+
+#[test]
+fn test_step_parts() {
+    let now = TimeStamp::start() + TimeSpan::SECOND;
+    let step = ClockStep {
+        now,
+        step: TimeSpan::MILLISECOND,
+    };
+
+    assert_eq!(step.into_parts(), (now, TimeSpan::MILLISECOND));
+
+    let (stamp, span) = step.into();
+    assert_eq!((stamp, span), (now, TimeSpan::MILLISECOND));
+
+    assert_eq!(ClockStep::zero_at(now).into_parts(), (now, TimeSpan::ZERO));
+
+    assert_eq!(step.now(), now);
+    assert_eq!(step.step(), TimeSpan::MILLISECOND);
+    assert_eq!(TimeStamp::from(step), now);
+    assert_eq!(TimeSpan::from(step), TimeSpan::MILLISECOND);
+}
+
+#[test]
+fn test_step_scaled() {
+    let third = NonZeroU64::new(3).unwrap();
+    let mut carry = StepCarry::default();
+    let mut real = TimeStamp::start();
+
+    for i in 1..=10_000u64 {
+        real += TimeSpan::MILLISECOND * 16;
+        let step = ClockStep {
+            now: real,
+            step: TimeSpan::MILLISECOND * 16,
+        };
+
+        let scaled = step.scaled(1, third, &mut carry);
+        assert_eq!(scaled.now, carry.now());
+        assert_eq!(
+            carry.now().elapsed_since(TimeStamp::start()),
+            TimeSpan::MILLISECOND * 16 * i / 3
+        );
+    }
+    assert_eq!(
+        carry.now().elapsed_since(TimeStamp::start()) * 3 + TimeSpan::new(carry.remainder()),
+        real.elapsed_since(TimeStamp::start())
+    );
+
+    let mut carry = StepCarry::new(TimeStamp::start() + TimeSpan::SECOND);
+    let step = ClockStep::zero_at(real);
+    assert_eq!(
+        step.scaled(2, NonZeroU64::MIN, &mut carry).step,
+        TimeSpan::ZERO
+    );
+    let step = ClockStep {
+        now: real,
+        step: TimeSpan::MILLISECOND * 16,
+    };
+    let doubled = step.scaled(2, NonZeroU64::MIN, &mut carry);
+    assert_eq!(doubled.step, TimeSpan::MILLISECOND * 32);
+    assert_eq!(
+        doubled.now,
+        TimeStamp::start() + TimeSpan::MILLISECOND * 1032
+    );
+}
+
+#[test]
+fn test_step_scaled_f32() {
+    let mut carry = StepCarry::default();
+    let step = ClockStep {
+        now: TimeStamp::start(),
+        step: TimeSpan::MILLISECOND * 10,
+    };
+
+    let mut total = TimeSpan::ZERO;
+    for _ in 0..300 {
+        total += step.scaled_f32(0.25, &mut carry).step;
+    }
+    assert_eq!(total, TimeSpan::MILLISECOND * 750);
+    assert_eq!(step.scaled_f32(0.0, &mut carry).step, TimeSpan::ZERO);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_step_fmt() {
+    let step = ClockStep {
+        now: TimeStamp::start() + TimeSpan::MILLISECOND * 1250,
+        step: TimeSpan::MICROSECOND * 16700,
+    };
+    assert_eq!(
+        format!("{:?}", step),
+        "ClockStep { now: 1.250s, step: 16.700ms }"
+    );
+    assert_eq!(
+        format!("{:#?}", step),
+        "ClockStep {\n    now: 1250000000ns,\n    step: 16700000ns,\n}"
+    );
+    assert_eq!(step.log_fmt().to_string(), "+16.700ms @ 1.250s");
+
+    let zero = ClockStep::zero_at(TimeStamp::start());
+    assert_eq!(format!("{:?}", zero), "ClockStep { now: 0, step: 0 }");
+    assert_eq!(zero.log_fmt().to_string(), "+0 @ 0");
+
+    let never = ClockStep {
+        now: TimeStamp::never(),
+        step: TimeSpan::MAX,
+    };
+    assert_eq!(
+        format!("{:?}", never),
+        "ClockStep { now: 213503d23:34:33.709, step: 213503d23:34:33.709 }"
+    );
+    assert_eq!(
+        never.log_fmt().to_string(),
+        "+213503d23:34:33.709 @ 213503d23:34:33.709"
+    );
+}
@@ -0,0 +1,92 @@
+//! Contains `Throttle` type that allows action at most once per interval.
+
+use crate::{span::TimeSpan, stamp::TimeStamp};
+
+/// Allows action at most once per minimal interval.
+///
+/// Useful for logging and UI updates that should not run on every frame.
+/// Unlike [`RateLimiter`](crate::RateLimiter) it doesn't accumulate bursts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Throttle {
+    min_interval: TimeSpan,
+    last: Option<TimeStamp>,
+}
+
+impl Throttle {
+    /// Creates new throttle with given minimal interval between allowed actions.
+    /// First action is always allowed.
+    pub const fn new(min_interval: TimeSpan) -> Self {
+        Throttle {
+            min_interval,
+            last: None,
+        }
+    }
+
+    /// Returns minimal interval between allowed actions.
+    pub fn min_interval(&self) -> TimeSpan {
+        self.min_interval
+    }
+
+    /// Returns time stamp of the last allowed action.
+    pub fn last(&self) -> Option<TimeStamp> {
+        self.last
+    }
+
+    /// Returns `true` if action is allowed at `now`
+    /// and remembers `now` as time of the last allowed action.
+    ///
+    /// Time stamps earlier than the last allowed action are not allowed.
+    pub fn allow(&mut self, now: TimeStamp) -> bool {
+        if self.time_until_allowed(now) == TimeSpan::ZERO {
+            self.last = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns time remaining until action is allowed.
+    /// Returns zero if action is allowed at `now`.
+    pub fn time_until_allowed(&self, now: TimeStamp) -> TimeSpan {
+        match self.last {
+            None => TimeSpan::ZERO,
+            Some(last) => match now.checked_elapsed_since(last) {
+                Some(elapsed) => self.min_interval.saturating_sub(elapsed),
+                None => self.min_interval.saturating_add(last - now),
+            },
+        }
+    }
+
+    /// Forgets the last allowed action, so next action is allowed.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+#[test]
+fn test_throttle() {
+    let start = TimeStamp::start();
+    let mut throttle = Throttle::new(TimeSpan::MILLISECOND * 250);
+
+    assert_eq!(throttle.time_until_allowed(start), TimeSpan::ZERO);
+    assert!(throttle.allow(start));
+    assert_eq!(throttle.last(), Some(start));
+
+    for ms in (10..250).step_by(10) {
+        let now = start + TimeSpan::MILLISECOND * ms;
+        assert!(!throttle.allow(now), "{}", ms);
+        assert_eq!(
+            throttle.time_until_allowed(now),
+            TimeSpan::MILLISECOND * (250 - ms)
+        );
+    }
+    assert_eq!(throttle.last(), Some(start));
+
+    let now = start + TimeSpan::MILLISECOND * 250;
+    assert!(throttle.allow(now));
+    assert!(!throttle.allow(now));
+    assert!(throttle.allow(now + TimeSpan::SECOND));
+
+    throttle.reset();
+    assert!(throttle.allow(start));
+}
@@ -0,0 +1,154 @@
+//! Contains `WakeupPlanner` type that finds how long to sleep
+//! until the earliest deadline of time sources.
+
+use crate::{freq::FrequencyTicker, rate::ClockRate, span::TimeSpan, stamp::TimeStamp};
+
+#[cfg(feature = "alloc")]
+use crate::scheduler::CallbackScheduler;
+
+/// Source of deadlines that need attention.
+pub trait NextDeadline {
+    /// Returns time stamp of the next deadline.
+    /// Returns `None` if there is no deadline.
+    fn next_deadline(&self) -> Option<TimeStamp>;
+}
+
+/// Time stamp is a deadline itself.
+impl NextDeadline for TimeStamp {
+    #[inline(always)]
+    fn next_deadline(&self) -> Option<TimeStamp> {
+        Some(*self)
+    }
+}
+
+impl<T> NextDeadline for Option<T>
+where
+    T: NextDeadline,
+{
+    #[inline(always)]
+    fn next_deadline(&self) -> Option<TimeStamp> {
+        self.as_ref()?.next_deadline()
+    }
+}
+
+impl<T> NextDeadline for &T
+where
+    T: NextDeadline + ?Sized,
+{
+    #[inline(always)]
+    fn next_deadline(&self) -> Option<TimeStamp> {
+        (**self).next_deadline()
+    }
+}
+
+impl NextDeadline for FrequencyTicker {
+    #[inline(always)]
+    fn next_deadline(&self) -> Option<TimeStamp> {
+        self.next_tick()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> NextDeadline for CallbackScheduler<T> {
+    #[inline(always)]
+    fn next_deadline(&self) -> Option<TimeStamp> {
+        self.next_at()
+    }
+}
+
+/// Aggregates deadlines from multiple sources
+/// and finds real time to sleep until the earliest one.
+///
+/// Deadlines are in game time of a [`ClockRate`],
+/// so event-driven loops can sleep instead of polling at fixed rate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WakeupPlanner {
+    earliest: Option<TimeStamp>,
+}
+
+impl WakeupPlanner {
+    /// Returns new planner without deadlines.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        WakeupPlanner { earliest: None }
+    }
+
+    /// Adds deadline of the source.
+    pub fn add(&mut self, source: impl NextDeadline) -> &mut Self {
+        if let Some(deadline) = source.next_deadline() {
+            self.earliest = Some(match self.earliest {
+                None => deadline,
+                Some(earliest) => earliest.min(deadline),
+            });
+        }
+        self
+    }
+
+    /// Returns the earliest deadline added so far.
+    #[inline(always)]
+    pub fn earliest(&self) -> Option<TimeStamp> {
+        self.earliest
+    }
+
+    /// Returns real time to sleep until the earliest deadline
+    /// is reached by the `clock`.
+    ///
+    /// Returns `None` if there are no deadlines, clock is paused
+    /// or the span doesn't fit into `TimeSpan`.
+    /// See [`ClockRate::real_time_until`].
+    pub fn real_sleep(&self, clock: &ClockRate) -> Option<TimeSpan> {
+        clock.real_time_until(self.earliest?)
+    }
+
+    /// Removes all deadlines.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.earliest = None;
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_wakeup_planner() {
+    use crate::freq::Frequency;
+
+    let start = TimeStamp::start();
+    let mut clock = ClockRate::new().with_rate(0.5);
+    clock.step(TimeSpan::SECOND);
+    let now = clock.now();
+
+    let mut ticker = FrequencyTicker::new(Frequency::from_hz(4), start);
+    ticker.ticks(TimeSpan::MILLISECOND * 600).for_each(drop);
+
+    let mut scheduler = CallbackScheduler::new(now);
+    scheduler.run_at(now + TimeSpan::MILLISECOND * 300, ());
+
+    let mut planner = WakeupPlanner::new();
+    assert_eq!(planner.real_sleep(&clock), None);
+
+    planner
+        .add(now + TimeSpan::SECOND)
+        .add(&scheduler)
+        .add(None::<TimeStamp>);
+    assert_eq!(planner.earliest(), Some(now + TimeSpan::MILLISECOND * 300));
+
+    // Ticker's next tick at 750 ms is the earliest.
+    planner.add(&ticker);
+    assert_eq!(
+        planner.earliest(),
+        Some(start + TimeSpan::MILLISECOND * 750)
+    );
+    assert_eq!(
+        planner.real_sleep(&clock),
+        Some(TimeSpan::MILLISECOND * 500)
+    );
+
+    // Passed deadline wakes up immediately.
+    planner.add(start);
+    assert_eq!(planner.real_sleep(&clock), Some(TimeSpan::ZERO));
+
+    clock.pause();
+    planner.clear();
+    planner.add(now + TimeSpan::SECOND);
+    assert_eq!(planner.real_sleep(&clock), None);
+}
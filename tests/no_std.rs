@@ -0,0 +1,67 @@
+//! Checks that core types are usable without `std` and `alloc`.
+//! Run with `cargo test --no-default-features --test no_std`.
+
+#![no_std]
+
+use gametime::{
+    ClockRate, ClockStep, FrameTimer, Frequency, FrequencyTicker, Rate, TimeSpan,
+    TimeSpanReservoir, TimeStamp,
+};
+
+#[test]
+fn test_no_std_ticker() {
+    let mut ticker = FrequencyTicker::new(Frequency::from_hz(10), TimeStamp::start());
+
+    let mut count = 0;
+    let mut last: Option<ClockStep> = None;
+    for step in ticker.ticks(TimeSpan::MILLISECOND * 350) {
+        let step: ClockStep = step;
+        assert_eq!(step.step, TimeSpan::MILLISECOND * 100);
+        last = Some(step);
+        count += 1;
+    }
+
+    assert_eq!(count, 3);
+    assert_eq!(
+        last.map(|step| step.now),
+        Some(TimeStamp::start() + TimeSpan::MILLISECOND * 300)
+    );
+}
+
+#[test]
+fn test_no_std_span_parse_display() {
+    let span: TimeSpan = "1.5s".parse().unwrap();
+    assert_eq!(span, TimeSpan::MILLISECOND * 1500);
+
+    let mut buf = [0; TimeSpan::MAX_DISPLAY_LENGTH];
+    assert_eq!(span.display_to_buffer(&mut buf), "1.500s");
+}
+
+#[test]
+fn test_no_std_clock_rate() {
+    let mut clock = ClockRate::new().with_rate(0.5);
+    assert_eq!(
+        clock.rate_exact(),
+        Rate::new(1, core::num::NonZeroU64::new(2).unwrap())
+    );
+
+    let step = clock.step(TimeSpan::SECOND);
+    assert_eq!(step.step, TimeSpan::MILLISECOND * 500);
+    assert_eq!(
+        clock.now(),
+        TimeStamp::start() + TimeSpan::MILLISECOND * 500
+    );
+}
+
+#[test]
+fn test_no_std_stats() {
+    let mut timer = FrameTimer::<8>::new();
+    let mut reservoir = TimeSpanReservoir::<8>::new();
+    for ms in 1..=4 {
+        timer.record(TimeSpan::MILLISECOND * ms);
+        reservoir.push(TimeSpan::MILLISECOND * ms);
+    }
+
+    assert_eq!(timer.percentile(1.0), TimeSpan::MILLISECOND * 4);
+    assert_eq!(reservoir.percentile(0.0), Some(TimeSpan::MILLISECOND));
+}